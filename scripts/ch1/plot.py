@@ -3,20 +3,20 @@ import matplotlib.pyplot as plt
 import os
 
 
-dir = "data/ch1"
+dir = os.environ.get("MAT627_DATA_DIR", "data/ch1")
 
-out_file = "plots/ch1/plot.png"
+out_file = os.path.join(os.environ.get("MAT627_PLOT_DIR", "plots/ch1"), "plot.png")
 
 
 alternating_dir = f"{dir}/Alternating"
 reciprocal_dir = f"{dir}/Reciprocal"
 fig, axs = plt.subplots(5, 3, figsize=(10, 10))
 
-for i, (a_fname, r_fname) in enumerate(
-    zip(os.listdir(alternating_dir), os.listdir(reciprocal_dir))
-):
-    a_data = np.load(f"{alternating_dir}/{a_fname}")
-    r_data = np.load(f"{reciprocal_dir}/{r_fname}")
+xs = sorted(int(fname.split(".")[0]) for fname in os.listdir(alternating_dir))
+
+for i, x_value in enumerate(xs):
+    a_data = np.load(f"{alternating_dir}/{x_value}.npy")
+    r_data = np.load(f"{reciprocal_dir}/{x_value}.npy")
 
     x = i % 5
     y = i // 5
@@ -24,7 +24,7 @@ for i, (a_fname, r_fname) in enumerate(
     #    axs[x, y].set_yscale("log")
     axs[x, y].plot(a_data, label="Alternating")
     axs[x, y].plot(r_data, label="Reciprocal")
-    axs[x, y].set_title(f"X={a_fname.split('.')[0]}", pad=10)
+    axs[x, y].set_title(f"X={x_value}", pad=10)
 
 handles, labels = axs[0, 0].get_legend_handles_labels()
 axs[3, 2].axis("off")
@@ -2,8 +2,8 @@ import numpy as np
 import matplotlib.pyplot as plt
 import os
 
-data_dir = "data/ch2_1"
-outfile = "plots/ch2_1/plot.png"
+data_dir = os.environ.get("MAT627_DATA_DIR", "data/ch2_1")
+outfile = os.path.join(os.environ.get("MAT627_PLOT_DIR", "plots/ch2_1"), "plot.png")
 colors = ["red", "blue", "black"]
 x = np.linspace(1.92, 2.08, num=100_000)
 for i, fname in enumerate(reversed(os.listdir(data_dir))):
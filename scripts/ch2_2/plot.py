@@ -6,8 +6,8 @@ from collections import defaultdict
 import numpy as np
 import matplotlib.pyplot as plt
 
-DATA_DIR = "data/ch2_2"
-PLOT_DIR = "plots/ch2_2"
+DATA_DIR = os.environ.get("MAT627_DATA_DIR", "data/ch2_2")
+PLOT_DIR = os.environ.get("MAT627_PLOT_DIR", "plots/ch2_2")
 
 
 def pretty_case(case_slug: str) -> str:
@@ -26,6 +26,7 @@ def pretty_method(method_slug: str) -> str:
         "backward": "backward",
         "center": "centered",
         "special": "special",
+        "richardson": "centered + Richardson",
     }
     return mapping.get(method_slug, method_slug.replace("_", " "))
 
@@ -33,13 +34,11 @@ def pretty_method(method_slug: str) -> str:
 def load_data():
     os.makedirs(PLOT_DIR, exist_ok=True)
 
-    data = defaultdict(lambda: defaultdict(dict))
+    data = defaultdict(dict)
     exact = {}
 
     exact_pat = re.compile(r"^(?P<case>.+)__exact\.npy$")
-    arr_pat = re.compile(
-        r"^(?P<case>.+)__(?P<method>.+)__(?P<kind>h|approx|err|abs_err|order)\.npy$"
-    )
+    arr_pat = re.compile(r"^(?P<case>.+)__(?P<method>.+)\.npy$")
 
     for path in glob.glob(os.path.join(DATA_DIR, "*.npy")):
         fname = os.path.basename(path)
@@ -53,10 +52,7 @@ def load_data():
 
         m = arr_pat.match(fname)
         if m:
-            case = m.group("case")
-            method = m.group("method")
-            kind = m.group("kind")
-            data[case][method][kind] = np.load(path)
+            data[m.group("case")][m.group("method")] = np.load(path)
 
     return data, exact
 
@@ -82,7 +78,7 @@ def plot_case(case, methods, exact_val):
     plt.figure(figsize=(8, 5))
     for method, d in sorted(methods.items()):
         h = d["h"]
-        abs_err = d.get("abs_err", np.abs(d["err"]))
+        abs_err = d["abs_err"]
         plt.loglog(h, abs_err, marker="o", label=pretty_method(method))
 
     plt.gca().invert_xaxis()
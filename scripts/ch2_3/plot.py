@@ -3,8 +3,8 @@ import os
 import matplotlib.pyplot as plt
 import numpy as np
 
-DATA_DIR = "data/ch2_3"
-PLOT_DIR = "plots/ch2_3"
+DATA_DIR = os.environ.get("MAT627_DATA_DIR", "data/ch2_3")
+PLOT_DIR = os.environ.get("MAT627_PLOT_DIR", "plots/ch2_3")
 OUT_FILE = os.path.join(PLOT_DIR, "plot.png")
 
 
@@ -27,7 +27,27 @@ def main():
     plt.savefig(OUT_FILE, dpi=150)
     plt.close()
 
-    print(f"Saved plot to {OUT_FILE}")
+    history = np.load(os.path.join(DATA_DIR, "history.npy"))
+    t_step = history["t"]
+    h_step = history["h"]
+    accepted = history["accepted"]
+    t_dense = np.load(os.path.join(DATA_DIR, "dense__t.npy"))
+    err_dense = np.load(os.path.join(DATA_DIR, "dense__err.npy"))
+
+    fig, axes = plt.subplots(2, 1, figsize=(8, 7), sharex=True, constrained_layout=True)
+    axes[0].semilogy(t_step[accepted], h_step[accepted], "o-", label="accepted")
+    axes[0].semilogy(t_step[~accepted], h_step[~accepted], "x", color="red", label="rejected")
+    axes[0].set_ylabel("h")
+    axes[0].set_title("Dormand-Prince step sizes")
+    axes[0].legend()
+    axes[1].semilogy(t_dense, np.abs(err_dense), color="black")
+    axes[1].set_xlabel("t")
+    axes[1].set_ylabel(r"$|y(t) - \tilde{y}(t)|$")
+    axes[1].set_title("Dense output error")
+    fig.savefig(os.path.join(PLOT_DIR, "adaptive_steps.png"), dpi=150)
+    plt.close(fig)
+
+    print(f"Saved plots to {PLOT_DIR}")
 
 
 if __name__ == "__main__":
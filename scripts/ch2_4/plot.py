@@ -3,17 +3,12 @@ import os
 import matplotlib.pyplot as plt
 import numpy as np
 
-DATA_DIR = "data/ch2_4"
-PLOT_DIR = "plots/ch2_4"
+DATA_DIR = os.environ.get("MAT627_DATA_DIR", "data/ch2_4")
+PLOT_DIR = os.environ.get("MAT627_PLOT_DIR", "plots/ch2_4")
 
 
 def load_summary(name: str):
-    return {
-        "n": np.load(os.path.join(DATA_DIR, f"{name}__n.npy")),
-        "h": np.load(os.path.join(DATA_DIR, f"{name}__h.npy")),
-        "err": np.load(os.path.join(DATA_DIR, f"{name}__err.npy")),
-        "rate": np.load(os.path.join(DATA_DIR, f"{name}__rate.npy")),
-    }
+    return np.load(os.path.join(DATA_DIR, f"{name}.npy"))
 
 
 def main():
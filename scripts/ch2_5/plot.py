@@ -3,18 +3,12 @@ import os
 import matplotlib.pyplot as plt
 import numpy as np
 
-DATA_DIR = "data/ch2_5"
-PLOT_DIR = "plots/ch2_5"
+DATA_DIR = os.environ.get("MAT627_DATA_DIR", "data/ch2_5")
+PLOT_DIR = os.environ.get("MAT627_PLOT_DIR", "plots/ch2_5")
 
 
 def load_summary(name: str):
-    return {
-        "n": np.load(os.path.join(DATA_DIR, f"{name}__n.npy")),
-        "h": np.load(os.path.join(DATA_DIR, f"{name}__h.npy")),
-        "approx": np.load(os.path.join(DATA_DIR, f"{name}__approx.npy")),
-        "err": np.load(os.path.join(DATA_DIR, f"{name}__err.npy")),
-        "rate": np.load(os.path.join(DATA_DIR, f"{name}__rate.npy")),
-    }
+    return np.load(os.path.join(DATA_DIR, f"{name}.npy"))
 
 
 def load_plot(name: str):
@@ -86,7 +80,7 @@ def main():
     ref32 = part3["err"][-1] * (part3["h"] / part3["h"][-1]) ** 1.5
 
     plt.loglog(part1["h"], ref2, "--", color="gray", label=r"reference slope $2$")
-    plt.loglog(part2["h"], ref4, "-.", color="tab:green", label=r"reference slope $4$")
+    plt.loglog(part2["h"], ref4, "-.", color="black", label=r"reference slope $4$")
     plt.loglog(part3["h"], ref32, ":", color="black", label=r"reference slope $3/2$")
 
     plt.gca().invert_xaxis()
@@ -98,6 +92,55 @@ def main():
     plt.savefig(os.path.join(PLOT_DIR, "error.png"), dpi=150)
     plt.close()
 
+    plt.figure(figsize=(8, 5))
+    for (name, title), marker in zip(cases, ["o", "s", "d"]):
+        romberg = load_summary(f"romberg__{name}")
+        plt.loglog(romberg["n"], romberg["err"], f"{marker}-", linewidth=2, label=title)
+
+    plt.xlabel("N")
+    plt.ylabel(r"$|R_{k,k} - I(f)|$")
+    plt.title("Romberg diagonal error")
+    plt.legend()
+    plt.tight_layout()
+    plt.savefig(os.path.join(PLOT_DIR, "romberg.png"), dpi=150)
+    plt.close()
+
+    plt.figure(figsize=(8, 5))
+    for (name, title), marker in zip(cases, ["o", "s", "d"]):
+        trap = load_summary(name)
+        gauss = load_summary(f"gauss__{name}")
+        line = plt.loglog(trap["h"], trap["err"], f"{marker}--", label=f"trapezoid, {title}")
+        plt.loglog(
+            gauss["h"],
+            gauss["err"],
+            f"{marker}-",
+            color=line[0].get_color(),
+            linewidth=2,
+            label=f"Gauss-Legendre, {title}",
+        )
+
+    plt.gca().invert_xaxis()
+    plt.xlabel("h")
+    plt.ylabel("absolute error")
+    plt.title("Composite trapezoid versus two-point Gauss-Legendre")
+    plt.legend(fontsize=7)
+    plt.tight_layout()
+    plt.savefig(os.path.join(PLOT_DIR, "gauss.png"), dpi=150)
+    plt.close()
+
+    fig, axes = plt.subplots(1, 2, figsize=(10, 4), constrained_layout=True)
+    for ax, method, title in zip(axes, ["simpson", "kronrod"], ["adaptive Simpson", "G7K15"]):
+        for (name, case_title), marker in zip(cases, ["o", "s", "d"]):
+            intervals = np.load(os.path.join(DATA_DIR, f"intervals__{method}__{name}.npy"))
+            a, b = intervals["a"], intervals["b"]
+            ax.loglog(0.5 * (a + b), b - a, marker, markersize=4, label=case_title)
+        ax.set_xlabel("subinterval midpoint")
+        ax.set_ylabel("subinterval width")
+        ax.set_title(f"{title}: final partition")
+        ax.legend(fontsize=7)
+    fig.savefig(os.path.join(PLOT_DIR, "adaptive.png"), dpi=150)
+    plt.close(fig)
+
     print(f"Saved plots to {PLOT_DIR}")
 
 
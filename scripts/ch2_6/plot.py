@@ -3,16 +3,13 @@ import os
 import matplotlib.pyplot as plt
 import numpy as np
 
-DATA_DIR = "data/ch2_6"
-PLOT_DIR = "plots/ch2_6"
+DATA_DIR = os.environ.get("MAT627_DATA_DIR", "data/ch2_6")
+PLOT_DIR = os.environ.get("MAT627_PLOT_DIR", "plots/ch2_6")
 N_VALUES = [3, 10, 25, 100]
 
 
 def load_summary():
-    return {
-        "n": np.load(os.path.join(DATA_DIR, "summary__n.npy")),
-        "en": np.load(os.path.join(DATA_DIR, "summary__en.npy")),
-    }
+    return np.load(os.path.join(DATA_DIR, "summary.npy"))
 
 
 def load_residual_profile(n: int):
@@ -29,6 +26,10 @@ def load_solution(n: int):
     }
 
 
+def load_refinement(n: int):
+    return np.load(os.path.join(DATA_DIR, f"refinement__n{n}.npy"))
+
+
 def main():
     os.makedirs(PLOT_DIR, exist_ok=True)
 
@@ -75,6 +76,26 @@ def main():
     plt.savefig(os.path.join(PLOT_DIR, "solution_profiles.png"), dpi=150)
     plt.close()
 
+    plt.figure(figsize=(8, 5))
+    for n in N_VALUES:
+        history = load_refinement(n)
+        plt.semilogy(
+            history["iteration"],
+            np.maximum(history["backward"], np.finfo(float).tiny),
+            marker="o",
+            linewidth=1.8,
+            label=rf"$n={n}$",
+        )
+
+    plt.axhline(np.finfo(float).eps, color="gray", linestyle="--", label=r"$\varepsilon_{64}$")
+    plt.xlabel("refinement iteration")
+    plt.ylabel(r"$\|r\|_\infty / (\|T\|_\infty \|x\|_\infty + \|b\|_\infty)$")
+    plt.title("Iterative refinement from single-precision LU factors")
+    plt.legend()
+    plt.tight_layout()
+    plt.savefig(os.path.join(PLOT_DIR, "refinement.png"), dpi=150)
+    plt.close()
+
     print(f"Saved plots to {PLOT_DIR}")
 
 
@@ -0,0 +1,92 @@
+use crate::error::{Mat627Error, Result};
+use crate::linalg::TridiagonalMatrix;
+
+/// Finite-difference discretisations of `-u'' + ... = f` on `(0, 1)` with
+/// homogeneous Dirichlet boundary conditions.
+#[derive(Debug, Clone, Copy)]
+pub enum Scheme {
+    /// `-u'' + u = f`, centered second difference.
+    ReactionDiffusion,
+    /// `-u'' + u' + u = f`, centered second difference and backward first difference.
+    AdvectionReactionBackward,
+}
+
+/// Assemble the tridiagonal system for `scheme` on the uniform mesh `x_i = i / n`.
+///
+/// Rows are scaled by `h^2`. Returns the matrix and load vector for the
+/// `n - 1` interior unknowns. Fails with `InvalidInput` when `n < 2`, which
+/// leaves no interior unknowns.
+pub fn build_system<F: Fn(f64) -> f64>(
+    n: usize,
+    rhs: F,
+    scheme: Scheme,
+) -> Result<(TridiagonalMatrix, Vec<f64>)> {
+    if n < 2 {
+        return Err(Mat627Error::InvalidInput(format!(
+            "boundary value problem needs at least 2 subintervals, got {n}"
+        )));
+    }
+
+    let m = n - 1;
+    let h = 1.0 / n as f64;
+
+    let mut lower = vec![0.0; m];
+    let mut diag = vec![0.0; m];
+    let mut upper = vec![0.0; m];
+    let mut load = vec![0.0; m];
+
+    for i in 0..m {
+        let x = (i + 1) as f64 * h;
+        load[i] = h * h * rhs(x);
+
+        match scheme {
+            Scheme::ReactionDiffusion => {
+                if i > 0 {
+                    lower[i] = -1.0;
+                }
+                diag[i] = 2.0 + h * h;
+                if i + 1 < m {
+                    upper[i] = -1.0;
+                }
+            }
+            Scheme::AdvectionReactionBackward => {
+                if i > 0 {
+                    lower[i] = -(1.0 + h);
+                }
+                diag[i] = 2.0 + h + h * h;
+                if i + 1 < m {
+                    upper[i] = -1.0;
+                }
+            }
+        }
+    }
+
+    let matrix = TridiagonalMatrix::from_padded(lower, diag, upper)?;
+    Ok((matrix, load))
+}
+
+/// Solve the boundary value problem on `n` subintervals.
+///
+/// Returns `(x_i, u_i)` for every mesh point, boundary values included.
+pub fn solve<F: Fn(f64) -> f64>(n: usize, rhs: F, scheme: Scheme) -> Result<Vec<(f64, f64)>> {
+    let h = 1.0 / n as f64;
+    let (matrix, load) = build_system(n, rhs, scheme)?;
+    let interior = matrix.solve(&load)?;
+
+    let mut values = Vec::with_capacity(n + 1);
+    values.push((0.0, 0.0));
+    for (i, ui) in interior.into_iter().enumerate() {
+        values.push(((i + 1) as f64 * h, ui));
+    }
+    values.push((1.0, 0.0));
+    Ok(values)
+}
+
+/// Maximum nodal error of `solution` against `exact`.
+pub fn max_error<F: Fn(f64) -> f64>(solution: &[(f64, f64)], exact: F) -> f64 {
+    solution
+        .iter()
+        .copied()
+        .map(|(x, approx)| (exact(x) - approx).abs())
+        .fold(0.0, f64::max)
+}
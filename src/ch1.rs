@@ -1,94 +1,365 @@
-use crate::util;
-use std::io;
-#[allow(dead_code)]
-fn alternating(x: i32, n: i32) -> f64 {
-    let x = x as f64;
-    let mut term: f64 = 1.0;
-    let mut sum: f64 = 1.0;
-    for i in 1..=n {
-        term *= (-x) / (i as f64);
-        sum += term;
-    }
-
-    sum
+use mat627::config::{Output, Validate, at_least};
+use mat627::error::Result;
+use mat627::float_format::{FloatFormat, RoundingMode};
+use mat627::parallel;
+use mat627::series::acceleration::Transform;
+use mat627::series::{PartialSums, Series, Stop, Stopped};
+use mat627::summation::Summation;
+use mat627::util::{self, Field};
+use serde::Deserialize;
+
+const X_VALUES: [i32; 13] = [-50, -20, -15, -10, -5, -1, 1, 5, 10, 50, 100, 500, 1000];
+const MAX_TERMS: usize = 100;
+
+/// Parameters of the Chapter 1 study, read from `config/ch1.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Arguments `x` of `e^{-x}`.
+    pub x_values: Vec<i32>,
+    /// Series are truncated after `n = 1, ..., max_terms` terms.
+    pub max_terms: usize,
+    /// Rule for the early-stopping table, e.g. `{ rule = "relative_term", tol = 1e-12, max_terms = 100 }`.
+    pub stop: Stop,
+    /// How the terms are added up, e.g. `"kahan"`.
+    pub summation: Summation,
+    /// Strategies compared in the summation table; empty to skip it.
+    pub compare_summations: Vec<Summation>,
+    /// Sequence transformations compared against the raw partial sums;
+    /// empty to skip them.
+    pub accelerations: Vec<Transform>,
+    /// Formats tabulated in Problem 2, e.g. `{ exponent_bits = 4, mantissa_bits = 3 }`.
+    pub float_formats: Vec<FloatFormat>,
+    /// Values whose ULP spacing is reported for every format.
+    pub ulp_at: Vec<f64>,
+    pub output: Output,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            x_values: X_VALUES.to_vec(),
+            max_terms: MAX_TERMS,
+            stop: Stop::Stagnation {
+                max_terms: MAX_TERMS,
+            },
+            summation: Summation::Naive,
+            compare_summations: Summation::ALL.to_vec(),
+            accelerations: Transform::ALL.to_vec(),
+            float_formats: vec![
+                FloatFormat::F16,
+                FloatFormat::BF16,
+                FloatFormat::F32,
+                FloatFormat::F64,
+            ],
+            ulp_at: vec![1.0, 1000.0, 1.0e6],
+            output: Output::default(),
+        }
+    }
 }
-#[allow(dead_code)]
-fn reciprocal(x: i32, n: i32) -> f64 {
-    let x = x as f64;
-    let mut term: f64 = 1.0;
-    let mut sum: f64 = 1.0;
-    for i in 1..=n {
-        term *= x / (i as f64);
-        sum += term;
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        at_least("max_terms", &[self.max_terms], 1)
     }
+}
 
-    1.0 / sum
+/// `e^{-x} = sum (-x)^k / k!`.
+fn alternating(x: f64) -> Series<impl FnMut(usize, f64) -> f64> {
+    Series::new(1.0, move |k, term| term * (-x / k as f64))
 }
 
-#[derive(PartialEq)]
+/// `e^{-x} = 1 / sum x^k / k!`.
+fn reciprocal(x: f64) -> Series<impl FnMut(usize, f64) -> f64> {
+    Series::new(1.0, move |k, term| term * (x / k as f64)).finish(f64::recip)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ApproximationType {
     Alternating,
     Reciprocal,
 }
-pub fn test_p1(approx_type: ApproximationType) {
-    let (name, func) = match approx_type {
-        ApproximationType::Alternating => ("Alternating", alternating as fn(i32, i32) -> f64),
-        ApproximationType::Reciprocal => ("Reciprocal", reciprocal as fn(i32, i32) -> f64),
-    };
+
+impl ApproximationType {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Alternating => "Alternating",
+            Self::Reciprocal => "Reciprocal",
+        }
+    }
+
+    fn sum(self, x: f64, stop: Stop, summation: Summation) -> PartialSums {
+        match self {
+            Self::Alternating => alternating(x).summation(summation).sum(stop),
+            Self::Reciprocal => reciprocal(x).summation(summation).sum(stop),
+        }
+    }
+}
+
+/// Write the relative error after `n = 1, ..., max_terms` terms to
+/// `{name}/{x}.npy` for every `x`, and where `config.stop` ends each series
+/// to `{name}_stop.npy`.
+pub fn test_p1(config: &Config, approx_type: ApproximationType) -> Result<()> {
+    let name = approx_type.name();
     println!(
-        "Testing {} Series Approximation for e^{{-x}} for x∈{{-50..1000}} and n∈{{1..100}}...",
-        name
+        "Testing {} Series Approximation for e^{{-x}} for x∈{:?} and n∈{{1..{}}}...",
+        name, config.x_values, config.max_terms
     );
-    let x_list: [i32; 13] = [-50, -20, -15, -10, -5, -1, 1, 5, 10, 50, 100, 500, 1000];
-    let n_list: [i32; 100] = std::array::from_fn(|i| (i + 1) as i32);
+    let data_dir = config.output.data_dir("ch1");
 
-    let _ = x_list.into_iter().map(|x| {
+    let runs = parallel::map(&config.x_values, |&x| {
         let exact = f64::exp(-x as f64);
-        let data: [f64; 100] = std::array::from_fn(|j| {
-            let n = n_list[j];
-            let approx = func(x, n);
-            util::rel_error(approx, exact)
-        });
-        util::write_data(&data, format!("data/ch1/{}", name), x.to_string());
+        let full = approx_type.sum(
+            x as f64,
+            Stop::Terms {
+                n: config.max_terms,
+            },
+            config.summation,
+        );
+        let stopped = approx_type.sum(x as f64, config.stop, config.summation);
+        (full.rel_errors(exact), stopped, exact)
     });
-}
 
-fn find_smallest_unrepresentable_n() -> u64 {
-    let mut l: u64 = 0;
-    let mut r: u64 = u64::MAX;
+    for (x, (errors, _, _)) in config.x_values.iter().zip(&runs) {
+        util::write_data(&errors[1..], format!("{data_dir}/{name}"), x.to_string())?;
+    }
 
-    while l < r {
-        let mid: u64 = l + (r - l) / 2u64;
-        if (mid as f32) as u64 != mid {
-            r = mid;
-        } else {
-            l = mid + 1;
-        }
+    println!(
+        "{:>6} {:>5} {:>12} {:>12}  stopped by",
+        "x", "n", "approx", "rel err"
+    );
+    for (x, (_, stopped, exact)) in config.x_values.iter().zip(&runs) {
+        println!(
+            "{x:>6} {:>5} {:>12.5e} {:>12.5e}  {}",
+            stopped.terms(),
+            stopped.value(),
+            util::rel_error(stopped.value(), *exact),
+            match stopped.stopped {
+                Stopped::TermLimit => "term limit",
+                Stopped::Tolerance => "tolerance",
+                Stopped::Stagnation => "stagnation",
+            }
+        );
     }
-    l
+    let fields = [
+        Field::Int("x", config.x_values.iter().map(|&x| i64::from(x)).collect()),
+        Field::Int("n", runs.iter().map(|(_, s, _)| s.terms() as i64).collect()),
+        Field::Float("approx", runs.iter().map(|(_, s, _)| s.value()).collect()),
+        Field::Float(
+            "rel_err",
+            runs.iter()
+                .map(|(_, s, exact)| util::rel_error(s.value(), *exact))
+                .collect(),
+        ),
+    ];
+    util::write_table(
+        &fields,
+        data_dir,
+        format!("{name}_stop"),
+        &config.output.export,
+    )
 }
 
-pub fn test_p2() -> io::Result<()> {
-    let n: u64 = find_smallest_unrepresentable_n();
+/// Rerun the full series with each of `config.compare_summations` and write
+/// the relative error after `max_terms` terms to `{name}_summation.npy`.
+///
+/// The `accumulation` column is the part of the `config.summation` error that
+/// exact summation removes, `|S - S_exact| / e^{-x}`; the `exact` column is
+/// what remains from truncation and the rounded terms themselves.
+pub fn compare_summations(config: &Config, approx_type: ApproximationType) -> Result<()> {
+    if config.compare_summations.is_empty() {
+        return Ok(());
+    }
+    let name = approx_type.name();
     println!(
-        "Smallest unrepresentable n as float32: \x1b[32m{}\x1b[0m",
-        n
+        "Relative error of the {name} series after {} terms by summation strategy:",
+        config.max_terms
     );
+    let stop = Stop::Terms {
+        n: config.max_terms,
+    };
 
-    let vals: Vec<String> = (n - 2..n + 4)
-        .map(|x| {
-            if (x as f32) as u64 != x {
-                format!("\x1b[31m{}\x1b[0m", (x as f32))
-            } else {
-                format!("\x1b[32m{}\x1b[0m", (x as f32))
-            }
-        })
+    let rows = parallel::map(&config.x_values, |&x| {
+        let exact = f64::exp(-x as f64);
+        let errors: Vec<f64> = config
+            .compare_summations
+            .iter()
+            .map(|&summation| {
+                util::rel_error(approx_type.sum(x as f64, stop, summation).value(), exact)
+            })
+            .collect();
+        let approx = approx_type.sum(x as f64, stop, config.summation).value();
+        let exactly_summed = approx_type.sum(x as f64, stop, Summation::Exact).value();
+        (errors, (approx - exactly_summed).abs() / exact)
+    });
+
+    let header: Vec<String> = config
+        .compare_summations
+        .iter()
+        .map(|s| format!("{:>12}", s.name()))
         .collect();
+    println!("{:>6} {} {:>12}", "x", header.join(" "), "accumulation");
+    for (x, (errors, accumulation)) in config.x_values.iter().zip(&rows) {
+        let cells: Vec<String> = errors.iter().map(|e| format!("{e:>12.5e}")).collect();
+        println!("{x:>6} {} {accumulation:>12.5e}", cells.join(" "));
+    }
+
+    let mut fields = vec![Field::Int(
+        "x",
+        config.x_values.iter().map(|&x| i64::from(x)).collect(),
+    )];
+    for (i, summation) in config.compare_summations.iter().enumerate() {
+        fields.push(Field::Float(
+            summation.name(),
+            rows.iter().map(|(errors, _)| errors[i]).collect(),
+        ));
+    }
+    fields.push(Field::Float(
+        "accumulation",
+        rows.iter().map(|&(_, accumulation)| accumulation).collect(),
+    ));
+    util::write_table(
+        &fields,
+        config.output.data_dir("ch1"),
+        format!("{name}_summation"),
+        &config.output.export,
+    )
+}
+
+/// Apply each of `config.accelerations` to the approximations after
+/// `n = 0, ..., max_terms` terms and write the raw and accelerated relative
+/// errors for `n >= 1` to `acceleration/{name}/{x}.npy`.
+pub fn compare_accelerations(config: &Config, approx_type: ApproximationType) -> Result<()> {
+    if config.accelerations.is_empty() {
+        return Ok(());
+    }
+    let name = approx_type.name();
+    let out_dir = format!("{}/acceleration/{name}", config.output.data_dir("ch1"));
+    let stop = Stop::Terms {
+        n: config.max_terms,
+    };
+
+    let runs = parallel::map(&config.x_values, |&x| {
+        let exact = f64::exp(-x as f64);
+        let sums = approx_type.sum(x as f64, stop, config.summation);
+        let accelerated: Vec<(Vec<f64>, f64)> = config
+            .accelerations
+            .iter()
+            .map(|transform| {
+                let run = transform.apply(&sums.approx);
+                let errors = run
+                    .estimates
+                    .iter()
+                    .map(|&estimate| util::rel_error(estimate, exact))
+                    .collect();
+                let indicator = run.errors.last().copied().flatten().unwrap_or(f64::NAN);
+                (errors, indicator / exact)
+            })
+            .collect();
+        (sums.rel_errors(exact), accelerated)
+    });
+
+    println!(
+        "Relative error of the {name} series after {} terms, raw and accelerated \
+         (est: relative change from the previous estimate):",
+        config.max_terms
+    );
+    let header: Vec<String> = config
+        .accelerations
+        .iter()
+        .map(|t| format!("{:>11} {:>9}", t.name(), "est"))
+        .collect();
+    println!("{:>6} {:>11} {}", "x", "raw", header.join(" "));
+    for (x, (raw, accelerated)) in config.x_values.iter().zip(&runs) {
+        let cells: Vec<String> = accelerated
+            .iter()
+            .map(|(errors, indicator)| {
+                format!("{:>11.4e} {indicator:>9.2e}", errors[errors.len() - 1])
+            })
+            .collect();
+        println!("{x:>6} {:>11.4e} {}", raw[raw.len() - 1], cells.join(" "));
+    }
 
-    println!("Proof:\n");
-    for v in &vals {
-        print!("\t{v}");
+    for (x, (raw, accelerated)) in config.x_values.iter().zip(&runs) {
+        let mut fields = vec![
+            Field::Int("n", (1..=config.max_terms as i64).collect()),
+            Field::Float("raw", raw[1..].to_vec()),
+        ];
+        for (transform, (errors, _)) in config.accelerations.iter().zip(accelerated) {
+            fields.push(Field::Float(transform.name(), errors[1..].to_vec()));
+        }
+        util::write_table(
+            &fields,
+            out_dir.clone(),
+            x.to_string(),
+            &config.output.export,
+        )?;
+    }
+    Ok(())
+}
+
+/// Print the properties of every format in `config.float_formats`, then
+/// answer Problem 2: the smallest positive integer `f32` cannot hold.
+pub fn test_p2(config: &Config) -> Result<()> {
+    for format in &config.float_formats {
+        print_format(format, &config.ulp_at);
+    }
+
+    let n = FloatFormat::F32
+        .first_unrepresentable_integer()
+        .expect("f32 has more exponent range than precision");
+    println!("Smallest unrepresentable n as float32: {n}");
+    println!("Proof (n -> n as f32, * where it differs):");
+    for k in n - 2..n + 4 {
+        let rounded = FloatFormat::F32.round(k as f64, RoundingMode::NearestEven);
+        debug_assert_eq!(rounded, f64::from(k as f32));
+        let mark = if rounded as u64 == k { "" } else { " *" };
+        println!("\t{k} -> {rounded}{mark}");
     }
-    println!();
     Ok(())
 }
+
+fn print_format(format: &FloatFormat, ulp_at: &[f64]) {
+    println!("\n{format}:");
+    for (property, power, value) in format.properties() {
+        println!("  {property:<32} {power:>18} {value:>14}");
+    }
+    for &x in ulp_at {
+        let ulp = format
+            .ulp(x)
+            .map_or_else(|| String::from("overflow"), |ulp| format!("{ulp:.6e}"));
+        println!("  {:<32} {:>18} {ulp:>14}", format!("ulp at {x:e}"), "");
+    }
+
+    if format.precision() == f64::MANTISSA_DIGITS {
+        println!("  (the rounding probes are f64 values, so f64 holds them exactly)");
+        return;
+    }
+    let eps = format.epsilon();
+    let max = format.max_normal();
+    // One ulp past the largest finite value, clamped to f64::MAX when that
+    // overflows f64 as well (11 exponent bits).
+    let past_max = max
+        + format
+            .ulp(max)
+            .expect("max_normal is finite in its own format");
+    let probes = [
+        ("1/3", 1.0 / 3.0),
+        ("-1/3", -1.0 / 3.0),
+        ("1 + eps/2", 1.0 + eps / 2.0),
+        ("-(1 + eps/2)", -(1.0 + eps / 2.0)),
+        ("max + ulp(max)", past_max.min(f64::MAX)),
+    ];
+    let modes: Vec<String> = RoundingMode::ALL
+        .iter()
+        .map(|mode| format!("{:>14}", mode.name()))
+        .collect();
+    println!("  {:<16} {}", "rounding", modes.join(" "));
+    for (label, x) in probes {
+        let cells: Vec<String> = RoundingMode::ALL
+            .iter()
+            .map(|&mode| format!("{:>14.7e}", format.round(x, mode)))
+            .collect();
+        println!("  {label:<16} {}", cells.join(" "));
+    }
+}
@@ -1,85 +1,188 @@
-use crate::util;
-use std::io;
-fn p_standard(x: f64, coeff: &[f64]) -> f64 {
-    let mut term: f64 = 1.0;
-    coeff.iter().fold(0.0, |acc, &a| {
-        let s = acc + a * term;
-        term *= x;
-        s
-    })
+use mat627::config::{Output, Validate, at_least, interval};
+use mat627::error::Result;
+use mat627::polynomials::{horners, standard_with};
+use mat627::summation::Summation;
+use mat627::{parallel, util};
+use serde::Deserialize;
+
+const INTERVAL: [f64; 2] = [1.92, 2.08];
+const SAMPLE_COUNTS: [usize; 2] = [1000, 100_000];
+const PLOT_SAMPLES: usize = 100_000;
+
+/// Parameters of the Section 2.1 study, read from `config/ch2_1.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The polynomial is `(x - root)^degree`, expanded into monomial coefficients.
+    pub root: f64,
+    pub degree: usize,
+    pub interval: [f64; 2],
+    /// Sample counts for the printed error comparison.
+    pub sample_counts: Vec<usize>,
+    /// Samples written for the plot.
+    pub plot_samples: usize,
+    /// How standard evaluation adds up its terms, e.g. `"exact"`.
+    pub summation: Summation,
+    pub output: Output,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            root: 2.0,
+            degree: 9,
+            interval: INTERVAL,
+            sample_counts: SAMPLE_COUNTS.to_vec(),
+            plot_samples: PLOT_SAMPLES,
+            summation: Summation::Naive,
+            output: Output::default(),
+        }
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        interval("interval", self.interval)?;
+        at_least("sample_counts", &self.sample_counts, 2)?;
+        at_least("plot_samples", &[self.plot_samples], 2)
+    }
 }
-fn p_horners(x: f64, coeff: &[f64]) -> f64 {
-    coeff.iter().rev().fold(0.0, |acc, &a| acc.mul_add(x, a))
+
+impl Config {
+    /// Coefficients of `(x - root)^degree`, lowest degree first.
+    fn coefficients(&self) -> Vec<f64> {
+        let mut coeff = vec![1.0];
+        for _ in 0..self.degree {
+            // Multiply by (x - root).
+            let mut next = vec![0.0; coeff.len() + 1];
+            for (k, c) in coeff.iter().enumerate() {
+                next[k] -= self.root * c;
+                next[k + 1] += c;
+            }
+            coeff = next;
+        }
+        coeff
+    }
+
+    fn exact(&self, x: f64) -> f64 {
+        (x - self.root).powi(self.degree as i32)
+    }
 }
-/// Generate N samples from [a,b] according to D = {a + kh} where h = (b-a)/N
+
+/// Generate n samples from [a,b] according to D = {a + kh} where h = (b-a)/n
 ///
 /// # Arguments
 /// `a` - lower bound (inclusive)
 /// `b` - upper bound (inclusive)
-fn domain<const N: usize>(a: f64, b: f64) -> [f64; N] {
-    let h: f64 = (b - a) / (N as f64);
-    std::array::from_fn(|k| h.mul_add(k as f64, a))
+fn domain(a: f64, b: f64, n: usize) -> Vec<f64> {
+    let h: f64 = (b - a) / (n as f64);
+    (0..n).map(|k| h.mul_add(k as f64, a)).collect()
 }
 
-pub fn compare_methods<const N: usize>(lower: f64, upper: f64) -> [f64; 2] {
-    let coeff = [
-        -512.0, 2304.0, -4608.0, 5376.0, -4032.0, 2016.0, -672.0, 144.0, -18.0, 1.0,
-    ];
+pub fn compare_methods(config: &Config, n: usize) -> [f64; 2] {
+    let [lower, upper] = config.interval;
+    let coeff = config.coefficients();
+    let d = domain(lower, upper, n);
 
-    let d = domain::<N>(lower, upper);
+    let errors = parallel::map(&d, |&x| {
+        let exact = config.exact(x);
+        [
+            (exact - standard_with(x, &coeff, config.summation)).abs(),
+            (exact - horners(x, &coeff)).abs(),
+        ]
+    });
 
-    let standard = d
+    errors
         .iter()
-        .map(|&x| ((x - 2.0).powi(9) - p_standard(x, &coeff)).abs())
-        .fold(0.0_f64, f64::max);
+        .fold([0.0_f64; 2], |[s, h], [es, eh]| [s.max(*es), h.max(*eh)])
+}
 
-    let horners = d
+pub fn plot_methods(config: &Config) -> Result<()> {
+    let [lower, upper] = config.interval;
+    let coeff = config.coefficients();
+    let d = domain(lower, upper, config.plot_samples);
+
+    let exact: Vec<f64> = d.iter().map(|&x| config.exact(x)).collect();
+    let standard: Vec<f64> = d
         .iter()
-        .map(|&x| ((x - 2.0).powi(9) - p_horners(x, &coeff)).abs())
-        .fold(0.0_f64, f64::max);
+        .map(|&x| standard_with(x, &coeff, config.summation))
+        .collect();
+    let horners: Vec<f64> = d.iter().map(|&x| horners(x, &coeff)).collect();
 
-    [standard, horners]
-}
+    let paths = config.output.paths("ch2_1");
+    let data_path = paths.data.clone();
 
-pub fn plot_methods<const N: usize>(lower: f64, upper: f64) -> io::Result<()> {
-    let d = domain::<N>(lower, upper);
+    util::write_data(&exact, data_path.clone(), String::from("exact"))?;
+    util::write_data(&standard, data_path.clone(), String::from("standard"))?;
+    util::write_data(&horners, data_path.clone(), String::from("horners"))?;
 
-    let coeff = vec![
-        -512.0, 2304.0, -4608.0, 5376.0, -4032.0, 2016.0, -672.0, 144.0, -18.0, 1.0,
-    ];
-    let exact: [f64; N] = std::array::from_fn(|i| f64::powi(d[i] - 2.0, 9));
-    let standard: [f64; N] = std::array::from_fn(|i| p_standard(d[i], &coeff));
-    let horners: [f64; N] = std::array::from_fn(|i| p_horners(d[i], &coeff));
+    if config.output.plot {
+        util::plot("ch2_1", &paths)?;
+    }
+    Ok(())
+}
 
-    let data_path = "data/ch2_1";
+pub fn generate(config: &Config) -> Result<()> {
+    let [a, b] = config.interval;
+    println!(
+        "Polynomial p(x) = (x-{})^{} on the interval [{a}, {b}]",
+        config.root, config.degree
+    );
+    for &n in &config.sample_counts {
+        println!("Comparing Standard vs. Horners at N={n}...\n");
+        let [standard, horners] = compare_methods(config, n);
 
-    util::write_data(&exact, data_path.to_string(), String::from("exact"));
-    util::write_data(&standard, data_path.to_string(), String::from("standard"));
-    util::write_data(&horners, data_path.to_string(), String::from("horners"));
+        println!("Standard Evaluation Max Absolute Error: {:.4e}", standard);
+        println!("Horners Evaluation Max Absolute Error:  {:.4e}\n", horners);
+    }
 
-    util::plot("ch2_1")
+    println!(
+        "(Grad) Plotting Exact, Standard, and Horners Evalutations at N={}...",
+        config.plot_samples
+    );
+    plot_methods(config)
 }
 
 #[allow(dead_code)]
-pub fn magnitudes(lower: f64, upper: f64) -> [f64; 3] {
-    let d = domain::<100_000>(lower, upper);
-    let coeff = vec![
-        -512.0, 2304.0, -4608.0, 5376.0, -4032.0, 2016.0, -672.0, 144.0, -18.0, 1.0,
-    ];
-    let exact = d
-        .iter()
-        .map(|&x| (x - 2.0).powi(9).abs())
-        .fold(0.0, f64::max);
+pub fn magnitudes(config: &Config) -> [f64; 3] {
+    let [lower, upper] = config.interval;
+    let coeff = config.coefficients();
+    let d = domain(lower, upper, config.plot_samples);
+    let exact = d.iter().map(|&x| config.exact(x).abs()).fold(0.0, f64::max);
 
     let standard = d
         .iter()
-        .map(|&x| p_standard(x, &coeff).abs())
+        .map(|&x| standard_with(x, &coeff, config.summation).abs())
         .fold(0.0, f64::max);
 
     let horners = d
         .iter()
-        .map(|&x| p_horners(x, &coeff).abs())
+        .map(|&x| horners(x, &coeff).abs())
         .fold(0.0, f64::max);
 
     [exact, standard, horners]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, compare_methods};
+
+    #[test]
+    fn default_coefficients_expand_x_minus_2_to_the_ninth() {
+        let coeff = Config::default().coefficients();
+
+        assert_eq!(
+            coeff,
+            vec![
+                -512.0, 2304.0, -4608.0, 5376.0, -4032.0, 2016.0, -672.0, 144.0, -18.0, 1.0
+            ]
+        );
+    }
+
+    #[test]
+    fn horner_is_more_accurate_near_the_root() {
+        let [standard, horners] = compare_methods(&Config::default(), 1000);
+
+        assert!(horners < standard);
+    }
+}
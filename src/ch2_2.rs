@@ -1,31 +1,53 @@
-use crate::util;
+use mat627::config::{Export, Output, Validate, at_least, positive};
+use mat627::convergence::{Convergence, ConvergenceStudy, Level, Sample};
+use mat627::differentiation::{
+    backward_diff, center_diff, forward_diff, richardson_diff, special_diff,
+};
+use mat627::error::Result;
+use mat627::extrapolation;
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::util::{self, Field};
+use serde::Deserialize;
 use std::collections::{BTreeMap, BTreeSet};
 use std::iter;
 type ScalarFn = fn(f64) -> f64;
-type DiffFn = fn(f64, f64, ScalarFn) -> f64;
+type DiffFn<'a> = dyn Fn(f64, f64, ScalarFn) -> f64 + Sync + 'a;
 
-fn forward_diff(x: f64, h: f64, f: ScalarFn) -> f64 {
-    (f(x + h) - f(x)) / h
-}
+const RICHARDSON_DEPTH: usize = 2;
 
-fn backward_diff(x: f64, h: f64, f: ScalarFn) -> f64 {
-    (f(x) - f(x - h)) / h
+/// Parameters of the Section 2.2 study, read from `config/ch2_2.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Point the derivatives are approximated at.
+    pub x: f64,
+    /// Largest step size; each further step halves it.
+    pub h0: f64,
+    pub steps: usize,
+    /// Richardson levels applied to the centered difference by the
+    /// "richardson" method; level `k` eliminates the `h^{2k}` term.
+    pub richardson_depth: usize,
+    pub output: Output,
 }
 
-fn center_diff(x: f64, h: f64, f: ScalarFn) -> f64 {
-    (f(x + h) - f(x - h)) / (2.0 * h)
-}
-fn special_diff(x: f64, h: f64, f: ScalarFn) -> f64 {
-    2.0 * forward_diff(x, h, f) - forward_diff(x, 2.0 * h, f)
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            x: 1.0,
+            h0: 0.5,
+            steps: 12,
+            richardson_depth: RICHARDSON_DEPTH,
+            output: Output::default(),
+        }
+    }
 }
-fn observed_order(err_prev: f64, err_curr: f64) -> Option<f64> {
-    let a = err_prev.abs();
-    let b = err_curr.abs();
-
-    if a == 0.0 || b == 0.0 {
-        None
-    } else {
-        Some((a / b).ln() / 2.0_f64.ln())
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        positive("h0", &[self.h0])?;
+        at_least("steps", &[self.steps], 1)?;
+        at_least("richardson_depth", &[self.richardson_depth], 1)
     }
 }
 
@@ -37,48 +59,35 @@ struct Case {
 }
 
 #[derive(Clone, Copy)]
-struct Method {
+struct Method<'a> {
     name: &'static str,
-    d: DiffFn,
+    d: &'a DiffFn<'a>,
 }
 
+/// The convergence study of one method on one case; `n` counts the halvings of `h0`.
 #[derive(Debug, Clone)]
-pub struct Row {
+pub struct Comparison {
     pub case: &'static str,
     pub method: &'static str,
-    pub h: f64,
-    pub approx: f64,
-    pub err: f64,
-    pub order: Option<f64>,
+    pub summary: Convergence,
 }
 
-fn eval_method(x: f64, hs: &[f64], case: Case, method: Method) -> Vec<Row> {
+fn eval_method(x: f64, hs: &[f64], case: Case, method: Method<'_>) -> Comparison {
     let exact = (case.df)(x);
+    let summary = ConvergenceStudy::new().run(hs.iter().copied().enumerate(), |_, h| {
+        let approx = (method.d)(x, h, case.f);
+        Sample::approx(approx, exact - approx)
+    });
 
-    hs.iter()
-        .copied()
-        .map(|h| {
-            let approx = (method.d)(x, h, case.f);
-            let err = exact - approx;
-            (h, approx, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (h, approx, err)| {
-            let order = prev_err.and_then(|e_prev| observed_order(e_prev, err));
-            *prev_err = Some(err);
-
-            Some(Row {
-                case: case.name,
-                method: method.name,
-                h,
-                approx,
-                err,
-                order,
-            })
-        })
-        .collect()
+    Comparison {
+        case: case.name,
+        method: method.name,
+        summary,
+    }
 }
 
-pub fn compare(input: f64) -> Vec<Row> {
+pub fn compare(config: &Config) -> Vec<Comparison> {
+    let input = config.x;
     let cases = [
         Case {
             name: "sqrt(x+1)",
@@ -92,38 +101,43 @@ pub fn compare(input: f64) -> Vec<Row> {
         },
     ];
 
+    let orders = extrapolation::arithmetic_orders(2.0, config.richardson_depth);
+    let richardson = |x, h, f| {
+        richardson_diff(x, h, f, center_diff, &orders)
+            .expect("halving h is a valid refinement ratio")
+            .best()
+    };
     let methods = [
         Method {
             name: "forward",
-            d: forward_diff,
+            d: &forward_diff,
         },
         Method {
             name: "backward",
-            d: backward_diff,
+            d: &backward_diff,
         },
         Method {
             name: "center",
-            d: center_diff,
+            d: &center_diff,
         },
         Method {
             name: "special",
-            d: special_diff,
+            d: &special_diff,
+        },
+        Method {
+            name: "richardson",
+            d: &richardson,
         },
     ];
 
-    let hs: Vec<f64> = iter::successors(Some(0.5_f64), |h| Some(h / 2.0))
-        .take(12)
+    let hs: Vec<f64> = iter::successors(Some(config.h0), |h| Some(h / 2.0))
+        .take(config.steps)
         .collect();
 
-    let hs_ref = &hs;
-
     cases
         .into_iter()
-        .flat_map(|case| {
-            methods
-                .into_iter()
-                .flat_map(move |method| eval_method(input, hs_ref, case, method))
-        })
+        .flat_map(|case| methods.into_iter().map(move |method| (case, method)))
+        .map(|(case, method)| eval_method(input, &hs, case, method))
         .collect()
 }
 
@@ -144,42 +158,142 @@ fn slug(s: &str) -> String {
     out.trim_matches('_').to_string()
 }
 
-pub fn write_compare_npy(rows: &[Row]) {
-    let out_dir = "data/ch2_2".to_string();
+pub fn write_compare_npy(
+    out_dir: &str,
+    comparisons: &[Comparison],
+    export: &[Export],
+) -> Result<()> {
+    let mut wrote_exact_for_case: BTreeSet<&'static str> = BTreeSet::new();
+
+    for c in comparisons {
+        let case_slug = slug(c.case);
+        let levels = &c.summary.levels;
 
-    let mut groups: BTreeMap<(&'static str, &'static str), Vec<&Row>> = BTreeMap::new();
-    for r in rows {
-        groups.entry((r.case, r.method)).or_default().push(r);
+        let mut fields = c.summary.fields();
+        fields.push(Field::Float(
+            "abs_err",
+            levels.iter().map(|l| l.err.abs()).collect(),
+        ));
+        util::write_table(
+            &fields,
+            out_dir.to_string(),
+            format!("{}__{}", case_slug, slug(c.method)),
+            export,
+        )?;
+
+        if let Some(first) = levels.first()
+            && wrote_exact_for_case.insert(c.case)
+        {
+            let exact = first.approx.unwrap_or(f64::NAN) + first.err;
+            util::write_data(&[exact], out_dir.to_string(), format!("{case_slug}__exact"))?;
+        }
     }
+    Ok(())
+}
 
-    for rs in groups.values_mut() {
-        rs.sort_by(|a, b| b.h.partial_cmp(&a.h).unwrap());
+fn case_tex(case_slug: &str) -> String {
+    match case_slug {
+        "sqrt_x_1" => String::from(r"$\sqrt{x+1}$"),
+        "exp_x" => String::from("$e^x$"),
+        _ => case_slug.replace('_', r"\_"),
     }
+}
 
-    let mut wrote_exact_for_case: BTreeSet<&'static str> = BTreeSet::new();
+/// `(title, operator)` for each method, the operator as it appears in the headers.
+///
+/// `depth` is the number of Richardson levels of the "richardson" method.
+fn method_tex(method_slug: &str, depth: usize) -> (String, String) {
+    let (title, op) = match method_slug {
+        "forward" => ("Forward difference", "D_h^+"),
+        "backward" => ("Backward difference", "D_h^-"),
+        "center" => ("Centered difference", "D_h"),
+        "special" => (
+            "Richardson-extrapolated forward difference",
+            r"\widetilde D_h^+",
+        ),
+        "richardson" => {
+            let levels = if depth == 1 { "level" } else { "levels" };
+            return (
+                format!("Centered difference with {depth} Richardson {levels}"),
+                format!("R_{{{depth},{depth}}}"),
+            );
+        }
+        _ => ("Difference", "D"),
+    };
+    (title.to_string(), op.to_string())
+}
 
-    for ((case, method), rs) in groups {
-        let case_slug = slug(case);
-        let method_slug = slug(method);
+/// One table per case and method, grouped like the `.npy` output.
+fn write_tables(config: &Config, path: &str, comparisons: &[Comparison]) -> Result<()> {
+    let mut groups: BTreeMap<(String, String), &Convergence> = BTreeMap::new();
+    for c in comparisons {
+        groups.insert((slug(c.case), slug(c.method)), &c.summary);
+    }
 
-        let hs: Vec<f64> = rs.iter().map(|r| r.h).collect();
-        let approx: Vec<f64> = rs.iter().map(|r| r.approx).collect();
-        let err: Vec<f64> = rs.iter().map(|r| r.err).collect();
-        let abs_err: Vec<f64> = rs.iter().map(|r| r.err.abs()).collect();
-        let order: Vec<f64> = rs.iter().map(|r| r.order.unwrap_or(f64::NAN)).collect();
+    let x = config.x;
+    let mut tex = TexFile::new();
+    let mut current_case = None;
+    for ((case_slug, method_slug), summary) in groups {
+        if current_case.as_ref() != Some(&case_slug) {
+            tex.line(format!("\\section{{{}}}", case_tex(&case_slug)))
+                .line("");
+            current_case = Some(case_slug.clone());
+        }
 
-        let base = format!("{}__{}", case_slug, method_slug);
+        let (title, op) = method_tex(&method_slug, config.richardson_depth);
+        let table = Table::new(
+            format!("{title} for {}.", case_tex(&case_slug)),
+            format!("{case_slug}_{method_slug}"),
+        )
+        .compact(3)
+        .fit_width()
+        .column("$h^{-1}$", Format::Reciprocal, |r: &Level| r.h)
+        .optional(format!("${op} f({x})$"), Format::Sci, |r| r.approx)
+        .column(format!("$E_h = f'({x}) - {op} f({x})$"), Format::Sci, |r| {
+            r.err
+        })
+        .rate(
+            r"$\frac{\ln\left|\frac{E_{2h}}{E_h}\right|}{\ln 2}$",
+            Format::Sci,
+            |r| r.rate,
+        );
 
-        util::write_data(&hs, out_dir.clone(), format!("{}__h", base));
-        util::write_data(&approx, out_dir.clone(), format!("{}__approx", base));
-        util::write_data(&err, out_dir.clone(), format!("{}__err", base));
-        util::write_data(&abs_err, out_dir.clone(), format!("{}__abs_err", base));
-        util::write_data(&order, out_dir.clone(), format!("{}__order", base));
+        tex.line(format!("\\subsection{{{title}}}"))
+            .line("")
+            .table(&table, &summary.levels);
+    }
 
-        if !wrote_exact_for_case.contains(case) && !rs.is_empty() {
-            let exact = rs[0].approx + rs[0].err;
-            util::write_data(&[exact], out_dir.clone(), format!("{}__exact", case_slug));
-            wrote_exact_for_case.insert(case);
+    tex.write(path)
+}
+
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_2");
+    let out_dir = paths.data.clone();
+    let comparisons = compare(config);
+    for c in &comparisons {
+        for r in &c.summary.levels {
+            println!(
+                "{:<10} {:<8} h={:<12.5e} approx={:<16.8e} err={:<16.8e} order={:?}",
+                c.case,
+                c.method,
+                r.h,
+                r.approx.unwrap_or(f64::NAN),
+                r.err,
+                r.rate.unwrap_or(0.0)
+            );
         }
     }
+    println!("Plotting Data...");
+    write_compare_npy(&out_dir, &comparisons, &config.output.export)?;
+    if config.output.plot {
+        util::plot("ch2_2", &paths)?;
+    }
+    if config.output.report {
+        write_tables(
+            config,
+            &format!("{}/figures/fd_tables.tex", paths.reports),
+            &comparisons,
+        )?;
+    }
+    Ok(())
 }
@@ -1,90 +1,154 @@
-use crate::util;
-use std::io;
+use mat627::config::{Export, Output, Validate, at_least, divides, positive};
+use mat627::convergence::{Convergence, ConvergenceStudy, Level, Sample};
+use mat627::error::Result;
+use mat627::extrapolation;
+use mat627::ode::{self, AdaptiveSolution, Ivp, StepFn, Tolerance, euler_step, rk4_step};
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::util::{self, Field};
+use serde::Deserialize;
 
 const T0: f64 = 1.0;
 const T1: f64 = 2.0;
 const Y0: f64 = 2.0;
+const ADAPTIVE_TOLS: [f64; 5] = [1.0e-5, 5.0e-6, 2.5e-6, 1.25e-6, 6.25e-7];
+const HISTORY_TOL: f64 = 1.0e-6;
+const RICHARDSON_DEPTH: usize = 2;
+
+const PART1_H: f64 = 0.25;
+const STEP_SIZES: [f64; 5] = [0.5, 0.25, 0.125, 0.0625, 0.03125];
+const EXTRAPOLATION_STEP_SIZES: [f64; 4] = [0.25, 0.125, 0.0625, 0.03125];
+const DENSE_SAMPLES: usize = 400;
+
+type Problem = Ivp<fn(f64, &[f64; 1]) -> [f64; 1], 1>;
+
+/// Parameters of the Section 2.3 study, read from `config/ch2_3.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub t0: f64,
+    pub t1: f64,
+    /// Initial value `y(t0)`.
+    pub y0: f64,
+    /// Step size of the Part 1 Euler table.
+    pub part1_h: f64,
+    /// Step sizes for the Euler and RK4 convergence tables, halving each time.
+    pub step_sizes: Vec<f64>,
+    /// Base step sizes for extrapolated Euler and Richardson, halving each time.
+    pub extrapolation_step_sizes: Vec<f64>,
+    pub richardson_depth: usize,
+    /// Dormand-Prince tolerances (`rtol = atol`), halving each time.
+    pub adaptive_tols: Vec<f64>,
+    /// Tolerance of the run whose step history and dense output are written.
+    pub history_tol: f64,
+    /// Points the exact and dense solutions are sampled at.
+    pub dense_samples: usize,
+    pub output: Output,
+}
 
-type StepFn = fn(f64, f64, f64) -> f64;
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            t0: T0,
+            t1: T1,
+            y0: Y0,
+            part1_h: PART1_H,
+            step_sizes: STEP_SIZES.to_vec(),
+            extrapolation_step_sizes: EXTRAPOLATION_STEP_SIZES.to_vec(),
+            richardson_depth: RICHARDSON_DEPTH,
+            adaptive_tols: ADAPTIVE_TOLS.to_vec(),
+            history_tol: HISTORY_TOL,
+            dense_samples: DENSE_SAMPLES,
+            output: Output::default(),
+        }
+    }
+}
 
-#[derive(Debug, Clone, Copy)]
-pub struct StepRow {
-    pub k: usize,
-    pub t: f64,
-    pub approx: f64,
-    pub exact: f64,
-    pub err: f64,
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        let span = self.t1 - self.t0;
+        if !(span.is_finite() && span > 0.0) {
+            return Err(format!("t1: {} does not exceed t0 = {}", self.t1, self.t0));
+        }
+        divides("part1_h", &[self.part1_h], span)?;
+        divides("step_sizes", &self.step_sizes, span)?;
+        // Extrapolated Euler advances in pairs of steps.
+        let pairs: Vec<f64> = self
+            .extrapolation_step_sizes
+            .iter()
+            .map(|h| 2.0 * h)
+            .collect();
+        divides("extrapolation_step_sizes (doubled)", &pairs, span)?;
+        positive("adaptive_tols", &self.adaptive_tols)?;
+        positive("history_tol", &[self.history_tol])?;
+        at_least("dense_samples", &[self.dense_samples], 2)
+    }
 }
 
+impl Config {
+    /// `dense_samples + 1` equally spaced times on `[t0, t1]`.
+    fn dense_times(&self) -> Vec<f64> {
+        let n = self.dense_samples;
+        (0..=n)
+            .map(|i| self.t0 + (self.t1 - self.t0) * (i as f64) / n as f64)
+            .collect()
+    }
+}
+
+/// Outcome of one Dormand-Prince run of the tolerance sweep.
 #[derive(Debug, Clone, Copy)]
-pub struct SummaryRow {
-    pub h: f64,
+pub struct AdaptiveRow {
+    /// `rtol = atol` of the run.
+    pub tol: f64,
     pub approx: f64,
+    /// Achieved error `y(t1) - y_N`.
     pub err: f64,
+    /// Observed order of `|err|` against `tol` from the previous run; ideally 1.
     pub rate: Option<f64>,
+    pub accepted: usize,
+    pub rejected: usize,
 }
 
-fn rhs(t: f64, y: f64) -> f64 {
-    (2.0 * t).sin() / t.powi(2) - 2.0 * y / t
-}
-
-fn exact(t: f64) -> f64 {
-    (4.0 + 2.0_f64.cos() - (2.0 * t).cos()) / (2.0 * t.powi(2))
+#[derive(Debug, Clone, Copy)]
+pub struct StepRow {
+    pub k: usize,
+    pub t: f64,
+    pub approx: f64,
+    pub exact: f64,
+    pub err: f64,
 }
 
-fn euler_step(t: f64, y: f64, h: f64) -> f64 {
-    y + h * rhs(t, y)
+fn rhs(t: f64, y: &[f64; 1]) -> [f64; 1] {
+    [(2.0 * t).sin() / t.powi(2) - 2.0 * y[0] / t]
 }
 
-fn rk4_step(t: f64, y: f64, h: f64) -> f64 {
-    let k1 = rhs(t, y);
-    let k2 = rhs(t + 0.5 * h, y + 0.5 * h * k1);
-    let k3 = rhs(t + 0.5 * h, y + 0.5 * h * k2);
-    let k4 = rhs(t + h, y + h * k3);
-
-    y + h * (k1 + 2.0 * k2 + 2.0 * k3 + k4) / 6.0
+fn problem(config: &Config) -> Problem {
+    Ivp::new(rhs, config.t0, config.t1, [config.y0])
 }
 
-fn step_count(start: f64, end: f64, h: f64) -> usize {
-    let n = (end - start) / h;
-    let rounded = n.round();
-    assert!((n - rounded).abs() < 1.0e-12);
-    rounded as usize
+/// `y(t) = (C - cos 2t) / 2t^2` with `C` fixed by `y(t0) = y0`.
+fn exact(config: &Config, t: f64) -> f64 {
+    let c = 2.0 * config.t0.powi(2) * config.y0 + (2.0 * config.t0).cos();
+    (c - (2.0 * t).cos()) / (2.0 * t.powi(2))
 }
 
-fn solve(step: StepFn, h: f64) -> Vec<(f64, f64)> {
-    let n = step_count(T0, T1, h);
-    let mut rows = Vec::with_capacity(n + 1);
-    let mut t = T0;
-    let mut y = Y0;
-
-    rows.push((t, y));
-    for k in 0..n {
-        y = step(t, y, h);
-        t = T0 + (k + 1) as f64 * h;
-        rows.push((t, y));
-    }
-
-    rows
+fn solve(config: &Config, step: StepFn<Problem, 1>, h: f64) -> Result<Vec<(f64, f64)>> {
+    let rows = ode::solve(&problem(config), step, h)?;
+    Ok(rows.into_iter().map(|(t, [y])| (t, y)).collect())
 }
 
-fn observed_rate(prev_err: f64, err: f64) -> Option<f64> {
-    let a = prev_err.abs();
-    let b = err.abs();
-
-    if a == 0.0 || b == 0.0 {
-        None
-    } else {
-        Some((a / b).ln() / 2.0_f64.ln())
-    }
+/// `(steps, h)` for each step size, the refinements of a fixed-step study.
+fn refinements<'a>(config: &'a Config, hs: &'a [f64]) -> impl Iterator<Item = (usize, f64)> + 'a {
+    hs.iter()
+        .map(|&h| (((config.t1 - config.t0) / h).round() as usize, h))
 }
 
-pub fn euler_part1() -> Vec<StepRow> {
-    solve(euler_step, 0.25)
+pub fn euler_part1(config: &Config) -> Result<Vec<StepRow>> {
+    let rows = solve(config, euler_step, config.part1_h)?
         .into_iter()
         .enumerate()
         .map(|(k, (t, approx))| {
-            let exact = exact(t);
+            let exact = exact(config, t);
 
             StepRow {
                 k,
@@ -94,162 +158,331 @@ pub fn euler_part1() -> Vec<StepRow> {
                 err: exact - approx,
             }
         })
-        .collect()
-}
+        .collect();
 
-fn summarize(step: StepFn, hs: &[f64]) -> Vec<SummaryRow> {
-    let exact_end = exact(T1);
+    Ok(rows)
+}
 
-    hs.iter()
-        .copied()
-        .map(|h| {
-            let approx = solve(step, h)
-                .last()
-                .map(|(_, y)| *y)
-                .expect("solver returned no steps");
-            let err = exact_end - approx;
-            (h, approx, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (h, approx, err)| {
-            let rate = prev_err.and_then(|prev| observed_rate(prev, err));
-            *prev_err = Some(err);
+fn summarize(config: &Config, step: StepFn<Problem, 1>, hs: &[f64]) -> Result<Convergence> {
+    let exact_end = exact(config, config.t1);
 
-            Some(SummaryRow {
-                h,
-                approx,
-                err,
-                rate,
-            })
-        })
-        .collect()
+    ConvergenceStudy::new().try_run(refinements(config, hs), |_, h| {
+        let (_, approx) = *solve(config, step, h)?
+            .last()
+            .expect("solve includes the initial condition");
+        Ok(Sample::approx(approx, exact_end - approx))
+    })
 }
 
-pub fn euler_summary() -> Vec<SummaryRow> {
-    summarize(euler_step, &[0.5, 0.25, 0.125, 0.0625, 0.03125])
+pub fn euler_summary(config: &Config) -> Result<Convergence> {
+    summarize(config, euler_step, &config.step_sizes)
 }
 
-pub fn rk4_summary() -> Vec<SummaryRow> {
-    summarize(rk4_step, &[0.5, 0.25, 0.125, 0.0625, 0.03125])
+pub fn rk4_summary(config: &Config) -> Result<Convergence> {
+    summarize(config, rk4_step, &config.step_sizes)
 }
 
-fn extrapolated_euler(h: f64) -> f64 {
-    let n = step_count(T0, T1, 2.0 * h);
-    let mut t = T0;
-    let mut yr = Y0;
+pub fn extrapolated_summary(config: &Config) -> Result<Convergence> {
+    let exact_end = exact(config, config.t1);
 
-    for _ in 0..n {
-        let z1 = euler_step(t, yr, h);
-        let z2 = euler_step(t + h, z1, h);
-        let z_bar = euler_step(t, yr, 2.0 * h);
+    ConvergenceStudy::new().try_run(
+        refinements(config, &config.extrapolation_step_sizes),
+        |_, h| {
+            let [approx] = ode::extrapolated_euler(&problem(config), h)?;
+            Ok(Sample::approx(approx, exact_end - approx))
+        },
+    )
+}
 
-        yr = 2.0 * z2 - z_bar;
-        t += 2.0 * h;
-    }
+/// Euler's method with `depth` levels of Richardson extrapolation at `t1`.
+///
+/// Row `h` uses Euler solves with `h, h/2, ..., h/2^depth`.
+pub fn richardson_summary(config: &Config, depth: usize) -> Result<Convergence> {
+    let exact_end = exact(config, config.t1);
+    let orders = extrapolation::arithmetic_orders(1.0, depth);
+
+    ConvergenceStudy::new().try_run(
+        refinements(config, &config.extrapolation_step_sizes),
+        |_, h| {
+            let [tableau] = ode::richardson_endpoint(&problem(config), euler_step, h, &orders)?;
+            let approx = tableau.best();
+            Ok(Sample::approx(approx, exact_end - approx))
+        },
+    )
+}
 
-    yr
+fn adaptive_solve(config: &Config, tol: f64) -> Result<AdaptiveSolution<1>> {
+    ode::dormand_prince(
+        &problem(config),
+        Tolerance {
+            rtol: tol,
+            atol: tol,
+        },
+    )
 }
 
-pub fn extrapolated_summary() -> Vec<SummaryRow> {
-    let hs = [0.25, 0.125, 0.0625, 0.03125];
-    let exact_end = exact(T1);
+/// Dormand-Prince runs over `config.adaptive_tols`, paired with their tolerance.
+pub fn adaptive_runs(config: &Config) -> Result<Vec<(f64, AdaptiveSolution<1>)>> {
+    config
+        .adaptive_tols
+        .iter()
+        .map(|&tol| Ok((tol, adaptive_solve(config, tol)?)))
+        .collect()
+}
 
-    hs.into_iter()
-        .map(|h| {
-            let approx = extrapolated_euler(h);
+/// Achieved error at `t1` and step counts for each adaptive run.
+///
+/// `rate` compares consecutive runs as a convergence study in the tolerance,
+/// so it measures how the achieved error tracks `tol`.
+pub fn adaptive_summary(config: &Config, runs: &[(f64, AdaptiveSolution<1>)]) -> Vec<AdaptiveRow> {
+    let exact_end = exact(config, config.t1);
+    let study = ConvergenceStudy::new();
+    let mut prev: Option<(f64, f64)> = None;
+
+    runs.iter()
+        .map(|(tol, solution)| {
+            let [approx] = solution.last();
             let err = exact_end - approx;
-            (h, approx, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (h, approx, err)| {
-            let rate = prev_err.and_then(|prev| observed_rate(prev, err));
-            *prev_err = Some(err);
-
-            Some(SummaryRow {
-                h,
+            let rate = prev.and_then(|coarse| study.observed_order(coarse, (*tol, err)));
+            prev = Some((*tol, err));
+            AdaptiveRow {
+                tol: *tol,
                 approx,
                 err,
                 rate,
-            })
+                accepted: solution.accepted(),
+                rejected: solution.rejected(),
+            }
         })
         .collect()
 }
 
-pub fn generate() -> io::Result<()> {
-    let part1 = euler_part1();
-    let euler = euler_summary();
-    let rk4 = rk4_summary();
-    let extrap = extrapolated_summary();
-
-    write_part1_data(&part1);
-    write_summary_data("euler", &euler);
-    write_summary_data("rk4", &rk4);
-    write_summary_data("extrapolated", &extrap);
-    write_plot_data(&part1);
-
-    util::plot("ch2_3")?;
-    util::run_python_script("scripts/ch2_3/make_tables.py")?;
-    util::copy_file("plots/ch2_3/plot.png", "reports/ch2_3/figures/plot.png")?;
-    util::build_report("reports/ch2_3", "2.3.pdf")
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_3");
+    let out_dir = paths.data.clone();
+    let part1 = euler_part1(config)?;
+    let euler = euler_summary(config)?;
+    let rk4 = rk4_summary(config)?;
+    let extrap = extrapolated_summary(config)?;
+    let richardson = richardson_summary(config, config.richardson_depth)?;
+    let runs = adaptive_runs(config)?;
+    let adaptive = adaptive_summary(config, &runs);
+
+    for ((_, solution), row) in runs.iter().zip(&adaptive) {
+        println!(
+            "tol = {:.3e}, err = {:.6e}, accepted = {}, rejected = {}, evaluations = {}",
+            row.tol, row.err, row.accepted, row.rejected, solution.evaluations
+        );
+    }
+
+    write_part1_data(&out_dir, &part1, &config.output.export)?;
+    for (name, summary) in [
+        ("euler", &euler),
+        ("rk4", &rk4),
+        ("extrapolated", &extrap),
+        ("richardson", &richardson),
+    ] {
+        if let Some(order) = summary.fitted_order {
+            println!("{name}: fitted order = {order:.6}");
+        }
+        summary.write(&out_dir, name, &config.output.export)?;
+    }
+    write_adaptive_data(config, &out_dir, &adaptive)?;
+    write_plot_data(config, &out_dir, &part1)?;
+
+    if config.output.plot {
+        util::plot("ch2_3", &paths)?;
+    }
+    if !config.output.report {
+        return Ok(());
+    }
+    let results = Results {
+        part1: &part1,
+        summaries: [
+            ("Part 3", "part3", "y_N", &euler),
+            ("Part 4", "part4", "y_N", &rk4),
+            ("Part 5", "part5", "y_N^r", &extrap),
+            (
+                "Richardson-extrapolated Euler",
+                "richardson",
+                "R_{2,2}",
+                &richardson,
+            ),
+        ],
+        adaptive: &adaptive,
+    };
+    write_tables(
+        config,
+        &format!("{}/figures/tables.tex", paths.reports),
+        &results,
+    )?;
+    util::publish_figures(&paths, &["plot.png", "adaptive_steps.png"])?;
+    util::build_report("ch2_3", &paths, "2.3.pdf")
+}
+
+/// Everything the report tables are built from.
+struct Results<'a> {
+    part1: &'a [StepRow],
+    /// `(title, label, approximation symbol, rows)` for the fixed-step tables.
+    summaries: [(&'static str, &'static str, &'static str, &'a Convergence); 4],
+    adaptive: &'a [AdaptiveRow],
+}
+
+fn write_tables(config: &Config, path: &str, results: &Results) -> Result<()> {
+    let mut tex = TexFile::new();
+    let inv_h = 1.0 / config.part1_h;
+    let step = if (inv_h - inv_h.round()).abs() < 1.0e-12 {
+        format!("\\tfrac{{1}}{{{}}}", inv_h.round())
+    } else {
+        config.part1_h.to_string()
+    };
+
+    tex.line(r"\section{Part 1}").table(
+        &Table::new(format!("Euler's method with $h={step}$."), "part1")
+            .compact(4)
+            .fit_width()
+            .column("$k$", Format::Int, |row: &StepRow| row.k as f64)
+            .column("$t_k$", Format::Fixed(2), |row| row.t)
+            .column("$y_k$", Format::Sci, |row| row.approx)
+            .column("$y(t_k)$", Format::Sci, |row| row.exact)
+            .column("Error $= y(t_k) - y_k$", Format::Sci, |row| row.err),
+        results.part1,
+    );
+
+    let t1 = config.t1;
+    for (title, label, symbol, summary) in results.summaries {
+        let table = Table::new(format!("{title}."), label)
+            .compact(4)
+            .column("$h$", Format::Trimmed(5), |row: &Level| row.h)
+            .optional(format!("${symbol}$"), Format::Sci, |row| row.approx)
+            .column(format!("$E_h = y({t1}) - {symbol}$"), Format::Sci, |row| {
+                row.err
+            })
+            .rate("Rate", Format::Fixed(6), |row| row.rate);
+
+        tex.line(format!("\\section{{{title}}}"))
+            .table(&table, &summary.levels);
+    }
+
+    tex.line(r"\section{Adaptive Dormand--Prince}").table(
+        &Table::new(
+            r"Dormand--Prince RK5(4) with $\mathrm{rtol}=\mathrm{atol}=\tau$.",
+            "adaptive",
+        )
+        .compact(4)
+        .column(r"$\tau$", Format::Sci, |row: &AdaptiveRow| row.tol)
+        .column("$y_N$", Format::Sci, |row| row.approx)
+        .column(format!(r"$E_\tau = y({t1}) - y_N$"), Format::Sci, |row| {
+            row.err
+        })
+        .rate("Rate", Format::Fixed(6), |row| row.rate)
+        .column("Accepted", Format::Int, |row| row.accepted as f64)
+        .column("Rejected", Format::Int, |row| row.rejected as f64),
+        results.adaptive,
+    );
+
+    tex.write(path)
 }
 
-fn write_part1_data(rows: &[StepRow]) {
-    let out_dir = String::from("data/ch2_3");
-    let ks: Vec<f64> = rows.iter().map(|row| row.k as f64).collect();
-    let t: Vec<f64> = rows.iter().map(|row| row.t).collect();
-    let approx: Vec<f64> = rows.iter().map(|row| row.approx).collect();
-    let exact_vals: Vec<f64> = rows.iter().map(|row| row.exact).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-
-    util::write_data(&ks, out_dir.clone(), String::from("part1__k"));
-    util::write_data(&t, out_dir.clone(), String::from("part1__t"));
-    util::write_data(&approx, out_dir.clone(), String::from("part1__approx"));
-    util::write_data(&exact_vals, out_dir.clone(), String::from("part1__exact"));
-    util::write_data(&err, out_dir, String::from("part1__err"));
+fn write_part1_data(out_dir: &str, rows: &[StepRow], export: &[Export]) -> Result<()> {
+    let fields = [
+        Field::Int("k", rows.iter().map(|row| row.k as i64).collect()),
+        Field::Float("t", rows.iter().map(|row| row.t).collect()),
+        Field::Float("approx", rows.iter().map(|row| row.approx).collect()),
+        Field::Float("exact", rows.iter().map(|row| row.exact).collect()),
+        Field::Float("err", rows.iter().map(|row| row.err).collect()),
+    ];
+
+    util::write_table(&fields, out_dir.to_string(), String::from("part1"), export)
 }
 
-fn write_summary_data(name: &str, rows: &[SummaryRow]) {
-    let out_dir = String::from("data/ch2_3");
-    let h: Vec<f64> = rows.iter().map(|row| row.h).collect();
-    let approx: Vec<f64> = rows.iter().map(|row| row.approx).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-    let rate: Vec<f64> = rows
+/// The adaptive summary, plus the step history and dense output of the
+/// `history_tol` run.
+fn write_adaptive_data(config: &Config, out_dir: &str, rows: &[AdaptiveRow]) -> Result<()> {
+    let out_dir = out_dir.to_string();
+    let fields = [
+        Field::Float("tol", rows.iter().map(|row| row.tol).collect()),
+        Field::Float("approx", rows.iter().map(|row| row.approx).collect()),
+        Field::Float("err", rows.iter().map(|row| row.err).collect()),
+        Field::Optional("rate", rows.iter().map(|row| row.rate).collect()),
+        Field::Int(
+            "accepted",
+            rows.iter().map(|row| row.accepted as i64).collect(),
+        ),
+        Field::Int(
+            "rejected",
+            rows.iter().map(|row| row.rejected as i64).collect(),
+        ),
+    ];
+    util::write_table(
+        &fields,
+        out_dir.clone(),
+        String::from("adaptive"),
+        &config.output.export,
+    )?;
+
+    let history = adaptive_solve(config, config.history_tol)?;
+    let steps = &history.steps;
+    let fields = [
+        Field::Float("t", steps.iter().map(|step| step.t).collect()),
+        Field::Float("h", steps.iter().map(|step| step.h).collect()),
+        Field::Float("err", steps.iter().map(|step| step.err).collect()),
+        Field::Bool("accepted", steps.iter().map(|step| step.accepted).collect()),
+    ];
+    util::write_records(&fields, out_dir.clone(), String::from("history"))?;
+
+    let t_dense = config.dense_times();
+    let y_dense = t_dense
+        .iter()
+        .map(|&t| Ok(history.eval(t)?[0]))
+        .collect::<Result<Vec<f64>>>()?;
+    let dense_err: Vec<f64> = t_dense
         .iter()
-        .map(|row| row.rate.unwrap_or(f64::NAN))
+        .zip(&y_dense)
+        .map(|(&t, y)| exact(config, t) - y)
         .collect();
 
-    util::write_data(&h, out_dir.clone(), format!("{name}__h"));
-    util::write_data(&approx, out_dir.clone(), format!("{name}__approx"));
-    util::write_data(&err, out_dir.clone(), format!("{name}__err"));
-    util::write_data(&rate, out_dir, format!("{name}__rate"));
+    util::write_data(&t_dense, out_dir.clone(), String::from("dense__t"))?;
+    util::write_data(&y_dense, out_dir.clone(), String::from("dense__y"))?;
+    util::write_data(&dense_err, out_dir, String::from("dense__err"))?;
+    Ok(())
 }
 
-fn write_plot_data(rows: &[StepRow]) {
-    let out_dir = String::from("data/ch2_3");
+fn write_plot_data(config: &Config, out_dir: &str, rows: &[StepRow]) -> Result<()> {
+    let out_dir = out_dir.to_string();
     let t_euler: Vec<f64> = rows.iter().map(|row| row.t).collect();
     let y_euler: Vec<f64> = rows.iter().map(|row| row.approx).collect();
 
-    let t_exact: Vec<f64> = (0..=400)
-        .map(|i| T0 + (T1 - T0) * (i as f64) / 400.0)
-        .collect();
-    let y_exact: Vec<f64> = t_exact.iter().copied().map(exact).collect();
+    let t_exact = config.dense_times();
+    let y_exact: Vec<f64> = t_exact.iter().map(|&t| exact(config, t)).collect();
 
-    util::write_data(&t_exact, out_dir.clone(), String::from("plot__exact_t"));
-    util::write_data(&y_exact, out_dir.clone(), String::from("plot__exact_y"));
-    util::write_data(&t_euler, out_dir.clone(), String::from("plot__euler_t"));
-    util::write_data(&y_euler, out_dir, String::from("plot__euler_y"));
+    util::write_data(&t_exact, out_dir.clone(), String::from("plot__exact_t"))?;
+    util::write_data(&y_exact, out_dir.clone(), String::from("plot__exact_y"))?;
+    util::write_data(&t_euler, out_dir.clone(), String::from("plot__euler_t"))?;
+    util::write_data(&y_euler, out_dir, String::from("plot__euler_y"))?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Y0, euler_summary, exact, extrapolated_summary, rk4_summary};
+    use super::{
+        Config, T1, Y0, adaptive_runs, adaptive_summary, euler_summary, exact,
+        extrapolated_summary, richardson_summary, rk4_summary,
+    };
 
     #[test]
     fn exact_solution_matches_initial_value() {
-        assert!((exact(1.0) - Y0).abs() < 1.0e-12);
+        assert!((exact(&Config::default(), 1.0) - Y0).abs() < 1.0e-12);
+
+        let shifted: Config = mat627::config::parse("t0 = 0.5\ny0 = -1.0\n").unwrap();
+        assert!((exact(&shifted, 0.5) + 1.0).abs() < 1.0e-12);
     }
 
     #[test]
     fn euler_converges_first_order() {
-        let rows = euler_summary();
+        let rows = euler_summary(&Config::default())
+            .expect("euler solve failed")
+            .levels;
         let last = rows.last().expect("missing euler rows");
 
         assert!(rows[1].err.abs() < rows[0].err.abs());
@@ -258,7 +491,9 @@ mod tests {
 
     #[test]
     fn rk4_converges_fourth_order() {
-        let rows = rk4_summary();
+        let rows = rk4_summary(&Config::default())
+            .expect("rk4 solve failed")
+            .levels;
         let last = rows.last().expect("missing rk4 rows");
 
         assert!(rows[1].err.abs() < rows[0].err.abs());
@@ -267,10 +502,46 @@ mod tests {
 
     #[test]
     fn extrapolation_improves_euler_order() {
-        let rows = extrapolated_summary();
+        let rows = extrapolated_summary(&Config::default())
+            .expect("extrapolation solve failed")
+            .levels;
         let last = rows.last().expect("missing extrapolation rows");
 
         assert!(rows[1].err.abs() < rows[0].err.abs());
         assert!((last.rate.expect("missing extrapolation rate") - 2.0).abs() < 0.15);
     }
+
+    #[test]
+    fn richardson_depth_raises_euler_order() {
+        for depth in [1, 2] {
+            let rows = richardson_summary(&Config::default(), depth)
+                .expect("richardson solve failed")
+                .levels;
+            let rate = rows.last().unwrap().rate.expect("missing richardson rate");
+
+            assert!(
+                (rate - (depth + 1) as f64).abs() < 0.2,
+                "depth {depth}: {rate}"
+            );
+        }
+    }
+
+    #[test]
+    fn adaptive_error_tracks_tolerance() {
+        let config = Config::default();
+        let runs = adaptive_runs(&config).expect("adaptive solve failed");
+        let rows = adaptive_summary(&config, &runs);
+
+        for row in &rows {
+            assert!(row.err.abs() < 10.0 * row.tol * exact(&config, T1).abs());
+        }
+        assert!(rows.last().unwrap().err.abs() < rows[0].err.abs());
+        assert!(rows.iter().all(|row| row.accepted > 0));
+        assert!(rows[0].rate.is_none());
+        assert!(
+            rows[1..]
+                .iter()
+                .all(|row| row.rate.is_some_and(|r| r > 0.0))
+        );
+    }
 }
@@ -1,5 +1,11 @@
-use crate::util;
-use std::io;
+use mat627::config::{Output, Validate, at_least, interval};
+use mat627::convergence::{Convergence, ConvergenceStudy, Level, Sample};
+use mat627::error::Result;
+use mat627::interpolation;
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::util;
+use serde::Deserialize;
 
 const PLOT_A: f64 = 0.0;
 const PLOT_B: f64 = 2.0;
@@ -7,90 +13,65 @@ const PLOT_N: usize = 4;
 const FINE_STEPS: usize = 1000;
 const N_VALUES: [usize; 6] = [1, 2, 4, 8, 16, 32];
 
-#[derive(Debug, Clone, Copy)]
-pub struct SummaryRow {
-    pub n: usize,
-    pub h: f64,
-    pub err: f64,
-    pub rate: Option<f64>,
+/// Parameters of the Section 2.4 study, read from `config/ch2_4.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Subinterval counts for the convergence tables.
+    pub n_values: Vec<usize>,
+    /// Steps of the fine grid the error is sampled on.
+    pub fine_steps: usize,
+    pub smooth_interval: [f64; 2],
+    pub singular_interval: [f64; 2],
+    pub plot_interval: [f64; 2],
+    pub plot_n: usize,
+    pub output: Output,
 }
 
-fn f(x: f64) -> f64 {
-    x.cbrt()
-}
-
-fn mesh(a: f64, b: f64, n: usize) -> Vec<(f64, f64)> {
-    assert!(n > 0, "n must be positive");
-
-    let h = (b - a) / n as f64;
-
-    (0..n)
-        .map(|i| {
-            let left = a + i as f64 * h;
-            let right = a + (i + 1) as f64 * h;
-            (left, right)
-        })
-        .collect()
-}
-
-fn linear_interp<F>(a: f64, b: f64, f: F) -> impl Fn(f64) -> f64
-where
-    F: Fn(f64) -> f64,
-{
-    assert!(a != b, "interval endpoints must be distinct");
-
-    let fa = f(a);
-    let fb = f(b);
-
-    move |x| fa * (b - x) / (b - a) + fb * (x - a) / (b - a)
-}
-
-fn fine_grid(a: f64, b: f64) -> Vec<f64> {
-    (0..=FINE_STEPS)
-        .map(|i| a + (b - a) * i as f64 / FINE_STEPS as f64)
-        .collect()
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            n_values: N_VALUES.to_vec(),
+            fine_steps: FINE_STEPS,
+            smooth_interval: [1.0, 2.0],
+            singular_interval: [0.0, 1.0],
+            plot_interval: [PLOT_A, PLOT_B],
+            plot_n: PLOT_N,
+            output: Output::default(),
+        }
+    }
 }
 
-fn interval_index(a: f64, b: f64, n: usize, x: f64) -> usize {
-    assert!(x >= a - 1.0e-12 && x <= b + 1.0e-12, "x must lie in [a, b]");
-
-    if (x - b).abs() < 1.0e-12 {
-        return n - 1;
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        at_least("n_values", &self.n_values, 1)?;
+        at_least("fine_steps", &[self.fine_steps], 1)?;
+        interval("smooth_interval", self.smooth_interval)?;
+        interval("singular_interval", self.singular_interval)?;
+        interval("plot_interval", self.plot_interval)?;
+        at_least("plot_n", &[self.plot_n], 1)
     }
-
-    let h = (b - a) / n as f64;
-    (((x - a) / h).floor() as usize).min(n - 1)
 }
 
-fn piecewise_linear_values(a: f64, b: f64, n: usize, xs: &[f64]) -> Vec<f64> {
-    let pieces: Vec<_> = mesh(a, b, n)
-        .into_iter()
-        .map(|(left, right)| linear_interp(left, right, f as fn(f64) -> f64))
-        .collect();
+fn f(x: f64) -> f64 {
+    x.cbrt()
+}
 
-    xs.iter()
-        .copied()
-        .map(|x| pieces[interval_index(a, b, n, x)](x))
-        .collect()
+fn fine_grid(a: f64, b: f64, steps: usize) -> Vec<f64> {
+    interpolation::uniform_grid(a, b, steps)
 }
 
-fn observed_rate(prev_err: f64, err: f64) -> Option<f64> {
-    if prev_err == 0.0 || err == 0.0 {
-        None
-    } else {
-        Some((prev_err / err).ln() / 2.0_f64.ln())
-    }
+fn piecewise_linear_values(a: f64, b: f64, n: usize, xs: &[f64]) -> Result<Vec<f64>> {
+    interpolation::piecewise_linear_values(f, a, b, n, xs)
 }
 
-fn summarize_case(a: f64, b: f64) -> Vec<SummaryRow> {
-    let z = fine_grid(a, b);
+fn summarize_case(config: &Config, [a, b]: [f64; 2]) -> Result<Convergence> {
+    let z = fine_grid(a, b, config.fine_steps);
 
-    N_VALUES
-        .iter()
-        .copied()
-        .map(|n| {
-            let h = (b - a) / n as f64;
-            let q = piecewise_linear_values(a, b, n, &z);
+    ConvergenceStudy::new().try_run(
+        config.n_values.iter().map(|&n| (n, (b - a) / n as f64)),
+        |n, _| {
+            let q = piecewise_linear_values(a, b, n, &z)?;
             let err = z
                 .iter()
                 .copied()
@@ -98,80 +79,106 @@ fn summarize_case(a: f64, b: f64) -> Vec<SummaryRow> {
                 .map(|(x, qx)| (qx - f(x)).abs())
                 .fold(0.0, f64::max);
 
-            (n, h, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (n, h, err)| {
-            let rate = prev_err.and_then(|prev| observed_rate(prev, err));
-            *prev_err = Some(err);
-
-            Some(SummaryRow { n, h, err, rate })
-        })
-        .collect()
+            Ok(Sample::err(err))
+        },
+    )
 }
 
-pub fn smooth_summary() -> Vec<SummaryRow> {
-    summarize_case(1.0, 2.0)
+pub fn smooth_summary(config: &Config) -> Result<Convergence> {
+    summarize_case(config, config.smooth_interval)
 }
 
-pub fn singular_summary() -> Vec<SummaryRow> {
-    summarize_case(0.0, 1.0)
+pub fn singular_summary(config: &Config) -> Result<Convergence> {
+    summarize_case(config, config.singular_interval)
 }
 
-fn write_plot_data() {
-    let out_dir = String::from("data/ch2_4");
-    let x = fine_grid(PLOT_A, PLOT_B);
+fn write_plot_data(config: &Config, out_dir: &str) -> Result<()> {
+    let out_dir = out_dir.to_string();
+    let [a, b] = config.plot_interval;
+    let n = config.plot_n;
+    let x = fine_grid(a, b, config.fine_steps);
     let exact: Vec<f64> = x.iter().copied().map(f).collect();
-    let approx = piecewise_linear_values(PLOT_A, PLOT_B, PLOT_N, &x);
-    let nodes_x: Vec<f64> = (0..=PLOT_N)
-        .map(|i| PLOT_A + (PLOT_B - PLOT_A) * i as f64 / PLOT_N as f64)
-        .collect();
+    let approx = piecewise_linear_values(a, b, n, &x)?;
+    let nodes_x: Vec<f64> = (0..=n).map(|i| a + (b - a) * i as f64 / n as f64).collect();
     let nodes_y: Vec<f64> = nodes_x.iter().copied().map(f).collect();
 
-    util::write_data(&x, out_dir.clone(), String::from("plot__x"));
-    util::write_data(&exact, out_dir.clone(), String::from("plot__exact"));
-    util::write_data(&approx, out_dir.clone(), String::from("plot__approx"));
-    util::write_data(&nodes_x, out_dir.clone(), String::from("plot__nodes_x"));
-    util::write_data(&nodes_y, out_dir, String::from("plot__nodes_y"));
+    util::write_data(&x, out_dir.clone(), String::from("plot__x"))?;
+    util::write_data(&exact, out_dir.clone(), String::from("plot__exact"))?;
+    util::write_data(&approx, out_dir.clone(), String::from("plot__approx"))?;
+    util::write_data(&nodes_x, out_dir.clone(), String::from("plot__nodes_x"))?;
+    util::write_data(&nodes_y, out_dir, String::from("plot__nodes_y"))?;
+    Ok(())
 }
 
-fn write_summary_data(name: &str, rows: &[SummaryRow]) {
-    let out_dir = String::from("data/ch2_4");
-    let n: Vec<f64> = rows.iter().map(|row| row.n as f64).collect();
-    let h: Vec<f64> = rows.iter().map(|row| row.h).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-    let rate: Vec<f64> = rows
-        .iter()
-        .map(|row| row.rate.unwrap_or(f64::NAN))
-        .collect();
-
-    util::write_data(&n, out_dir.clone(), format!("{name}__n"));
-    util::write_data(&h, out_dir.clone(), format!("{name}__h"));
-    util::write_data(&err, out_dir.clone(), format!("{name}__err"));
-    util::write_data(&rate, out_dir, format!("{name}__rate"));
+fn write_tables(
+    config: &Config,
+    path: &str,
+    smooth: &Convergence,
+    singular: &Convergence,
+) -> Result<()> {
+    let mut tex = TexFile::new();
+    let parts = [
+        ("Part 2", "smooth", config.smooth_interval, smooth),
+        ("Part 3", "singular", config.singular_interval, singular),
+    ];
+
+    for (title, label, [a, b], summary) in parts {
+        let interval = format!("$[{a},{b}]$");
+        let table = Table::new(
+            format!("Maximum fine-grid error for $f(x)=x^{{1/3}}$ on {interval}."),
+            label,
+        )
+        .compact(6)
+        .column("$N$", Format::Int, |row: &Level| row.n as f64)
+        .column("$h$", Format::Trimmed(5), |row| row.h)
+        .column(
+            r"$E_h = \max_{z_i \in D}|q_N(z_i) - f(z_i)|$",
+            Format::Sci,
+            |row| row.err,
+        )
+        .rate("Rate", Format::Fixed(6), |row| row.rate);
+
+        tex.line(format!("\\section{{{title}}}"))
+            .line(format!(
+                "\\noindent Uniform piecewise linear interpolation on {interval}."
+            ))
+            .table(&table, &summary.levels);
+    }
+
+    tex.write(path)
 }
 
-pub fn generate() -> io::Result<()> {
-    let smooth = smooth_summary();
-    let singular = singular_summary();
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_4");
+    let out_dir = paths.data.clone();
+    let smooth = smooth_summary(config)?;
+    let singular = singular_summary(config)?;
 
-    write_plot_data();
-    write_summary_data("smooth", &smooth);
-    write_summary_data("singular", &singular);
+    write_plot_data(config, &out_dir)?;
+    smooth.write(&out_dir, "smooth", &config.output.export)?;
+    singular.write(&out_dir, "singular", &config.output.export)?;
 
-    util::plot("ch2_4")?;
-    util::run_python_script("scripts/ch2_4/make_tables.py")?;
-    util::copy_file(
-        "plots/ch2_4/approximation.png",
-        "reports/ch2_4/figures/approximation.png",
+    if config.output.plot {
+        util::plot("ch2_4", &paths)?;
+    }
+    if !config.output.report {
+        return Ok(());
+    }
+    write_tables(
+        config,
+        &format!("{}/figures/tables.tex", paths.reports),
+        &smooth,
+        &singular,
     )?;
-    util::copy_file("plots/ch2_4/error.png", "reports/ch2_4/figures/error.png")?;
-    util::build_report("reports/ch2_4", "2.4.pdf")
+    util::publish_figures(&paths, &["approximation.png", "error.png"])?;
+    util::build_report("ch2_4", &paths, "2.4.pdf")
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        PLOT_A, PLOT_B, PLOT_N, f, piecewise_linear_values, singular_summary, smooth_summary,
+        Config, PLOT_A, PLOT_B, PLOT_N, f, piecewise_linear_values, singular_summary,
+        smooth_summary,
     };
 
     #[test]
@@ -179,7 +186,8 @@ mod tests {
         let nodes: Vec<f64> = (0..=PLOT_N)
             .map(|i| PLOT_A + (PLOT_B - PLOT_A) * i as f64 / PLOT_N as f64)
             .collect();
-        let q = piecewise_linear_values(PLOT_A, PLOT_B, PLOT_N, &nodes);
+        let q =
+            piecewise_linear_values(PLOT_A, PLOT_B, PLOT_N, &nodes).expect("nodes lie in the mesh");
 
         for (x, qx) in nodes.iter().copied().zip(q) {
             assert!((qx - f(x)).abs() < 1.0e-12);
@@ -188,7 +196,9 @@ mod tests {
 
     #[test]
     fn smooth_case_converges_second_order() {
-        let rows = smooth_summary();
+        let rows = smooth_summary(&Config::default())
+            .expect("default config is valid")
+            .levels;
         let last = rows.last().expect("missing smooth rows");
 
         assert!(rows[1].err < rows[0].err);
@@ -197,11 +207,23 @@ mod tests {
 
     #[test]
     fn singular_case_converges_like_h_one_third() {
-        let rows = singular_summary();
+        let rows = singular_summary(&Config::default())
+            .expect("default config is valid")
+            .levels;
         let last = rows.last().expect("missing singular rows");
         let rate = last.rate.expect("missing singular rate");
 
         assert!(rows[1].err < rows[0].err);
         assert!(rate > 0.25 && rate < 0.45);
     }
+
+    #[test]
+    fn config_overrides_mesh_sizes() {
+        let config: Config =
+            mat627::config::parse("n_values = [4, 8]\nfine_steps = 200\n").expect("valid config");
+        let rows = smooth_summary(&config).expect("valid config").levels;
+
+        assert_eq!(rows.iter().map(|row| row.n).collect::<Vec<_>>(), vec![4, 8]);
+        assert_eq!(config.plot_interval, [PLOT_A, PLOT_B]);
+    }
 }
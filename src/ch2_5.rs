@@ -1,31 +1,145 @@
-use crate::util;
-use std::io;
+use mat627::config::{Export, Output, Validate, at_least, at_most, interval};
+use mat627::convergence::{Convergence, ConvergenceStudy, Level, Sample};
+use mat627::error::Result;
+use mat627::interpolation;
+use mat627::parallel;
+use mat627::quadrature::adaptive::{self, AdaptiveResult};
+use mat627::quadrature::gauss::{composite_gauss_legendre, gauss_jacobi};
+use mat627::quadrature::{self, Romberg, composite_trapezoid_with};
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::summation::Summation;
+use mat627::util::{self, Field};
+use serde::Deserialize;
 
 const SMOOTH_N_VALUES: [usize; 5] = [2, 4, 8, 16, 32];
 const SQRT_N_VALUES: [usize; 6] = [1, 2, 4, 8, 16, 32];
 const PLOT_N: usize = 4;
 const PLOT_STEPS: usize = 400;
+const ROMBERG_TOL: f64 = 1.0e-12;
+const ROMBERG_MAX_LEVELS: usize = 10;
+const GAUSS_POINTS: usize = 2;
+const SUMMATION_N_VALUES: [usize; 3] = [1_000, 10_000, 100_000];
+const ADAPTIVE_OPTIONS: adaptive::Options = adaptive::Options {
+    atol: 1.0e-10,
+    rtol: 1.0e-10,
+    max_depth: 50,
+};
+
+/// Upper bounds accepted from the config file.
+const MAX_ROMBERG_LEVELS: usize = 30;
+const MAX_ADAPTIVE_DEPTH: usize = 60;
 
 type ScalarFn = fn(f64) -> f64;
 
-#[derive(Debug, Clone, Copy)]
-pub struct SummaryRow {
-    pub n: usize,
-    pub h: f64,
-    pub approx: f64,
-    pub err: f64,
-    pub rate: Option<f64>,
+/// Parameters of the Section 2.5 study, read from `config/ch2_5.toml`.
+///
+/// Part 3 stays on `[0, 1]` because its Gauss-Jacobi rule places the `sqrt`
+/// singularity at the left endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub part1_interval: [f64; 2],
+    pub part2_interval: [f64; 2],
+    /// Subinterval counts for Parts 1 and 2.
+    pub smooth_n_values: Vec<usize>,
+    /// Subinterval counts for Part 3.
+    pub sqrt_n_values: Vec<usize>,
+    /// Subintervals of the plotted trapezoid approximation.
+    pub plot_n: usize,
+    pub plot_steps: usize,
+    /// Romberg stops once the diagonal error estimate is below this.
+    pub romberg_tol: f64,
+    /// Extrapolation depth cap; with `romberg_tol = 0` every level is taken.
+    pub romberg_max_levels: usize,
+    /// Points per panel of composite Gauss-Legendre.
+    pub gauss_points: usize,
+    pub adaptive: adaptive::Options,
+    /// How the trapezoid rule adds up its interior values, e.g. `"kahan"`.
+    pub summation: Summation,
+    /// Strategies compared in the summation table; empty to skip it.
+    pub compare_summations: Vec<Summation>,
+    /// Subinterval counts of the summation table, large enough for rounding
+    /// in the accumulation to show.
+    pub summation_n_values: Vec<usize>,
+    pub output: Output,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            part1_interval: [1.0, 3.0],
+            part2_interval: [0.0, 2.0],
+            smooth_n_values: SMOOTH_N_VALUES.to_vec(),
+            sqrt_n_values: SQRT_N_VALUES.to_vec(),
+            plot_n: PLOT_N,
+            plot_steps: PLOT_STEPS,
+            romberg_tol: ROMBERG_TOL,
+            romberg_max_levels: ROMBERG_MAX_LEVELS,
+            gauss_points: GAUSS_POINTS,
+            adaptive: ADAPTIVE_OPTIONS,
+            summation: Summation::Naive,
+            compare_summations: Summation::ALL.to_vec(),
+            summation_n_values: SUMMATION_N_VALUES.to_vec(),
+            output: Output::default(),
+        }
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        interval("part1_interval", self.part1_interval)?;
+        interval("part2_interval", self.part2_interval)?;
+        at_least("smooth_n_values", &self.smooth_n_values, 1)?;
+        at_least("sqrt_n_values", &self.sqrt_n_values, 1)?;
+        at_least("plot_n", &[self.plot_n], 1)?;
+        at_least("plot_steps", &[self.plot_steps], 1)?;
+        // Level k costs 2^(k-1) new evaluations.
+        at_least("romberg_max_levels", &[self.romberg_max_levels], 1)?;
+        at_most(
+            "romberg_max_levels",
+            &[self.romberg_max_levels],
+            MAX_ROMBERG_LEVELS,
+        )?;
+        at_least("gauss_points", &[self.gauss_points], 1)?;
+        at_least("summation_n_values", &self.summation_n_values, 1)?;
+        for (key, tol) in [
+            ("adaptive.atol", self.adaptive.atol),
+            ("adaptive.rtol", self.adaptive.rtol),
+        ] {
+            if tol.is_nan() || tol < 0.0 {
+                return Err(format!("{key}: {tol} is not a non-negative number"));
+            }
+        }
+        // With both tolerances zero no panel is ever accepted and the
+        // bisection runs all the way down to `max_depth`.
+        if self.adaptive.atol == 0.0 && self.adaptive.rtol == 0.0 {
+            return Err(String::from(
+                "adaptive: atol and rtol are both zero, at least one must be positive",
+            ));
+        }
+        // Deeper than this a panel is narrower than the f64 spacing of its endpoints.
+        at_most(
+            "adaptive.max_depth",
+            &[self.adaptive.max_depth],
+            MAX_ADAPTIVE_DEPTH,
+        )
+    }
 }
 
 #[derive(Clone, Copy)]
-struct Case {
+struct Case<'a> {
     slug: &'static str,
     title: &'static str,
+    /// The integrand in LaTeX, for the report.
+    integrand: &'static str,
     a: f64,
     b: f64,
-    ns: &'static [usize],
+    ns: &'a [usize],
     exact: f64,
     f: ScalarFn,
+    /// `f(x) = (x - a)^p g(x)` with smooth `g`, given as `(p, g)`.
+    endpoint_weight: Option<(f64, ScalarFn)>,
 }
 
 fn smooth_integrand(x: f64) -> f64 {
@@ -44,180 +158,520 @@ fn antiderivative(x: f64) -> f64 {
     -(x.powi(2) + 2.0 * x + 2.0) * (-x).exp()
 }
 
-fn cases() -> [Case; 3] {
+fn cases(config: &Config) -> [Case<'_>; 3] {
+    let [a1, b1] = config.part1_interval;
+    let [a2, b2] = config.part2_interval;
+
     [
         Case {
             slug: "part1",
             title: "Part 1",
-            a: 1.0,
-            b: 3.0,
-            ns: &SMOOTH_N_VALUES,
-            exact: smooth_exact_integral(1.0, 3.0),
+            integrand: r"$f(x)=x^2e^{-x}$",
+            a: a1,
+            b: b1,
+            ns: &config.smooth_n_values,
+            exact: smooth_exact_integral(a1, b1),
             f: smooth_integrand,
+            endpoint_weight: None,
         },
         Case {
             slug: "part2",
             title: "Part 2",
-            a: 0.0,
-            b: 2.0,
-            ns: &SMOOTH_N_VALUES,
-            exact: smooth_exact_integral(0.0, 2.0),
+            integrand: r"$f(x)=x^2e^{-x}$",
+            a: a2,
+            b: b2,
+            ns: &config.smooth_n_values,
+            exact: smooth_exact_integral(a2, b2),
             f: smooth_integrand,
+            endpoint_weight: None,
         },
         Case {
             slug: "part3",
             title: "Part 3",
+            integrand: r"$f(x)=\sqrt{x}$",
             a: 0.0,
             b: 1.0,
-            ns: &SQRT_N_VALUES,
+            ns: &config.sqrt_n_values,
             exact: 2.0 / 3.0,
             f: sqrt_integrand,
+            endpoint_weight: Some((0.5, |_| 1.0)),
         },
     ]
 }
 
-fn composite_trapezoid(f: ScalarFn, a: f64, b: f64, n: usize) -> f64 {
-    assert!(n > 0, "n must be positive");
-
-    let h = (b - a) / n as f64;
-    let interior_sum: f64 = (1..n).map(|i| f(a + i as f64 * h)).sum();
-
-    0.5 * h * (f(a) + 2.0 * interior_sum + f(b))
+fn summarize(case: Case, summation: Summation) -> Result<Convergence> {
+    summarize_with(case, |n| {
+        composite_trapezoid_with(case.f, case.a, case.b, n, summation)
+    })
 }
 
-fn observed_rate(prev_err: f64, err: f64) -> Option<f64> {
-    if prev_err == 0.0 || err == 0.0 {
-        None
-    } else {
-        Some((prev_err / err).ln() / 2.0_f64.ln())
+/// The trapezoid error at each of `config.summation_n_values` with every
+/// strategy in `config.compare_summations`, written to `summation__{slug}.npy`.
+///
+/// The `accumulation` column is `|Q - Q_exact|` for `config.summation`: the
+/// part of its error that exact summation of the same values removes.
+fn compare_summations(config: &Config, out_dir: &str, case: Case) -> Result<()> {
+    if config.compare_summations.is_empty() {
+        return Ok(());
     }
-}
-
-fn summarize(case: Case) -> Vec<SummaryRow> {
-    case.ns
+    let ns = &config.summation_n_values;
+    let trapezoid = |n, summation| composite_trapezoid_with(case.f, case.a, case.b, n, summation);
+
+    let rows = parallel::try_map(ns, |&n| -> Result<(Vec<f64>, f64)> {
+        let errors = config
+            .compare_summations
+            .iter()
+            .map(|&summation| Ok((trapezoid(n, summation)? - case.exact).abs()))
+            .collect::<Result<Vec<f64>>>()?;
+        let accumulation =
+            (trapezoid(n, config.summation)? - trapezoid(n, Summation::Exact)?).abs();
+        Ok((errors, accumulation))
+    })?;
+
+    let header: Vec<String> = config
+        .compare_summations
         .iter()
-        .copied()
-        .map(|n| {
-            let h = (case.b - case.a) / n as f64;
-            let approx = composite_trapezoid(case.f, case.a, case.b, n);
-            let err = (approx - case.exact).abs();
-            (n, h, approx, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (n, h, approx, err)| {
-            let rate = prev_err.and_then(|prev| observed_rate(prev, err));
-            *prev_err = Some(err);
-
-            Some(SummaryRow {
-                n,
-                h,
-                approx,
-                err,
-                rate,
-            })
-        })
-        .collect()
+        .map(|s| format!("{:>11}", s.name()))
+        .collect();
+    println!(
+        "{}: trapezoid error by summation strategy\n{:>8} {} {:>12}",
+        case.title,
+        "n",
+        header.join(" "),
+        "accumulation"
+    );
+    for (n, (errors, accumulation)) in ns.iter().zip(&rows) {
+        let cells: Vec<String> = errors.iter().map(|e| format!("{e:>11.4e}")).collect();
+        println!("{n:>8} {} {accumulation:>12.4e}", cells.join(" "));
+    }
+
+    let mut fields = vec![Field::Int("n", ns.iter().map(|&n| n as i64).collect())];
+    for (i, summation) in config.compare_summations.iter().enumerate() {
+        fields.push(Field::Float(
+            summation.name(),
+            rows.iter().map(|(errors, _)| errors[i]).collect(),
+        ));
+    }
+    fields.push(Field::Float(
+        "accumulation",
+        rows.iter().map(|&(_, accumulation)| accumulation).collect(),
+    ));
+    util::write_table(
+        &fields,
+        out_dir.to_string(),
+        format!("summation__{}", case.slug),
+        &config.output.export,
+    )
 }
 
-fn fine_grid(a: f64, b: f64) -> Vec<f64> {
-    (0..=PLOT_STEPS)
-        .map(|i| a + (b - a) * i as f64 / PLOT_STEPS as f64)
-        .collect()
+/// Composite `gauss_points`-point Gauss-Legendre on the same meshes as the trapezoid rule.
+fn gauss_summary(config: &Config, case: Case) -> Result<Convergence> {
+    summarize_with(case, |n| {
+        composite_gauss_legendre(case.f, case.a, case.b, n, config.gauss_points)
+    })
 }
 
-fn interval_index(a: f64, b: f64, n: usize, x: f64) -> usize {
-    if (x - b).abs() < 1.0e-12 {
-        return n - 1;
-    }
+/// Gauss-Jacobi with the endpoint factor absorbed into the weight; `n` counts nodes.
+fn jacobi_summary(case: Case) -> Result<Option<Convergence>> {
+    let Some((p, g)) = case.endpoint_weight else {
+        return Ok(None);
+    };
 
-    let h = (b - a) / n as f64;
-    (((x - a) / h).floor() as usize).min(n - 1)
+    summarize_with(case, |n| gauss_jacobi(g, case.a, case.b, n, 0.0, p)).map(Some)
 }
 
-fn piecewise_linear_values(f: ScalarFn, a: f64, b: f64, n: usize, xs: &[f64]) -> Vec<f64> {
-    let h = (b - a) / n as f64;
+fn summarize_with<Q: Fn(usize) -> Result<f64> + Sync>(case: Case, rule: Q) -> Result<Convergence> {
+    ConvergenceStudy::new().try_run(
+        case.ns.iter().map(|&n| (n, (case.b - case.a) / n as f64)),
+        |n, _| {
+            let approx = rule(n)?;
+            Ok(Sample::approx(approx, (approx - case.exact).abs()))
+        },
+    )
+}
 
-    xs.iter()
-        .copied()
-        .map(|x| {
-            let i = interval_index(a, b, n, x);
-            let x_left = a + i as f64 * h;
-            let x_right = x_left + h;
-            let f_left = f(x_left);
-            let f_right = f(x_right);
-
-            f_left * (x_right - x) / h + f_right * (x - x_left) / h
-        })
-        .collect()
+fn romberg(config: &Config, case: Case) -> Romberg {
+    quadrature::romberg(
+        case.f,
+        case.a,
+        case.b,
+        config.romberg_tol,
+        config.romberg_max_levels,
+    )
 }
 
-fn write_summary_data(case: Case, rows: &[SummaryRow]) {
-    let out_dir = String::from("data/ch2_5");
-    let n: Vec<f64> = rows.iter().map(|row| row.n as f64).collect();
-    let h: Vec<f64> = rows.iter().map(|row| row.h).collect();
-    let approx: Vec<f64> = rows.iter().map(|row| row.approx).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-    let rate: Vec<f64> = rows
+/// Error of the Romberg diagonal `R[i][i]` against the exact integral, one
+/// row per level with `n = 2^i` subintervals in the first column.
+fn romberg_summary(case: Case, result: &Romberg) -> Convergence {
+    let levels = result
+        .tableau
+        .h
         .iter()
-        .map(|row| row.rate.unwrap_or(f64::NAN))
+        .copied()
+        .zip(result.diagonal())
+        .enumerate()
+        .map(|(i, (h, approx))| Level {
+            n: 1 << i,
+            h,
+            approx: Some(approx),
+            err: (approx - case.exact).abs(),
+            rate: None,
+        })
         .collect();
+    ConvergenceStudy::new().summarize(levels)
+}
 
-    util::write_data(&n, out_dir.clone(), format!("{}__n", case.slug));
-    util::write_data(&h, out_dir.clone(), format!("{}__h", case.slug));
-    util::write_data(&approx, out_dir.clone(), format!("{}__approx", case.slug));
-    util::write_data(&err, out_dir.clone(), format!("{}__err", case.slug));
-    util::write_data(&rate, out_dir, format!("{}__rate", case.slug));
+/// Adaptive Simpson and Gauss-Kronrod runs, labelled by method slug.
+fn adaptive_runs(config: &Config, case: Case) -> Result<[(&'static str, AdaptiveResult); 2]> {
+    Ok([
+        (
+            "simpson",
+            adaptive::adaptive_simpson(case.f, case.a, case.b, config.adaptive)?,
+        ),
+        (
+            "kronrod",
+            adaptive::gauss_kronrod(case.f, case.a, case.b, config.adaptive)?,
+        ),
+    ])
 }
 
-fn write_plot_data(case: Case) {
-    let out_dir = String::from("data/ch2_5");
-    let x = fine_grid(case.a, case.b);
+fn write_adaptive_data(
+    out_dir: &str,
+    method: &str,
+    cases: &[Case],
+    results: &[AdaptiveResult],
+    export: &[Export],
+) -> Result<()> {
+    let fields = [
+        Field::Float("estimate", results.iter().map(|r| r.estimate).collect()),
+        Field::Float("bound", results.iter().map(|r| r.error).collect()),
+        Field::Float(
+            "err",
+            results
+                .iter()
+                .zip(cases)
+                .map(|(r, case)| (r.estimate - case.exact).abs())
+                .collect(),
+        ),
+        Field::Int(
+            "evaluations",
+            results.iter().map(|r| r.evaluations as i64).collect(),
+        ),
+        Field::Int(
+            "intervals",
+            results.iter().map(|r| r.intervals.len() as i64).collect(),
+        ),
+    ];
+
+    util::write_table(
+        &fields,
+        out_dir.to_string(),
+        format!("adaptive__{method}"),
+        export,
+    )
+}
+
+fn write_plot_data(config: &Config, out_dir: &str, case: Case) -> Result<()> {
+    let out_dir = out_dir.to_string();
+    let n = config.plot_n;
+    let x = interpolation::uniform_grid(case.a, case.b, config.plot_steps);
     let exact: Vec<f64> = x.iter().copied().map(case.f).collect();
-    let interp = piecewise_linear_values(case.f, case.a, case.b, PLOT_N, &x);
-    let nodes_x: Vec<f64> = (0..=PLOT_N)
-        .map(|i| case.a + (case.b - case.a) * i as f64 / PLOT_N as f64)
+    let interp = interpolation::piecewise_linear_values(case.f, case.a, case.b, n, &x)?;
+    let nodes_x: Vec<f64> = (0..=n)
+        .map(|i| case.a + (case.b - case.a) * i as f64 / n as f64)
         .collect();
     let nodes_y: Vec<f64> = nodes_x.iter().copied().map(case.f).collect();
 
-    util::write_data(&x, out_dir.clone(), format!("plot__{}__x", case.slug));
+    util::write_data(&x, out_dir.clone(), format!("plot__{}__x", case.slug))?;
     util::write_data(
         &exact,
         out_dir.clone(),
         format!("plot__{}__exact", case.slug),
-    );
+    )?;
     util::write_data(
         &interp,
         out_dir.clone(),
         format!("plot__{}__interp", case.slug),
-    );
+    )?;
     util::write_data(
         &nodes_x,
         out_dir.clone(),
         format!("plot__{}__nodes_x", case.slug),
+    )?;
+    util::write_data(&nodes_y, out_dir, format!("plot__{}__nodes_y", case.slug))?;
+    Ok(())
+}
+
+/// The summaries of one case that end up in the report.
+struct CaseTables {
+    trapezoid: Convergence,
+    romberg: Convergence,
+    romberg_evaluations: usize,
+    gauss: Convergence,
+    jacobi: Option<Convergence>,
+}
+
+/// `10^{k}` for exact powers of ten, plain `{:e}` otherwise.
+fn tolerance_tex(tol: f64) -> String {
+    let exponent = tol.log10().round();
+    if tol == 10.0_f64.powf(exponent) {
+        format!("10^{{{exponent}}}")
+    } else {
+        format!("{tol:e}")
+    }
+}
+
+fn convergence_table<'a>(
+    caption: String,
+    label: String,
+    approx_header: &str,
+    err_header: &str,
+) -> Table<'a, Level> {
+    Table::new(caption, label)
+        .compact(5)
+        .column("$N$", Format::Int, |row: &Level| row.n as f64)
+        .column("$h$", Format::Trimmed(5), |row| row.h)
+        .optional(approx_header, Format::Sci, |row| row.approx)
+        .column(err_header, Format::Sci, |row| row.err)
+        .rate("Rate", Format::Fixed(6), |row| row.rate)
+}
+
+fn write_tables(
+    config: &Config,
+    path: &str,
+    cases: &[Case],
+    tables: &[CaseTables],
+    adaptive: [(&str, &[AdaptiveResult]); 2],
+) -> Result<()> {
+    let mut tex = TexFile::new();
+    let interval = |case: &Case| format!("$[{},{}]$", case.a, case.b);
+
+    for (case, t) in cases.iter().zip(tables) {
+        let table = convergence_table(
+            format!("Composite trapezoid-rule results on {}.", interval(case)),
+            case.slug.to_string(),
+            "$T_N(f)$",
+            "$E_h = |T_N(f)-I(f)|$",
+        );
+        tex.line(format!("\\section{{{}}}", case.title))
+            .line(format!(
+                "\\noindent Composite trapezoid rule for {} on {}.",
+                case.integrand,
+                interval(case)
+            ))
+            .table(&table, &t.trapezoid.levels);
+    }
+
+    tex.line(r"\section{Romberg integration}");
+    for (case, t) in cases.iter().zip(tables) {
+        let table = convergence_table(
+            format!("Romberg integration on {}.", interval(case)),
+            format!("romberg_{}", case.slug),
+            "$R_{k,k}$",
+            "$|R_{k,k}-I(f)|$",
+        );
+        tex.line(format!("\\subsection{{Romberg: {}}}", case.title))
+            .line(format!(
+                "\\noindent Romberg diagonal $R_{{k,k}}$ on {} using {} function evaluations.",
+                interval(case),
+                t.romberg_evaluations
+            ))
+            .table(&table, &t.romberg.levels);
+    }
+
+    let points = match config.gauss_points {
+        2 => String::from("two"),
+        n => n.to_string(),
+    };
+    tex.line(r"\section{Gaussian quadrature}");
+    for (case, t) in cases.iter().zip(tables) {
+        let (trapezoid, gauss) = (&t.trapezoid.levels, &t.gauss.levels);
+        let rows: Vec<usize> = (0..gauss.len()).collect();
+        let mut table = Table::new(
+            format!(
+                "Trapezoid versus composite {points}-point Gauss--Legendre on {}.",
+                interval(case)
+            ),
+            format!("gauss_{}", case.slug),
+        )
+        .compact(5)
+        .column("$N$", Format::Int, |&i: &usize| gauss[i].n as f64)
+        .column("$E_h$ (trapezoid)", Format::Sci, |&i| trapezoid[i].err)
+        .column("$E_h$ (Gauss--Legendre)", Format::Sci, |&i| gauss[i].err)
+        .rate("Rate", Format::Fixed(6), |&i| gauss[i].rate);
+        if let Some(jacobi) = &t.jacobi {
+            table = table.column("$E_N$ (Gauss--Jacobi, $N$ nodes)", Format::Sci, move |&i| {
+                jacobi.levels[i].err
+            });
+        }
+        tex.line(format!(
+            "\\subsection{{Gaussian quadrature: {}}}",
+            case.title
+        ))
+        .table(&table, &rows);
+    }
+
+    let rows: Vec<(&str, &Case, &AdaptiveResult)> = adaptive
+        .iter()
+        .flat_map(|(method, results)| {
+            cases
+                .iter()
+                .zip(results.iter())
+                .map(move |(case, result)| (*method, case, result))
+        })
+        .collect();
+    let tol = if config.adaptive.atol == config.adaptive.rtol {
+        format!(
+            r"$\mathrm{{atol}}=\mathrm{{rtol}}={}$",
+            tolerance_tex(config.adaptive.atol)
+        )
+    } else {
+        format!(
+            r"$\mathrm{{atol}}={}$, $\mathrm{{rtol}}={}$",
+            tolerance_tex(config.adaptive.atol),
+            tolerance_tex(config.adaptive.rtol)
+        )
+    };
+    tex.line(r"\section{Adaptive quadrature}").table(
+        &Table::new(format!("Adaptive quadrature with {tol}."), "adaptive")
+            .compact(5)
+            .text(
+                "Method",
+                |(method, _, _): &(&str, &Case, &AdaptiveResult)| method.to_string(),
+            )
+            .text("Case", |(_, case, _)| case.title.to_string())
+            .column("Error bound", Format::Sci, |(_, _, result)| result.error)
+            .column("$|Q-I(f)|$", Format::Sci, |(_, case, result)| {
+                (result.estimate - case.exact).abs()
+            })
+            .column("Evaluations", Format::Int, |(_, _, result)| {
+                result.evaluations as f64
+            })
+            .column("Subintervals", Format::Int, |(_, _, result)| {
+                result.intervals.len() as f64
+            }),
+        &rows,
     );
-    util::write_data(&nodes_y, out_dir, format!("plot__{}__nodes_y", case.slug));
+
+    tex.write(path)
 }
 
-pub fn generate() -> io::Result<()> {
-    for case in cases() {
-        let rows = summarize(case);
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_5");
+    let out_dir = paths.data.clone();
+    let mut simpson = Vec::new();
+    let mut kronrod = Vec::new();
+    let mut tables = Vec::new();
+
+    for case in cases(config) {
+        let rows = summarize(case, config.summation)?;
         println!("{}: exact integral = {:.10e}", case.title, case.exact);
-        write_summary_data(case, &rows);
-        write_plot_data(case);
+        if let Some(order) = rows.fitted_order {
+            println!("{}: trapezoid fitted order = {order:.6}", case.title);
+        }
+        rows.write(&out_dir, case.slug, &config.output.export)?;
+        write_plot_data(config, &out_dir, case)?;
+        compare_summations(config, &out_dir, case)?;
+
+        let result = romberg(config, case);
+        let romberg_rows = romberg_summary(case, &result);
+        println!(
+            "{}: Romberg R = {:.10e} after {} levels, {} evaluations, converged = {}",
+            case.title,
+            result.estimate(),
+            romberg_rows.levels.len() - 1,
+            result.evaluations,
+            result.converged
+        );
+        romberg_rows.write(
+            &out_dir,
+            &format!("romberg__{}", case.slug),
+            &config.output.export,
+        )?;
+        util::write_records(
+            &[Field::Int("evaluations", vec![result.evaluations as i64])],
+            out_dir.clone(),
+            format!("romberg__{}__evaluations", case.slug),
+        )?;
+        let gauss = gauss_summary(config, case)?;
+        gauss.write(
+            &out_dir,
+            &format!("gauss__{}", case.slug),
+            &config.output.export,
+        )?;
+        let jacobi = jacobi_summary(case)?;
+        if let Some(rows) = &jacobi {
+            rows.write(
+                &out_dir,
+                &format!("jacobi__{}", case.slug),
+                &config.output.export,
+            )?;
+        }
+
+        let [(_, s), (_, k)] = adaptive_runs(config, case)?;
+        for (method, run) in [("simpson", &s), ("kronrod", &k)] {
+            println!(
+                "{}: adaptive {} = {:.10e}, bound = {:.3e}, error = {:.3e}, {} evaluations, {} intervals",
+                case.title,
+                method,
+                run.estimate,
+                run.error,
+                (run.estimate - case.exact).abs(),
+                run.evaluations,
+                run.intervals.len()
+            );
+            run.write_intervals(&out_dir, &format!("intervals__{method}__{}", case.slug))?;
+        }
+        simpson.push(s);
+        kronrod.push(k);
+        tables.push(CaseTables {
+            trapezoid: rows,
+            romberg: romberg_rows,
+            romberg_evaluations: result.evaluations,
+            gauss,
+            jacobi,
+        });
     }
 
-    util::plot("ch2_5")?;
-    util::run_python_script("scripts/ch2_5/make_tables.py")?;
-    util::copy_file(
-        "plots/ch2_5/trapezoids.png",
-        "reports/ch2_5/figures/trapezoids.png",
+    let all = cases(config);
+    write_adaptive_data(&out_dir, "simpson", &all, &simpson, &config.output.export)?;
+    write_adaptive_data(&out_dir, "kronrod", &all, &kronrod, &config.output.export)?;
+
+    if config.output.plot {
+        util::plot("ch2_5", &paths)?;
+    }
+    if !config.output.report {
+        return Ok(());
+    }
+    write_tables(
+        config,
+        &format!("{}/figures/tables.tex", paths.reports),
+        &all,
+        &tables,
+        [
+            ("Adaptive Simpson", &simpson),
+            ("Gauss--Kronrod G7K15", &kronrod),
+        ],
     )?;
-    util::copy_file("plots/ch2_5/error.png", "reports/ch2_5/figures/error.png")?;
-    util::build_report("reports/ch2_5", "2.5.pdf")
+    util::publish_figures(
+        &paths,
+        &[
+            "trapezoids.png",
+            "error.png",
+            "gauss.png",
+            "adaptive.png",
+            "romberg.png",
+        ],
+    )?;
+    util::build_report("ch2_5", &paths, "2.5.pdf")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{cases, composite_trapezoid, smooth_exact_integral, summarize};
+    use super::{
+        Config, Summation, Validate, adaptive_runs, cases, composite_trapezoid_with, gauss_summary,
+        jacobi_summary, romberg, romberg_summary, smooth_exact_integral, summarize,
+    };
 
     #[test]
     fn smooth_exact_integrals_match_assignment_values() {
@@ -229,18 +683,34 @@ mod tests {
     }
 
     #[test]
-    fn trapezoid_rule_is_exact_for_linear_functions() {
-        let approx = composite_trapezoid(|x| 3.0 * x - 1.0, 0.0, 2.0, 8);
-        let exact = 4.0;
+    fn config_moves_smooth_interval() {
+        let config: Config = mat627::config::parse("part1_interval = [0.0, 1.0]\n").unwrap();
+        let [part1, part2, _] = cases(&config);
+
+        assert_eq!((part1.a, part1.b), (0.0, 1.0));
+        assert!((part1.exact - smooth_exact_integral(0.0, 1.0)).abs() < 1.0e-15);
+        assert_eq!((part2.a, part2.b), (0.0, 2.0));
+    }
 
-        assert!((approx - exact).abs() < 1.0e-12);
+    #[test]
+    fn config_rejects_adaptive_settings_that_never_stop() {
+        assert!(Config::default().validate().is_ok());
+        for text in [
+            "[adaptive]\natol = 0.0\nrtol = 0.0\nmax_depth = 50\n",
+            "[adaptive]\natol = 1e-10\nrtol = 1e-10\nmax_depth = 1000\n",
+            "romberg_max_levels = 64\n",
+        ] {
+            let config: Config = mat627::config::parse(text).unwrap();
+            assert!(config.validate().is_err(), "{text}");
+        }
     }
 
     #[test]
     fn smooth_cases_converge_quadratically() {
-        let [part1, part2, _] = cases();
-        let rows1 = summarize(part1);
-        let rows2 = summarize(part2);
+        let config = Config::default();
+        let [part1, part2, _] = cases(&config);
+        let rows1 = summarize(part1, Summation::Naive).unwrap().levels;
+        let rows2 = summarize(part2, Summation::Naive).unwrap().levels;
         let rate1 = rows1.last().expect("missing part1 rows").rate.unwrap();
         let rate2 = rows2.last().expect("missing part2 rows").rate.unwrap();
 
@@ -250,10 +720,103 @@ mod tests {
 
     #[test]
     fn sqrt_case_converges_like_h_to_three_halves() {
-        let [_, _, part3] = cases();
-        let rows = summarize(part3);
+        let config = Config::default();
+        let [_, _, part3] = cases(&config);
+        let summary = summarize(part3, Summation::Naive).unwrap();
+        let rate = summary.fitted_order.expect("no part3 fit");
+        assert!((rate - 1.5).abs() < 0.08);
+
+        let rows = summary.levels;
         let rate = rows.last().expect("missing part3 rows").rate.unwrap();
 
         assert!((rate - 1.5).abs() < 0.08);
     }
+
+    #[test]
+    fn romberg_converges_for_smooth_cases() {
+        let config = Config::default();
+        let [part1, part2, _] = cases(&config);
+
+        for case in [part1, part2] {
+            let result = romberg(&config, case);
+            assert!(result.converged, "{} did not converge", case.slug);
+            assert!((result.estimate() - case.exact).abs() < 1.0e-11);
+        }
+    }
+
+    #[test]
+    fn romberg_acceleration_breaks_down_for_sqrt() {
+        let config = Config::default();
+        let [_, _, part3] = cases(&config);
+        let result = romberg(&config, part3);
+        let rows = romberg_summary(part3, &result).levels;
+        let rate = rows.last().expect("missing Romberg rows").rate.unwrap();
+
+        assert!(!result.converged);
+        assert!((rate - 1.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn gauss_legendre_beats_trapezoid_on_smooth_cases() {
+        let config = Config::default();
+        let [part1, part2, _] = cases(&config);
+
+        for case in [part1, part2] {
+            let trapezoid = summarize(case, Summation::Naive).unwrap().levels;
+            let gauss = gauss_summary(&config, case).unwrap().levels;
+            let rate = gauss[2].rate.expect("missing Gauss rate");
+
+            assert!((rate - 4.0).abs() < 0.2, "{}: {rate}", case.slug);
+            for (t, g) in trapezoid.iter().zip(&gauss) {
+                assert!(g.err < t.err);
+            }
+        }
+    }
+
+    #[test]
+    fn gauss_jacobi_is_exact_for_sqrt_case() {
+        let config = Config::default();
+        let [part1, _, part3] = cases(&config);
+        let rows = jacobi_summary(part3)
+            .unwrap()
+            .expect("part3 has an endpoint weight");
+
+        assert!(jacobi_summary(part1).unwrap().is_none());
+        for row in rows.levels {
+            assert!(row.err < 1.0e-14, "n = {}: {}", row.n, row.err);
+        }
+    }
+
+    #[test]
+    fn adaptive_methods_cluster_near_sqrt_singularity() {
+        let config = Config::default();
+        let [_, _, part3] = cases(&config);
+
+        for (method, result) in adaptive_runs(&config, part3).unwrap() {
+            let first = result.intervals[0];
+
+            assert!(result.converged, "{method} did not converge");
+            assert!((result.estimate - part3.exact).abs() < 1.0e-9, "{method}");
+            assert!(
+                first.b - first.a < 1.0e-4,
+                "{method}: first width {}",
+                first.b
+            );
+        }
+    }
+
+    #[test]
+    fn accumulation_error_is_far_below_truncation_error() {
+        let config = Config::default();
+        let [part1, _, _] = cases(&config);
+        let trapezoid =
+            |summation| composite_trapezoid_with(part1.f, part1.a, part1.b, 10_000, summation);
+        let exact_sum = trapezoid(Summation::Exact).unwrap();
+
+        for summation in Summation::ALL {
+            let accumulation = (trapezoid(summation).unwrap() - exact_sum).abs();
+            assert!(accumulation < 1.0e-13, "{summation}: {accumulation:e}");
+        }
+        assert!((exact_sum - part1.exact).abs() > 1.0e-10);
+    }
 }
@@ -1,12 +1,53 @@
-use crate::util;
-use std::io;
+use mat627::config::{Export, Output, Validate, at_least};
+use mat627::error::{Mat627Error, Result};
+use mat627::linalg::{
+    self, Refinement, RefinementOptions, ResidualPrecision, SolveDiagnostics, TridiagonalMatrix,
+    max_abs, vector_subtract,
+};
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::util::{self, Field};
+use serde::Deserialize;
 
 const N_VALUES: [usize; 4] = [3, 10, 25, 100];
+/// Factor in `f32`, accumulate residuals in `f64`.
+const REFINEMENT_OPTIONS: RefinementOptions = RefinementOptions {
+    max_iterations: 30,
+    residual: ResidualPrecision::Working,
+};
+
+/// Parameters of the Section 2.6 study, read from `config/ch2_6.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// System sizes.
+    pub n_values: Vec<usize>,
+    /// Mixed-precision refinement settings, e.g. `residual = "compensated"`.
+    pub refinement: RefinementOptions,
+    pub output: Output,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            n_values: N_VALUES.to_vec(),
+            refinement: REFINEMENT_OPTIONS,
+            output: Output::default(),
+        }
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        at_least("n_values", &self.n_values, 1)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct SummaryRow {
     pub n: usize,
     pub en: f64,
+    pub diagnostics: SolveDiagnostics,
 }
 
 #[derive(Debug, Clone)]
@@ -15,10 +56,16 @@ struct CaseData {
     solution: Vec<f64>,
     residual: Vec<f64>,
     en: f64,
+    diagnostics: SolveDiagnostics,
+    refinement: Refinement,
 }
 
-fn build_problem(n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
-    assert!(n > 0, "n must be positive");
+fn build_problem(n: usize) -> Result<(TridiagonalMatrix, Vec<f64>)> {
+    if n == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "system size n must be positive",
+        )));
+    }
 
     let mut lower = vec![0.0; n];
     let mut diag = vec![0.0; n];
@@ -35,98 +82,62 @@ fn build_problem(n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
         }
     }
 
-    (lower, diag, upper, rhs)
+    let matrix = TridiagonalMatrix::from_padded(lower, diag, upper)?;
+    Ok((matrix, rhs))
 }
 
-fn solve_tridiagonal(
-    lower: &[f64],
-    mut diag: Vec<f64>,
-    upper: &[f64],
-    mut rhs: Vec<f64>,
-) -> Vec<f64> {
-    let n = diag.len();
-    assert_eq!(lower.len(), n, "lower diagonal length mismatch");
-    assert_eq!(upper.len(), n, "upper diagonal length mismatch");
-    assert_eq!(rhs.len(), n, "rhs length mismatch");
-
-    for i in 1..n {
-        let multiplier = lower[i] / diag[i - 1];
-        diag[i] -= multiplier * upper[i - 1];
-        rhs[i] -= multiplier * rhs[i - 1];
-    }
-
-    let mut x = vec![0.0; n];
-    x[n - 1] = rhs[n - 1] / diag[n - 1];
+fn solve_case(n: usize, options: RefinementOptions) -> Result<CaseData> {
+    let (matrix, rhs) = build_problem(n)?;
+    let lu = matrix.lu()?;
+    let solution = lu.solve(&rhs)?;
 
-    for i in (0..n - 1).rev() {
-        x[i] = (rhs[i] - upper[i] * x[i + 1]) / diag[i];
-    }
-
-    x
-}
-
-fn tridiagonal_matvec(lower: &[f64], diag: &[f64], upper: &[f64], y: &[f64]) -> Vec<f64> {
-    let n = y.len();
-    assert_eq!(lower.len(), n, "lower diagonal length mismatch");
-    assert_eq!(diag.len(), n, "diagonal length mismatch");
-    assert_eq!(upper.len(), n, "upper diagonal length mismatch");
-
-    let mut out = vec![0.0; n];
-    for i in 0..n {
-        let mut value = diag[i] * y[i];
-        if i > 0 {
-            value += lower[i] * y[i - 1];
-        }
-        if i + 1 < n {
-            value += upper[i] * y[i + 1];
-        }
-        out[i] = value;
-    }
-
-    out
-}
-
-fn vector_subtract(y: &[f64], z: &[f64]) -> Vec<f64> {
-    assert_eq!(y.len(), z.len(), "vector length mismatch");
-    y.iter().zip(z).map(|(yi, zi)| yi - zi).collect()
-}
-
-fn max_abs(values: &[f64]) -> f64 {
-    values.iter().copied().map(f64::abs).fold(0.0, f64::max)
-}
-
-fn solve_case(n: usize) -> CaseData {
-    let (lower, diag, upper, rhs) = build_problem(n);
-    let solution = solve_tridiagonal(&lower, diag, &upper, rhs);
-
-    let (lower_r, diag_r, upper_r, rhs_r) = build_problem(n);
-    let tx = tridiagonal_matvec(&lower_r, &diag_r, &upper_r, &solution);
-    let residual = vector_subtract(&rhs_r, &tx);
+    let tx = matrix.matvec(&solution)?;
+    let residual = vector_subtract(&rhs, &tx);
     let en = max_abs(&residual);
+    let diagnostics = matrix.diagnostics(&lu, &solution, &rhs)?;
+    let refinement = linalg::refine(&matrix, &matrix.lu_in::<f32>()?, &rhs, options)?;
 
-    CaseData {
+    Ok(CaseData {
         n,
         solution,
         residual,
         en,
-    }
+        diagnostics,
+        refinement,
+    })
 }
 
-fn generate_cases() -> Vec<CaseData> {
-    N_VALUES.into_iter().map(solve_case).collect()
+fn generate_cases(config: &Config) -> Result<Vec<CaseData>> {
+    config
+        .n_values
+        .iter()
+        .map(|&n| solve_case(n, config.refinement))
+        .collect()
 }
 
-fn write_summary_data(rows: &[SummaryRow]) {
-    let out_dir = String::from("data/ch2_6");
-    let n: Vec<f64> = rows.iter().map(|row| row.n as f64).collect();
-    let en: Vec<f64> = rows.iter().map(|row| row.en).collect();
-
-    util::write_data(&n, out_dir.clone(), String::from("summary__n"));
-    util::write_data(&en, out_dir, String::from("summary__en"));
+fn write_summary_data(out_dir: &str, rows: &[SummaryRow], export: &[Export]) -> Result<()> {
+    let diagnostic = |f: fn(&SolveDiagnostics) -> f64| -> Vec<f64> {
+        rows.iter().map(|row| f(&row.diagnostics)).collect()
+    };
+    let fields = [
+        Field::Int("n", rows.iter().map(|row| row.n as i64).collect()),
+        Field::Float("en", rows.iter().map(|row| row.en).collect()),
+        Field::Float("cond", diagnostic(|d| d.condition)),
+        Field::Float("eta", diagnostic(|d| d.normwise_backward)),
+        Field::Float("omega", diagnostic(|d| d.componentwise_backward)),
+        Field::Float("forward_bound", diagnostic(|d| d.forward_bound)),
+    ];
+
+    util::write_table(
+        &fields,
+        out_dir.to_string(),
+        String::from("summary"),
+        export,
+    )
 }
 
-fn write_profile_data(cases: &[CaseData]) {
-    let out_dir = String::from("data/ch2_6");
+fn write_profile_data(out_dir: &str, cases: &[CaseData]) -> Result<()> {
+    let out_dir = out_dir.to_string();
 
     for case in cases {
         let indices: Vec<f64> = (1..=case.n).map(|i| i as f64).collect();
@@ -136,66 +147,166 @@ fn write_profile_data(cases: &[CaseData]) {
             &indices,
             out_dir.clone(),
             format!("residual__n{}__i", case.n),
-        );
+        )?;
         util::write_data(
             &abs_residual,
             out_dir.clone(),
             format!("residual__n{}__abs", case.n),
-        );
+        )?;
         util::write_data(
             &case.solution,
             out_dir.clone(),
             format!("solution__n{}__x", case.n),
-        );
+        )?;
         util::write_data(
             &indices,
             out_dir.clone(),
             format!("solution__n{}__i", case.n),
-        );
+        )?;
     }
+    Ok(())
 }
 
-pub fn generate() -> io::Result<()> {
-    let cases = generate_cases();
+fn write_refinement_data(out_dir: &str, cases: &[CaseData]) -> Result<()> {
+    let out_dir = out_dir.to_string();
+
+    for case in cases {
+        let history = &case.refinement.history;
+        let fields = [
+            Field::Int(
+                "iteration",
+                history.iter().map(|step| step.iteration as i64).collect(),
+            ),
+            Field::Float(
+                "residual",
+                history.iter().map(|step| step.residual_norm).collect(),
+            ),
+            Field::Float(
+                "backward",
+                history.iter().map(|step| step.backward_error).collect(),
+            ),
+        ];
+        let refined_abs: Vec<f64> = case
+            .refinement
+            .residual
+            .iter()
+            .copied()
+            .map(f64::abs)
+            .collect();
+
+        util::write_records(&fields, out_dir.clone(), format!("refinement__n{}", case.n))?;
+        util::write_data(
+            &refined_abs,
+            out_dir.clone(),
+            format!("residual__n{}__refined_abs", case.n),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_tables(path: &str, summary: &[SummaryRow]) -> Result<()> {
+    let residuals = Table::new(
+        "Maximum residual component for the tridiagonal solves.",
+        "residuals",
+    )
+    .column("$n$", Format::Int, |row: &SummaryRow| row.n as f64)
+    .column(r"$e_n = \max_i |r_i|$", Format::Sci, |row| row.en);
+
+    let diagnostics = Table::new(
+        r"Estimated condition number, normwise ($\eta$) and componentwise ($\omega$) backward errors, and forward-error bound.",
+        "diagnostics",
+    )
+    .column("$n$", Format::Int, |row: &SummaryRow| row.n as f64)
+    .column(r"$\kappa_\infty(T)$", Format::Fixed(4), |row| {
+        row.diagnostics.condition
+    })
+    .column(r"$\eta$", Format::Sci, |row| row.diagnostics.normwise_backward)
+    .column(r"$\omega$", Format::Sci, |row| {
+        row.diagnostics.componentwise_backward
+    })
+    .column(
+        r"$\|T^{-1}\|_\infty \|r\|_\infty / \|x\|_\infty$",
+        Format::Sci,
+        |row| row.diagnostics.forward_bound,
+    );
+
+    TexFile::new()
+        .table(&residuals, summary)
+        .table(&diagnostics, summary)
+        .write(path)
+}
+
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_6");
+    let out_dir = paths.data.clone();
+    let cases = generate_cases(config)?;
     let summary: Vec<SummaryRow> = cases
         .iter()
         .map(|case| SummaryRow {
             n: case.n,
             en: case.en,
+            diagnostics: case.diagnostics,
         })
         .collect();
 
     for row in &summary {
-        println!("n = {:>3}, e_n = {:.10e}", row.n, row.en);
+        println!(
+            "n = {:>3}, e_n = {:.10e}, cond = {:.4e}, eta = {:.3e}, omega = {:.3e}, fwd <= {:.3e}",
+            row.n,
+            row.en,
+            row.diagnostics.condition,
+            row.diagnostics.normwise_backward,
+            row.diagnostics.componentwise_backward,
+            row.diagnostics.forward_bound
+        );
     }
 
-    write_summary_data(&summary);
-    write_profile_data(&cases);
+    for case in &cases {
+        let last = case
+            .refinement
+            .history
+            .last()
+            .expect("refinement records the initial solve");
+        println!(
+            "n = {:>3}, f32 LU + refinement: {} iterations, ||r|| = {:.3e}, eta = {:.3e}",
+            case.n,
+            case.refinement.iterations(),
+            last.residual_norm,
+            last.backward_error
+        );
+    }
 
-    util::plot("ch2_6")?;
-    util::run_python_script("scripts/ch2_6/make_tables.py")?;
-    util::copy_file(
-        "plots/ch2_6/residual_profiles.png",
-        "reports/ch2_6/figures/residual_profiles.png",
-    )?;
-    util::copy_file(
-        "plots/ch2_6/max_residual.png",
-        "reports/ch2_6/figures/max_residual.png",
+    write_summary_data(&out_dir, &summary, &config.output.export)?;
+    write_profile_data(&out_dir, &cases)?;
+    write_refinement_data(&out_dir, &cases)?;
+
+    if config.output.plot {
+        util::plot("ch2_6", &paths)?;
+    }
+    if !config.output.report {
+        return Ok(());
+    }
+    write_tables(&format!("{}/figures/tables.tex", paths.reports), &summary)?;
+    util::publish_figures(
+        &paths,
+        &[
+            "residual_profiles.png",
+            "max_residual.png",
+            "refinement.png",
+        ],
     )?;
-    util::build_report("reports/ch2_6", "2.6.pdf")
+    util::build_report("ch2_6", &paths, "2.6.pdf")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{
-        N_VALUES, build_problem, max_abs, solve_case, solve_tridiagonal, tridiagonal_matvec,
-        vector_subtract,
-    };
+    use super::{N_VALUES, REFINEMENT_OPTIONS, build_problem, solve_case};
+    use mat627::linalg::{max_abs, vector_subtract};
 
     #[test]
     fn solves_n3_reference_system() {
-        let (lower, diag, upper, rhs) = build_problem(3);
-        let x = solve_tridiagonal(&lower, diag, &upper, rhs);
+        let (matrix, rhs) = build_problem(3).expect("n is positive");
+        let x = matrix.solve(&rhs).expect("system is nonsingular");
         let expected = [4.0 / 9.0, 1.0 / 9.0, 2.0 / 9.0];
 
         for (xi, expected_i) in x.iter().copied().zip(expected) {
@@ -206,7 +317,7 @@ mod tests {
     #[test]
     fn matvec_and_subtraction_produce_small_residuals() {
         for n in N_VALUES {
-            let case = solve_case(n);
+            let case = solve_case(n, REFINEMENT_OPTIONS).expect("system is nonsingular");
             assert!(
                 max_abs(&case.residual) < 1.0e-12,
                 "residual too large for n={n}"
@@ -214,11 +325,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn diagnostics_show_backward_stable_well_conditioned_solves() {
+        for n in N_VALUES {
+            let diagnostics = solve_case(n, REFINEMENT_OPTIONS)
+                .expect("system is nonsingular")
+                .diagnostics;
+
+            // Every row is diagonally dominant by at least 1, so ||T^{-1}|| <= 1 and
+            // kappa(T) <= ||T|| = n + 2.
+            assert!(
+                diagnostics.condition <= (n + 2) as f64,
+                "condition too large for n={n}"
+            );
+            assert!(diagnostics.normwise_backward < 1.0e-15);
+            assert!(diagnostics.componentwise_backward < 1.0e-15);
+            assert!(diagnostics.forward_bound < 1.0e-14);
+        }
+    }
+
+    #[test]
+    fn mixed_precision_refinement_reaches_double_precision_residuals() {
+        for n in N_VALUES {
+            let case = solve_case(n, REFINEMENT_OPTIONS).expect("system is nonsingular");
+            let refinement = &case.refinement;
+            let first = refinement.history[0];
+            let last = refinement.history.last().unwrap();
+
+            assert!(refinement.converged, "refinement stalled for n={n}");
+            assert!(first.backward_error > 1.0e-10);
+            assert!(last.backward_error < 1.0e-15);
+            assert!(max_abs(&vector_subtract(&refinement.solution, &case.solution)) < 1.0e-14);
+        }
+    }
+
     #[test]
     fn tridiagonal_matvec_matches_problem_definition() {
-        let (lower, diag, upper, _) = build_problem(4);
+        let (matrix, _) = build_problem(4).expect("n is positive");
         let y = [1.0, -2.0, 0.5, 3.0];
-        let w = tridiagonal_matvec(&lower, &diag, &upper, &y);
+        let w = matrix.matvec(&y).expect("matching dimensions");
         let expected = [
             2.0 * y[0] + y[1],
             y[0] + 3.0 * y[1] + y[2],
@@ -1,5 +1,11 @@
-use crate::util;
-use std::io;
+use mat627::bvp::{self, Scheme};
+use mat627::config::{Export, Output, Validate, at_least};
+use mat627::convergence::{Convergence, ConvergenceStudy, Level, Sample};
+use mat627::error::Result;
+use mat627::report::TexFile;
+use mat627::report::table::{Format, Table};
+use mat627::util::{self, Field};
+use serde::Deserialize;
 
 const PART1_N: usize = 5;
 const CONVERGENCE_N_VALUES: [usize; 6] = [5, 10, 20, 40, 80, 160];
@@ -8,6 +14,38 @@ const RATE_TOL: f64 = 1.0e-12;
 
 type ScalarFn = fn(f64) -> f64;
 
+/// Parameters of the Section 2.7 study, read from `config/ch2_7.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Subintervals for the Part 1 table.
+    pub part1_n: usize,
+    /// Subinterval counts for Parts 2-4.
+    pub n_values: Vec<usize>,
+    /// Steps of the grid the exact solution is plotted on.
+    pub fine_steps: usize,
+    pub output: Output,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            part1_n: PART1_N,
+            n_values: CONVERGENCE_N_VALUES.to_vec(),
+            fine_steps: FINE_STEPS,
+            output: Output::default(),
+        }
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), String> {
+        at_least("part1_n", &[self.part1_n], 2)?;
+        at_least("n_values", &self.n_values, 2)?;
+        at_least("fine_steps", &[self.fine_steps], 1)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct PointRow {
     pub x: f64,
@@ -16,24 +54,12 @@ pub struct PointRow {
     pub err: f64,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct SummaryRow {
-    pub n: usize,
-    pub h: f64,
-    pub err: f64,
-    pub rate: Option<f64>,
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Scheme {
-    ReactionDiffusion,
-    AdvectionReactionBackward,
-}
-
 #[derive(Clone, Copy)]
 struct Case {
     slug: &'static str,
     title: &'static str,
+    /// One-sentence description printed above the table.
+    problem: &'static str,
     exact: ScalarFn,
     rhs: ScalarFn,
     scheme: Scheme,
@@ -68,6 +94,7 @@ fn convergence_cases() -> [Case; 3] {
         Case {
             slug: "part2",
             title: "Part 2",
+            problem: r"Discrete error for $-u'' + u = 4e^{-x} - 4xe^{-x}$ with exact solution $u(x)=x(1-x)e^{-x}$.",
             exact: exact_exp,
             rhs: rhs_exp,
             scheme: Scheme::ReactionDiffusion,
@@ -75,6 +102,7 @@ fn convergence_cases() -> [Case; 3] {
         Case {
             slug: "part3",
             title: "Part 3",
+            problem: r"Discrete error for $-u'' + u = 2 + x - x^2$ with exact solution $u(x)=x(1-x)$.",
             exact: exact_poly,
             rhs: rhs_poly,
             scheme: Scheme::ReactionDiffusion,
@@ -82,6 +110,7 @@ fn convergence_cases() -> [Case; 3] {
         Case {
             slug: "part4",
             title: "Part 4",
+            problem: r"Discrete error for $-u'' + u' + u = -(x-1)(x^2-11)$ using the backward difference $(u(x)-u(x-h))/h$ for $u'$.",
             exact: exact_grad,
             rhs: rhs_grad,
             scheme: Scheme::AdvectionReactionBackward,
@@ -89,119 +118,16 @@ fn convergence_cases() -> [Case; 3] {
     ]
 }
 
-fn observed_rate(prev_err: f64, err: f64) -> Option<f64> {
-    if prev_err.abs() < RATE_TOL || err.abs() < RATE_TOL {
-        None
-    } else {
-        Some((prev_err.abs() / err.abs()).ln() / 2.0_f64.ln())
-    }
-}
-
-fn build_system(
-    n: usize,
-    rhs: ScalarFn,
-    scheme: Scheme,
-) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
-    assert!(n >= 2, "n must be at least 2");
-
-    let m = n - 1;
-    let h = 1.0 / n as f64;
-
-    let mut lower = vec![0.0; m];
-    let mut diag = vec![0.0; m];
-    let mut upper = vec![0.0; m];
-    let mut load = vec![0.0; m];
-
-    for i in 0..m {
-        let x = (i + 1) as f64 * h;
-        load[i] = h * h * rhs(x);
-
-        match scheme {
-            Scheme::ReactionDiffusion => {
-                if i > 0 {
-                    lower[i] = -1.0;
-                }
-                diag[i] = 2.0 + h * h;
-                if i + 1 < m {
-                    upper[i] = -1.0;
-                }
-            }
-            Scheme::AdvectionReactionBackward => {
-                if i > 0 {
-                    lower[i] = -(1.0 + h);
-                }
-                diag[i] = 2.0 + h + h * h;
-                if i + 1 < m {
-                    upper[i] = -1.0;
-                }
-            }
-        }
-    }
-
-    (lower, diag, upper, load)
-}
-
-fn solve_tridiagonal(
-    lower: &[f64],
-    mut diag: Vec<f64>,
-    upper: &[f64],
-    mut rhs: Vec<f64>,
-) -> Vec<f64> {
-    let n = diag.len();
-    assert!(n > 0, "system must be non-empty");
-    assert_eq!(lower.len(), n, "lower diagonal length mismatch");
-    assert_eq!(upper.len(), n, "upper diagonal length mismatch");
-    assert_eq!(rhs.len(), n, "rhs length mismatch");
-
-    for i in 1..n {
-        let multiplier = lower[i] / diag[i - 1];
-        diag[i] -= multiplier * upper[i - 1];
-        rhs[i] -= multiplier * rhs[i - 1];
-    }
-
-    let mut x = vec![0.0; n];
-    x[n - 1] = rhs[n - 1] / diag[n - 1];
-
-    for i in (0..n - 1).rev() {
-        x[i] = (rhs[i] - upper[i] * x[i + 1]) / diag[i];
-    }
-
-    x
-}
-
-fn solve_case(n: usize, rhs: ScalarFn, scheme: Scheme) -> Vec<(f64, f64)> {
-    let h = 1.0 / n as f64;
-    let (lower, diag, upper, load) = build_system(n, rhs, scheme);
-    let interior = solve_tridiagonal(&lower, diag, &upper, load);
-
-    let mut values = Vec::with_capacity(n + 1);
-    values.push((0.0, 0.0));
-    for (i, ui) in interior.into_iter().enumerate() {
-        values.push(((i + 1) as f64 * h, ui));
-    }
-    values.push((1.0, 0.0));
-    values
-}
-
-fn max_error(solution: &[(f64, f64)], exact: ScalarFn) -> f64 {
-    solution
-        .iter()
-        .copied()
-        .map(|(x, approx)| (exact(x) - approx).abs())
-        .fold(0.0, f64::max)
-}
-
-fn fine_grid() -> Vec<f64> {
-    (0..=FINE_STEPS)
-        .map(|i| i as f64 / FINE_STEPS as f64)
-        .collect()
+fn fine_grid(steps: usize) -> Vec<f64> {
+    (0..=steps).map(|i| i as f64 / steps as f64).collect()
 }
 
-pub fn part1_rows() -> Vec<PointRow> {
-    solve_case(PART1_N, rhs_exp, Scheme::ReactionDiffusion)
+pub fn part1_rows(config: &Config) -> Result<Vec<PointRow>> {
+    let n = config.part1_n;
+    let rows = bvp::solve(n, rhs_exp, Scheme::ReactionDiffusion)?
         .into_iter()
         .skip(1)
-        .take(PART1_N - 1)
+        .take(n - 1)
         .map(|(x, approx)| {
             let exact = exact_exp(x);
             PointRow {
@@ -211,90 +137,103 @@ pub fn part1_rows() -> Vec<PointRow> {
                 err: exact - approx,
             }
         })
-        .collect()
-}
+        .collect();
 
-fn summarize(case: Case) -> Vec<SummaryRow> {
-    CONVERGENCE_N_VALUES
-        .iter()
-        .copied()
-        .map(|n| {
-            let solution = solve_case(n, case.rhs, case.scheme);
-            let h = 1.0 / n as f64;
-            let err = max_error(&solution, case.exact);
-            (n, h, err)
-        })
-        .scan(None, |prev_err: &mut Option<f64>, (n, h, err)| {
-            let rate = prev_err.and_then(|prev| observed_rate(prev, err));
-            *prev_err = Some(err);
+    Ok(rows)
+}
 
-            Some(SummaryRow { n, h, err, rate })
-        })
-        .collect()
+fn summarize(config: &Config, case: Case) -> Result<Convergence> {
+    ConvergenceStudy::new().rate_floor(RATE_TOL).try_run(
+        config.n_values.iter().map(|&n| (n, 1.0 / n as f64)),
+        |n, _| {
+            let solution = bvp::solve(n, case.rhs, case.scheme)?;
+            Ok(Sample::err(bvp::max_error(&solution, case.exact)))
+        },
+    )
 }
 
-pub fn part2_summary() -> Vec<SummaryRow> {
-    summarize(convergence_cases()[0])
+pub fn part2_summary(config: &Config) -> Result<Convergence> {
+    summarize(config, convergence_cases()[0])
 }
 
-pub fn part3_summary() -> Vec<SummaryRow> {
-    summarize(convergence_cases()[1])
+pub fn part3_summary(config: &Config) -> Result<Convergence> {
+    summarize(config, convergence_cases()[1])
 }
 
-pub fn part4_summary() -> Vec<SummaryRow> {
-    summarize(convergence_cases()[2])
+pub fn part4_summary(config: &Config) -> Result<Convergence> {
+    summarize(config, convergence_cases()[2])
 }
 
-fn write_part1_data(rows: &[PointRow]) {
-    let out_dir = String::from("data/ch2_7");
-    let x: Vec<f64> = rows.iter().map(|row| row.x).collect();
-    let approx: Vec<f64> = rows.iter().map(|row| row.approx).collect();
-    let exact: Vec<f64> = rows.iter().map(|row| row.exact).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-
-    util::write_data(&x, out_dir.clone(), String::from("part1__x"));
-    util::write_data(&approx, out_dir.clone(), String::from("part1__approx"));
-    util::write_data(&exact, out_dir.clone(), String::from("part1__exact"));
-    util::write_data(&err, out_dir, String::from("part1__err"));
+fn write_part1_data(out_dir: &str, rows: &[PointRow], export: &[Export]) -> Result<()> {
+    let fields = [
+        Field::Float("x", rows.iter().map(|row| row.x).collect()),
+        Field::Float("approx", rows.iter().map(|row| row.approx).collect()),
+        Field::Float("exact", rows.iter().map(|row| row.exact).collect()),
+        Field::Float("err", rows.iter().map(|row| row.err).collect()),
+    ];
+
+    util::write_table(&fields, out_dir.to_string(), String::from("part1"), export)
 }
 
-fn write_plot_data() {
-    let out_dir = String::from("data/ch2_7");
-    let nodes = solve_case(PART1_N, rhs_exp, Scheme::ReactionDiffusion);
+fn write_plot_data(config: &Config, out_dir: &str) -> Result<()> {
+    let out_dir = out_dir.to_string();
+    let nodes = bvp::solve(config.part1_n, rhs_exp, Scheme::ReactionDiffusion)?;
     let nodes_x: Vec<f64> = nodes.iter().map(|(x, _)| *x).collect();
     let nodes_y: Vec<f64> = nodes.iter().map(|(_, y)| *y).collect();
 
-    let x = fine_grid();
+    let x = fine_grid(config.fine_steps);
     let exact: Vec<f64> = x.iter().copied().map(exact_exp).collect();
 
-    util::write_data(&x, out_dir.clone(), String::from("plot__x"));
-    util::write_data(&exact, out_dir.clone(), String::from("plot__exact"));
-    util::write_data(&nodes_x, out_dir.clone(), String::from("plot__nodes_x"));
-    util::write_data(&nodes_y, out_dir, String::from("plot__nodes_y"));
+    util::write_data(&x, out_dir.clone(), String::from("plot__x"))?;
+    util::write_data(&exact, out_dir.clone(), String::from("plot__exact"))?;
+    util::write_data(&nodes_x, out_dir.clone(), String::from("plot__nodes_x"))?;
+    util::write_data(&nodes_y, out_dir, String::from("plot__nodes_y"))?;
+    Ok(())
 }
 
-fn write_summary_data(name: &str, rows: &[SummaryRow]) {
-    let out_dir = String::from("data/ch2_7");
-    let n: Vec<f64> = rows.iter().map(|row| row.n as f64).collect();
-    let h: Vec<f64> = rows.iter().map(|row| row.h).collect();
-    let err: Vec<f64> = rows.iter().map(|row| row.err).collect();
-    let rate: Vec<f64> = rows
-        .iter()
-        .map(|row| row.rate.unwrap_or(f64::NAN))
-        .collect();
+fn write_tables(path: &str, part1: &[PointRow], summaries: &[(Case, Convergence)]) -> Result<()> {
+    let mut tex = TexFile::new();
+
+    tex.line(r"\section{Part 1}").table(
+        &Table::new(
+            r"Finite-difference solution for $-u'' + u = 4e^{-x} - 4xe^{-x}$ with $N=5$.",
+            "part1",
+        )
+        .compact(7)
+        .column("$x_k$", Format::Fixed(1), |row: &PointRow| row.x)
+        .column("$U_k$", Format::Sci, |row| row.approx)
+        .column("$u(x_k)$", Format::Sci, |row| row.exact)
+        .column("$u(x_k)-U_k$", Format::Sci, |row| row.err),
+        part1,
+    );
+
+    for (case, summary) in summaries {
+        let table = Table::new(
+            format!("Maximum nodal error for {}.", case.title.to_lowercase()),
+            case.slug,
+        )
+        .compact(7)
+        .column("$N$", Format::Int, |row: &Level| row.n as f64)
+        .column("$h$", Format::Trimmed(5), |row| row.h)
+        .column(r"$E_h = \max_k |u(x_k)-U_k|$", Format::Sci, |row| row.err)
+        .rate("Rate", Format::Fixed(6), |row| row.rate);
+
+        tex.line(format!("\\section{{{}}}", case.title))
+            .line(format!("\\noindent {}", case.problem))
+            .table(&table, &summary.levels);
+    }
 
-    util::write_data(&n, out_dir.clone(), format!("{name}__n"));
-    util::write_data(&h, out_dir.clone(), format!("{name}__h"));
-    util::write_data(&err, out_dir.clone(), format!("{name}__err"));
-    util::write_data(&rate, out_dir, format!("{name}__rate"));
+    tex.write(path)
 }
 
-pub fn generate() -> io::Result<()> {
-    let part1 = part1_rows();
+pub fn generate(config: &Config) -> Result<()> {
+    let paths = config.output.paths("ch2_7");
+    let out_dir = paths.data.clone();
+    let part1 = part1_rows(config)?;
     let summaries = [
-        (convergence_cases()[0], part2_summary()),
-        (convergence_cases()[1], part3_summary()),
-        (convergence_cases()[2], part4_summary()),
+        (convergence_cases()[0], part2_summary(config)?),
+        (convergence_cases()[1], part3_summary(config)?),
+        (convergence_cases()[2], part4_summary(config)?),
     ];
 
     for row in &part1 {
@@ -304,9 +243,9 @@ pub fn generate() -> io::Result<()> {
         );
     }
 
-    for (case, rows) in &summaries {
+    for (case, summary) in &summaries {
         println!("{}", case.title);
-        for row in rows {
+        for row in &summary.levels {
             println!(
                 "  N = {:>3}, h = {:.5}, E_h = {:.10e}, rate = {}",
                 row.n,
@@ -317,31 +256,39 @@ pub fn generate() -> io::Result<()> {
                     .unwrap_or_else(|| String::from("--"))
             );
         }
+        if let Some(order) = summary.fitted_order {
+            println!("  fitted order = {order:.6}");
+        }
     }
 
-    write_part1_data(&part1);
-    write_plot_data();
-    for (case, rows) in &summaries {
-        write_summary_data(case.slug, rows);
+    write_part1_data(&out_dir, &part1, &config.output.export)?;
+    write_plot_data(config, &out_dir)?;
+    for (case, summary) in &summaries {
+        summary.write(&out_dir, case.slug, &config.output.export)?;
     }
 
-    util::plot("ch2_7")?;
-    util::run_python_script("scripts/ch2_7/make_tables.py")?;
-    util::copy_file(
-        "plots/ch2_7/approximation.png",
-        "reports/ch2_7/figures/approximation.png",
+    if config.output.plot {
+        util::plot("ch2_7", &paths)?;
+    }
+    if !config.output.report {
+        return Ok(());
+    }
+    write_tables(
+        &format!("{}/figures/tables.tex", paths.reports),
+        &part1,
+        &summaries,
     )?;
-    util::copy_file("plots/ch2_7/error.png", "reports/ch2_7/figures/error.png")?;
-    util::build_report("reports/ch2_7", "2.7.pdf")
+    util::publish_figures(&paths, &["approximation.png", "error.png"])?;
+    util::build_report("ch2_7", &paths, "2.7.pdf")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{PART1_N, part1_rows, part2_summary, part3_summary, part4_summary};
+    use super::{Config, PART1_N, part1_rows, part2_summary, part3_summary, part4_summary};
 
     #[test]
     fn part1_has_expected_mesh_points() {
-        let rows = part1_rows();
+        let rows = part1_rows(&Config::default()).expect("Part 1 solve failed");
 
         assert_eq!(rows.len(), PART1_N - 1);
         assert!((rows[0].x - 0.2).abs() < 1.0e-12);
@@ -350,25 +297,28 @@ mod tests {
 
     #[test]
     fn part2_converges_second_order() {
-        let rows = part2_summary();
+        let summary = part2_summary(&Config::default()).expect("Part 2 solve failed");
+        let rows = &summary.levels;
         let last = rows.last().expect("missing Part 2 rows");
 
         assert!(rows[1].err < rows[0].err);
         assert!((last.rate.expect("missing Part 2 rate") - 2.0).abs() < 0.06);
+        assert!((summary.fitted_order.expect("no Part 2 fit") - 2.0).abs() < 0.06);
     }
 
     #[test]
     fn part3_is_exact_to_roundoff() {
-        let rows = part3_summary();
+        let summary = part3_summary(&Config::default()).expect("Part 3 solve failed");
 
-        for row in rows {
+        for row in summary.levels {
             assert!(row.err < 1.0e-12, "Part 3 error too large for N={}", row.n);
         }
     }
 
     #[test]
     fn part4_converges_first_order() {
-        let rows = part4_summary();
+        let summary = part4_summary(&Config::default()).expect("Part 4 solve failed");
+        let rows = &summary.levels;
         let last = rows.last().expect("missing Part 4 rows");
 
         assert!(rows[1].err < rows[0].err);
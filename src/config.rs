@@ -0,0 +1,332 @@
+//! Optional per-chapter experiment configuration.
+//!
+//! Each chapter driver describes its parameters with a `Config` struct whose
+//! `Default` reproduces the assignment. A file `config/<chapter>.toml`, when
+//! present, overrides any subset of the fields; missing files and missing keys
+//! fall back to the defaults. Every loaded config is then checked with
+//! [`Validate`], so out-of-range values are reported by file and key instead
+//! of tripping an assert inside a solver.
+
+use crate::error::Mat627Error;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Directory searched for `<chapter>.toml`.
+pub const CONFIG_DIR: &str = "config";
+
+/// Output settings shared by every chapter, read from the `[output]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Output {
+    /// Where the `.npy` files go; `data/<chapter>` when unset.
+    pub data_dir: Option<String>,
+    /// Where the figures go; `plots/<chapter>` when unset.
+    pub plot_dir: Option<String>,
+    /// Where tables, figures and the PDF go; `reports/<chapter>` when unset.
+    pub report_dir: Option<String>,
+    /// Run the chapter's plotting script.
+    pub plot: bool,
+    /// Generate tables and build the LaTeX report.
+    pub report: bool,
+    /// Plain-text copies of every result table, written next to the `.npy` files.
+    pub export: Vec<Export>,
+}
+
+/// Plain-text format a result table can be exported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Export {
+    /// `<name>.csv` with a header row; missing values are empty cells.
+    Csv,
+    /// `<name>.jsonl` with one object per row; missing values are `null`.
+    Jsonl,
+}
+
+impl Export {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl fmt::Display for Export {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for Export {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "jsonl" | "json" => Ok(Self::Jsonl),
+            other => Err(format!(
+                "unknown export format '{other}' (expected csv or jsonl)"
+            )),
+        }
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            plot_dir: None,
+            report_dir: None,
+            plot: true,
+            report: true,
+            export: Vec::new(),
+        }
+    }
+}
+
+impl Output {
+    /// The configured data directory, or `data/<chapter>`.
+    pub fn data_dir(&self, chapter: &str) -> String {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(|| format!("data/{chapter}"))
+    }
+
+    /// Resolve every output directory for `chapter`.
+    pub fn paths(&self, chapter: &str) -> Paths {
+        let or_default = |dir: &Option<String>, root: &str| {
+            dir.clone().unwrap_or_else(|| format!("{root}/{chapter}"))
+        };
+
+        Paths {
+            data: self.data_dir(chapter),
+            plots: or_default(&self.plot_dir, "plots"),
+            reports: or_default(&self.report_dir, "reports"),
+        }
+    }
+
+    /// Place all three directories under `root` (`root/data/<chapter>`, ...).
+    pub fn relocate(&mut self, root: &Path, chapter: &str) {
+        let under = |kind: &str| Some(root.join(kind).join(chapter).display().to_string());
+        self.data_dir = under("data");
+        self.plot_dir = under("plots");
+        self.report_dir = under("reports");
+    }
+}
+
+/// Resolved output directories of one chapter run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    pub data: String,
+    pub plots: String,
+    pub reports: String,
+}
+
+impl Paths {
+    pub fn create_dirs(&self) -> io::Result<()> {
+        for dir in [&self.data, &self.plots, &self.reports] {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Path of the config file for `chapter`.
+pub fn path(chapter: &str) -> PathBuf {
+    Path::new(CONFIG_DIR).join(format!("{chapter}.toml"))
+}
+
+/// Range checks a chapter config runs after parsing.
+pub trait Validate {
+    /// `Err("<key>: <reason>")` for the first offending key.
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Load `config/<chapter>.toml`, or the defaults if it does not exist.
+pub fn load<T: DeserializeOwned + Default + Validate>(chapter: &str) -> crate::error::Result<T> {
+    load_from(&path(chapter))
+}
+
+/// Load and validate a config file, or the defaults if `path` does not exist.
+///
+/// Unknown keys and type mismatches are reported as `InvalidData` with the file
+/// name, values rejected by [`Validate`] as `InvalidInput` with the file and key.
+pub fn load_from<T: DeserializeOwned + Default + Validate>(path: &Path) -> crate::error::Result<T> {
+    let config = if path.exists() {
+        let text = fs::read_to_string(path)?;
+        parse(&text).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {err}", path.display()),
+            )
+        })?
+    } else {
+        T::default()
+    };
+
+    config
+        .validate()
+        .map_err(|message| Mat627Error::InvalidInput(format!("{}: {message}", path.display())))?;
+    Ok(config)
+}
+
+/// Every value in `values` is at least `min`.
+pub fn at_least(key: &str, values: &[usize], min: usize) -> Result<(), String> {
+    match values.iter().find(|&&v| v < min) {
+        Some(v) => Err(format!("{key}: {v} is less than {min}")),
+        None => Ok(()),
+    }
+}
+
+/// Every value in `values` is at most `max`.
+pub fn at_most(key: &str, values: &[usize], max: usize) -> Result<(), String> {
+    match values.iter().find(|&&v| v > max) {
+        Some(v) => Err(format!("{key}: {v} is greater than {max}")),
+        None => Ok(()),
+    }
+}
+
+/// Every value in `values` is finite and strictly positive.
+pub fn positive(key: &str, values: &[f64]) -> Result<(), String> {
+    match values.iter().find(|v| !(v.is_finite() && **v > 0.0)) {
+        Some(v) => Err(format!("{key}: {v} is not a positive number")),
+        None => Ok(()),
+    }
+}
+
+/// `[a, b]` is a finite interval with `a < b`.
+pub fn interval(key: &str, [a, b]: [f64; 2]) -> Result<(), String> {
+    if a.is_finite() && b.is_finite() && a < b {
+        Ok(())
+    } else {
+        Err(format!("{key}: [{a}, {b}] is not an interval with a < b"))
+    }
+}
+
+/// Every step in `steps` is positive and fits a whole number of times into `span`.
+pub fn divides(key: &str, steps: &[f64], span: f64) -> Result<(), String> {
+    positive(key, steps)?;
+    for &h in steps {
+        let n = span / h;
+        if n.round() < 1.0 || (n - n.round()).abs() >= 1.0e-12 {
+            return Err(format!("{key}: {h} does not divide the span {span}"));
+        }
+    }
+    Ok(())
+}
+
+/// Parse config text, filling missing keys from the defaults.
+pub fn parse<T: DeserializeOwned>(text: &str) -> Result<T, toml::de::Error> {
+    toml::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Export, Output, Paths, Validate, at_least, divides, load_from, parse, positive};
+    use crate::error::Mat627Error;
+    use serde::Deserialize;
+    use std::fs;
+    use std::path::Path;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Example {
+        n_values: Vec<usize>,
+        tol: f64,
+        output: Output,
+    }
+
+    impl Default for Example {
+        fn default() -> Self {
+            Self {
+                n_values: vec![1, 2, 4],
+                tol: 1.0e-6,
+                output: Output::default(),
+            }
+        }
+    }
+
+    impl Validate for Example {
+        fn validate(&self) -> Result<(), String> {
+            at_least("n_values", &self.n_values, 1)?;
+            positive("tol", &[self.tol])
+        }
+    }
+
+    #[test]
+    fn missing_keys_keep_defaults() {
+        let config: Example = parse("tol = 1e-8\n[output]\nplot = false\n").unwrap();
+
+        assert_eq!(config.n_values, vec![1, 2, 4]);
+        assert_eq!(config.tol, 1.0e-8);
+        assert!(!config.output.plot);
+        assert!(config.output.report);
+        assert_eq!(config.output.data_dir("ch0"), "data/ch0");
+    }
+
+    #[test]
+    fn relocate_moves_every_directory() {
+        let mut output = Output::default();
+        assert_eq!(output.paths("ch0").plots, "plots/ch0");
+
+        output.relocate(Path::new("/tmp/run"), "ch0");
+        assert_eq!(
+            output.paths("ch0"),
+            Paths {
+                data: String::from("/tmp/run/data/ch0"),
+                plots: String::from("/tmp/run/plots/ch0"),
+                reports: String::from("/tmp/run/reports/ch0"),
+            }
+        );
+    }
+
+    #[test]
+    fn export_formats_parse_from_toml_and_the_command_line() {
+        let config: Example = parse("[output]\nexport = [\"csv\", \"jsonl\"]\n").unwrap();
+        assert_eq!(config.output.export, vec![Export::Csv, Export::Jsonl]);
+
+        assert_eq!("JSON".parse::<Export>(), Ok(Export::Jsonl));
+        assert!("parquet".parse::<Export>().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(parse::<Example>("n_value = [1]\n").is_err());
+    }
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let config: Example = load_from(Path::new("config/does_not_exist.toml")).unwrap();
+
+        assert_eq!(config, Example::default());
+    }
+
+    #[test]
+    fn out_of_range_values_name_the_file_and_key() {
+        let dir = std::env::temp_dir().join(format!("mat627-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("example.toml");
+        fs::write(&path, "n_values = [4, 0]\n").unwrap();
+
+        let err = load_from::<Example>(&path).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(err, Mat627Error::InvalidInput(_)));
+        let message = err.to_string();
+        assert!(message.contains("example.toml: n_values: 0"), "{message}");
+    }
+
+    #[test]
+    fn steps_must_divide_the_span() {
+        assert!(divides("h", &[0.5, 0.25, 0.1], 1.0).is_ok());
+        assert!(divides("h", &[0.3], 1.0).is_err());
+        assert!(divides("h", &[2.0], 1.0).is_err());
+        assert!(divides("h", &[-0.5], 1.0).is_err());
+        assert!(positive("tol", &[f64::NAN]).is_err());
+    }
+}
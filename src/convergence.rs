@@ -0,0 +1,248 @@
+//! Convergence studies: errors over a refinement sequence and the orders they imply.
+//!
+//! A [`ConvergenceStudy`] measures an error functional at each refinement
+//! `(n, h)`, computes the observed order between consecutive levels from
+//! their actual step ratio, and fits a least-squares order over the
+//! asymptotic range. The resulting [`Convergence`] is the summary type every
+//! chapter writes and tabulates.
+
+use crate::config::Export;
+use crate::error::Result;
+use crate::parallel;
+use crate::util::{self, Field};
+use std::convert::Infallible;
+use std::ops::Range;
+
+/// What the error functional returns for one refinement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// The approximation the error was measured on, when there is a single one.
+    pub approx: Option<f64>,
+    pub err: f64,
+}
+
+impl Sample {
+    /// An approximation and its error.
+    pub fn approx(approx: f64, err: f64) -> Self {
+        Self {
+            approx: Some(approx),
+            err,
+        }
+    }
+
+    /// An error with no single approximation behind it, e.g. a max norm.
+    pub fn err(err: f64) -> Self {
+        Self { approx: None, err }
+    }
+}
+
+/// One row of a convergence table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+    /// Refinement count: subintervals, steps or nodes.
+    pub n: usize,
+    pub h: f64,
+    pub approx: Option<f64>,
+    pub err: f64,
+    /// Observed order against the previous level, `None` on the first level
+    /// or when either error is at or below the study's floor.
+    pub rate: Option<f64>,
+}
+
+/// The levels of a study with their observed and fitted orders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Convergence {
+    pub levels: Vec<Level>,
+    /// Least-squares slope of `ln|err|` against `ln h` over `fit`.
+    pub fitted_order: Option<f64>,
+    /// The levels the order was fitted over.
+    pub fit: Range<usize>,
+}
+
+impl Convergence {
+    /// Record-array columns `n`, `h`, `approx` (when every level has one),
+    /// `err` and `rate`.
+    pub fn fields(&self) -> Vec<Field> {
+        let levels = &self.levels;
+        let mut fields = vec![
+            Field::Int("n", levels.iter().map(|l| l.n as i64).collect()),
+            Field::Float("h", levels.iter().map(|l| l.h).collect()),
+        ];
+        if let Some(approx) = levels.iter().map(|l| l.approx).collect::<Option<Vec<_>>>() {
+            fields.push(Field::Float("approx", approx));
+        }
+        fields.push(Field::Float("err", levels.iter().map(|l| l.err).collect()));
+        fields.push(Field::Optional(
+            "rate",
+            levels.iter().map(|l| l.rate).collect(),
+        ));
+        fields
+    }
+
+    /// Write [`Convergence::fields`] with [`util::write_table`].
+    pub fn write(&self, dir: &str, fname: &str, export: &[Export]) -> Result<()> {
+        util::write_table(&self.fields(), dir.to_string(), fname.to_string(), export)
+    }
+}
+
+/// Settings for turning measured errors into orders.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ConvergenceStudy {
+    floor: f64,
+    fit_last: Option<usize>,
+}
+
+impl ConvergenceStudy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat errors with `|err| <= floor` as exact: they get no observed order
+    /// and are left out of the fit. The default floor is zero.
+    pub fn rate_floor(mut self, floor: f64) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// Fit the order over at most the last `levels` levels of the asymptotic range.
+    pub fn fit_last(mut self, levels: usize) -> Self {
+        self.fit_last = Some(levels);
+        self
+    }
+
+    /// Measure `error` at each refinement `(n, h)`.
+    ///
+    /// The levels are independent, so with the `parallel` feature they are
+    /// measured concurrently; see [`crate::parallel`].
+    pub fn run(
+        &self,
+        refinements: impl IntoIterator<Item = (usize, f64)>,
+        error: impl Fn(usize, f64) -> Sample + Sync + Send,
+    ) -> Convergence {
+        match self.try_run(refinements, |n, h| Ok::<_, Infallible>(error(n, h))) {
+            Ok(convergence) => convergence,
+            Err(never) => match never {},
+        }
+    }
+
+    /// [`ConvergenceStudy::run`] with a fallible error functional.
+    pub fn try_run<E: Send>(
+        &self,
+        refinements: impl IntoIterator<Item = (usize, f64)>,
+        error: impl Fn(usize, f64) -> std::result::Result<Sample, E> + Sync + Send,
+    ) -> std::result::Result<Convergence, E> {
+        let refinements: Vec<(usize, f64)> = refinements.into_iter().collect();
+        let levels = parallel::try_map(&refinements, |&(n, h)| {
+            let sample = error(n, h)?;
+            Ok(Level {
+                n,
+                h,
+                approx: sample.approx,
+                err: sample.err,
+                rate: None,
+            })
+        })?;
+        Ok(self.summarize(levels))
+    }
+
+    /// Fill in the observed orders of already measured levels and fit the
+    /// asymptotic order.
+    ///
+    /// The asymptotic range runs from the first level with a usable error to
+    /// the level with the smallest one; past it, rounding error dominates.
+    pub fn summarize(&self, mut levels: Vec<Level>) -> Convergence {
+        let mut prev: Option<(f64, f64)> = None;
+        for level in &mut levels {
+            level.rate = prev.and_then(|coarse| self.observed_order(coarse, (level.h, level.err)));
+            prev = Some((level.h, level.err));
+        }
+
+        let usable = |level: &Level| level.err.is_finite() && level.err.abs() > self.floor;
+        let start = levels.iter().position(usable).unwrap_or(levels.len());
+        let end = levels
+            .iter()
+            .enumerate()
+            .filter(|(_, level)| usable(level))
+            .min_by(|(_, a), (_, b)| a.err.abs().total_cmp(&b.err.abs()))
+            .map_or(start, |(i, _)| i + 1);
+        let start = match self.fit_last {
+            Some(count) => start.max(end.saturating_sub(count)),
+            None => start,
+        };
+        let points: Vec<(f64, f64)> = levels[start..end]
+            .iter()
+            .filter(|level| usable(level))
+            .map(|level| (level.h.ln(), level.err.abs().ln()))
+            .collect();
+
+        Convergence {
+            levels,
+            fitted_order: least_squares_slope(&points),
+            fit: start..end,
+        }
+    }
+
+    /// `ln(|e_coarse| / |e_fine|) / ln(h_coarse / h_fine)` for `(h, err)` pairs.
+    pub fn observed_order(&self, (h0, e0): (f64, f64), (h1, e1): (f64, f64)) -> Option<f64> {
+        let (a, b) = (e0.abs(), e1.abs());
+        if a <= self.floor || b <= self.floor || h0 == h1 {
+            None
+        } else {
+            Some((a / b).ln() / (h0 / h1).ln())
+        }
+    }
+}
+
+/// Slope of the least-squares line through `points`, `None` with fewer than two
+/// distinct abscissae.
+fn least_squares_slope(points: &[(f64, f64)]) -> Option<f64> {
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    (points.len() >= 2 && sxx > 0.0).then(|| sxy / sxx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConvergenceStudy, Sample};
+
+    #[test]
+    fn observed_orders_use_the_actual_step_ratio() {
+        // err = h^2 on steps shrinking by 3, then by 2.
+        let hs = [0.9, 0.3, 0.15];
+        let study = ConvergenceStudy::new()
+            .run(hs.iter().enumerate().map(|(i, &h)| (i, h)), |_, h| {
+                Sample::err(h * h)
+            });
+
+        assert_eq!(study.levels[0].rate, None);
+        for level in &study.levels[1..] {
+            assert!((level.rate.unwrap() - 2.0).abs() < 1.0e-12);
+        }
+        assert!((study.fitted_order.unwrap() - 2.0).abs() < 1.0e-12);
+        assert_eq!(study.fit, 0..3);
+    }
+
+    #[test]
+    fn fit_stops_at_the_smallest_error_and_skips_the_floor() {
+        // Truncation error h^2 until rounding takes over at the fourth level.
+        let errs = [1.0e-2, 2.5e-3, 6.25e-4, 1.0e-3, 0.0];
+        let study = ConvergenceStudy::new().rate_floor(1.0e-12).run(
+            (0..errs.len()).map(|i| (1 << i, 0.5_f64.powi(i as i32))),
+            |n, _| Sample::err(errs[n.trailing_zeros() as usize]),
+        );
+
+        assert_eq!(study.fit, 0..3);
+        assert!((study.fitted_order.unwrap() - 2.0).abs() < 1.0e-12);
+        assert_eq!(study.levels[4].rate, None);
+
+        let tail = ConvergenceStudy::new().fit_last(2).summarize(study.levels);
+        assert_eq!(tail.fit, 1..3);
+        assert!((tail.fitted_order.unwrap() - 2.0).abs() < 1.0e-12);
+    }
+}
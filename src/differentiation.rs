@@ -0,0 +1,68 @@
+use crate::error::Result;
+use crate::extrapolation::{self, Tableau};
+
+/// One-sided forward difference `(f(x+h) - f(x)) / h`, first order in `h`.
+pub fn forward_diff<F: Fn(f64) -> f64>(x: f64, h: f64, f: F) -> f64 {
+    (f(x + h) - f(x)) / h
+}
+
+/// One-sided backward difference `(f(x) - f(x-h)) / h`, first order in `h`.
+pub fn backward_diff<F: Fn(f64) -> f64>(x: f64, h: f64, f: F) -> f64 {
+    (f(x) - f(x - h)) / h
+}
+
+/// Centered difference `(f(x+h) - f(x-h)) / 2h`, second order in `h`.
+pub fn center_diff<F: Fn(f64) -> f64>(x: f64, h: f64, f: F) -> f64 {
+    (f(x + h) - f(x - h)) / (2.0 * h)
+}
+
+/// Forward difference with one Richardson step, `2 D(h) - D(2h)`, second order in `h`.
+pub fn special_diff<F: Fn(f64) -> f64>(x: f64, h: f64, f: F) -> f64 {
+    extrapolation::richardson_step(
+        forward_diff(x, 2.0 * h, &f),
+        forward_diff(x, h, &f),
+        2.0,
+        1.0,
+    )
+}
+
+/// Richardson tableau for the difference quotient `d` of `f` at `x`, halving `h`.
+///
+/// `orders` lists the exponents of the error expansion of `d`: `[1, 2, 3, ...]`
+/// for one-sided differences and `[2, 4, 6, ...]` for centered ones.
+pub fn richardson_diff<F, D>(x: f64, h: f64, f: F, d: D, orders: &[f64]) -> Result<Tableau>
+where
+    F: Fn(f64) -> f64 + Copy,
+    D: Fn(f64, f64, F) -> f64,
+{
+    extrapolation::richardson(|step| d(x, step, f), h, 2.0, orders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backward_diff, center_diff, forward_diff, richardson_diff, special_diff};
+
+    #[test]
+    fn all_methods_are_exact_for_linear_functions() {
+        let f = |x: f64| 4.0 * x - 1.0;
+
+        for d in [
+            forward_diff(1.0, 0.1, f),
+            backward_diff(1.0, 0.1, f),
+            center_diff(1.0, 0.1, f),
+            special_diff(1.0, 0.1, f),
+        ] {
+            assert!((d - 4.0).abs() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn extrapolated_center_diff_reaches_high_accuracy() {
+        let tableau = richardson_diff(1.0, 0.25, f64::exp, center_diff, &[2.0, 4.0, 6.0])
+            .expect("ratio 2 is valid");
+        let plain = center_diff(1.0, 0.25 / 8.0, f64::exp);
+
+        assert!((tableau.best() - 1.0_f64.exp()).abs() < 1.0e-12);
+        assert!((plain - 1.0_f64.exp()).abs() > 1.0e-5);
+    }
+}
@@ -0,0 +1,83 @@
+//! Crate-wide error type for the chapter drivers and their output stages.
+
+use crate::linalg::LinalgError;
+use std::fmt;
+use std::io;
+use std::process::ExitStatus;
+
+pub type Result<T> = std::result::Result<T, Mat627Error>;
+
+/// Everything that can stop a chapter run.
+#[derive(Debug)]
+pub enum Mat627Error {
+    /// Filesystem failure (creating directories, copying figures, reading configs).
+    Io(io::Error),
+    /// Writing, reading or decoding the `.npy` file at `path` failed.
+    Npy { path: String, source: io::Error },
+    /// An external program could not be started at all.
+    Spawn { command: String, source: io::Error },
+    /// An external program ran and exited unsuccessfully.
+    Process { command: String, status: ExitStatus },
+    /// The native plotting backend failed to draw a figure.
+    Plot(String),
+    /// A numerical routine rejected its input (wrong length, NaN, ...).
+    InvalidInput(String),
+    /// A solver broke down, e.g. on a zero pivot.
+    Breakdown(String),
+}
+
+impl fmt::Display for Mat627Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Npy { path, source } => write!(f, "{path}: {source}"),
+            Self::Spawn { command, source } => write!(f, "could not run `{command}`: {source}"),
+            Self::Process { command, status } => write!(f, "`{command}` failed ({status})"),
+            Self::Plot(message) => write!(f, "plotting failed: {message}"),
+            Self::InvalidInput(message) => write!(f, "invalid input: {message}"),
+            Self::Breakdown(message) => write!(f, "solver breakdown: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Mat627Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) | Self::Npy { source: err, .. } | Self::Spawn { source: err, .. } => {
+                Some(err)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Mat627Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<LinalgError> for Mat627Error {
+    fn from(err: LinalgError) -> Self {
+        match err {
+            LinalgError::Singular { .. } => Self::Breakdown(err.to_string()),
+            _ => Self::InvalidInput(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mat627Error;
+    use crate::linalg::LinalgError;
+
+    #[test]
+    fn linalg_errors_split_into_input_and_breakdown() {
+        let singular = Mat627Error::from(LinalgError::Singular { pivot: 3 });
+        assert!(matches!(singular, Mat627Error::Breakdown(_)));
+        assert!(singular.to_string().contains("column 3"));
+
+        let empty = Mat627Error::from(LinalgError::Empty);
+        assert!(matches!(empty, Mat627Error::InvalidInput(_)));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::error::{Mat627Error, Result};
+
+/// One Richardson step eliminating the `h^order` term.
+///
+/// `coarse` and `fine` approximate the same quantity with step sizes
+/// `ratio * h` and `h`.
+pub fn richardson_step(coarse: f64, fine: f64, ratio: f64, order: f64) -> f64 {
+    fine + (fine - coarse) / (ratio.powf(order) - 1.0)
+}
+
+/// Richardson extrapolation tableau.
+///
+/// `values[i][j]` combines `A(h_i), ..., A(h_{i-j})` to eliminate the first
+/// `j` terms of the error expansion `A(h) = A + c1 h^p1 + c2 h^p2 + ...`,
+/// where `orders = [p1, p2, ...]` and `h_i = h_0 / ratio^i`.
+#[derive(Debug, Clone)]
+pub struct Tableau {
+    pub ratio: f64,
+    pub orders: Vec<f64>,
+    pub h: Vec<f64>,
+    pub values: Vec<Vec<f64>>,
+    /// Error estimate for each `values[i][j]`, `None` only for `values[0][0]`.
+    pub errors: Vec<Vec<Option<f64>>>,
+}
+
+impl Tableau {
+    /// Empty tableau. Fails with `InvalidInput` unless `ratio > 1`.
+    pub fn new(ratio: f64, orders: &[f64]) -> Result<Self> {
+        if ratio.is_nan() || ratio <= 1.0 {
+            return Err(Mat627Error::InvalidInput(format!(
+                "refinement ratio {ratio} must exceed 1"
+            )));
+        }
+
+        Ok(Self {
+            ratio,
+            orders: orders.to_vec(),
+            h: Vec::new(),
+            values: Vec::new(),
+            errors: Vec::new(),
+        })
+    }
+
+    /// Append the row for a new approximation `A(h)`, where `h` is the
+    /// previous step divided by `ratio`. Columns beyond `orders.len()` are not formed.
+    pub fn push(&mut self, approximation: f64, h: f64) {
+        let i = self.values.len();
+        let cols = i.min(self.orders.len()) + 1;
+        let mut row = Vec::with_capacity(cols);
+        row.push(approximation);
+        for j in 1..cols {
+            let coarse = self.values[i - 1][j - 1];
+            row.push(richardson_step(
+                coarse,
+                row[j - 1],
+                self.ratio,
+                self.orders[j - 1],
+            ));
+        }
+
+        // T[i][j] - T[i-1][j] ~ c h_i^p (ratio^p - 1) with p the next order
+        // in the expansion; without one, fall back to the raw difference. The
+        // diagonal has no entry above it, so use its last correction instead.
+        let errors = (0..cols)
+            .map(|j| match self.values.last().and_then(|prev| prev.get(j)) {
+                Some(prev) => {
+                    let diff = (row[j] - prev).abs();
+                    Some(match self.orders.get(j) {
+                        Some(&p) => diff / (self.ratio.powf(p) - 1.0),
+                        None => diff,
+                    })
+                }
+                None if j > 0 => Some((row[j] - row[j - 1]).abs()),
+                None => None,
+            })
+            .collect();
+
+        self.h.push(h);
+        self.values.push(row);
+        self.errors.push(errors);
+    }
+
+    /// Most extrapolated entry of the last row.
+    pub fn best(&self) -> f64 {
+        *self
+            .values
+            .last()
+            .and_then(|row| row.last())
+            .expect("tableau is empty")
+    }
+
+    /// Error estimate for [`Tableau::best`].
+    pub fn best_error(&self) -> Option<f64> {
+        self.errors
+            .last()
+            .and_then(|row| row.last().copied().flatten())
+    }
+
+    /// Deepest column in the tableau.
+    pub fn depth(&self) -> usize {
+        self.values.last().map_or(0, |row| row.len() - 1)
+    }
+}
+
+/// Build a Richardson tableau from successive approximations.
+///
+/// `approximations[i]` is `A(h0 / ratio^i)`; see [`Tableau`].
+pub fn tableau_from(
+    approximations: &[f64],
+    h0: f64,
+    ratio: f64,
+    orders: &[f64],
+) -> Result<Tableau> {
+    let mut tableau = Tableau::new(ratio, orders)?;
+    for (i, &a) in approximations.iter().enumerate() {
+        tableau.push(a, h0 / ratio.powi(i as i32));
+    }
+
+    Ok(tableau)
+}
+
+/// Richardson extrapolation of `f(h) -> A` as `h -> 0`.
+///
+/// Evaluates `f` at `h0 / ratio^i` for `i = 0..=orders.len()` and eliminates the
+/// error terms `h^p` for each `p` in `orders`, in sequence.
+///
+/// # Arguments
+/// * `f` - The approximation as a function of step size
+/// * `h0` - The coarsest step size
+/// * `ratio` - Refinement factor between rows (2 halves `h`)
+/// * `orders` - Exponents of the error expansion, lowest first
+pub fn richardson<F: Fn(f64) -> f64>(f: F, h0: f64, ratio: f64, orders: &[f64]) -> Result<Tableau> {
+    let approximations: Vec<f64> = (0..=orders.len())
+        .map(|i| f(h0 / ratio.powi(i as i32)))
+        .collect();
+
+    tableau_from(&approximations, h0, ratio, orders)
+}
+
+/// The order sequence `p, 2p, 3p, ...` of length `depth`.
+pub fn arithmetic_orders(p: f64, depth: usize) -> Vec<f64> {
+    (1..=depth).map(|k| k as f64 * p).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{arithmetic_orders, richardson};
+    use crate::error::Mat627Error;
+
+    #[test]
+    fn eliminates_polynomial_error_terms_exactly() {
+        let f = |h: f64| 3.0 + 2.0 * h - 5.0 * h * h + 0.5 * h.powi(3);
+        let tableau = richardson(f, 0.5, 2.0, &[1.0, 2.0, 3.0]).expect("ratio exceeds 1");
+
+        assert_eq!(tableau.depth(), 3);
+        assert!((tableau.best() - 3.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn error_estimates_bound_centered_difference_levels() {
+        let d = |h: f64| (f64::exp(1.0 + h) - f64::exp(1.0 - h)) / (2.0 * h);
+        let exact = 1.0_f64.exp();
+        let tableau = richardson(d, 0.4, 2.0, &arithmetic_orders(2.0, 3)).expect("ratio exceeds 1");
+
+        for (row, errors) in tableau.values.iter().zip(&tableau.errors).skip(1) {
+            let j = row.len() - 2;
+            let estimate = errors[j].expect("missing error estimate");
+            let actual = (row[j] - exact).abs();
+            assert!(estimate / actual > 0.5 && estimate / actual < 2.0);
+        }
+        assert!((tableau.best() - exact).abs() < 1.0e-11);
+    }
+
+    #[test]
+    fn non_integer_ratio_and_orders_are_supported() {
+        let f = |h: f64| 1.0 + h.powf(1.5) + h.powi(2);
+        let tableau = richardson(f, 1.0, 3.0, &[1.5, 2.0]).expect("ratio exceeds 1");
+
+        assert!((tableau.best() - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn ratio_at_most_one_is_invalid_input() {
+        for ratio in [1.0, 0.5, f64::NAN] {
+            assert!(matches!(
+                richardson(|h| h, 1.0, ratio, &[1.0]),
+                Err(Mat627Error::InvalidInput(_))
+            ));
+        }
+    }
+}
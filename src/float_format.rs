@@ -0,0 +1,361 @@
+//! Properties of binary floating-point formats.
+//!
+//! A [`FloatFormat`] is an IEEE 754-style binary format given by its exponent
+//! and stored mantissa widths: `f32` and `f64`, the 16-bit `f16` and `bf16`,
+//! or any other width up to those of `f64`. Values of the narrower formats
+//! are emulated in software by rounding `f64`s with [`FloatFormat::round`],
+//! which is exact because every such format is a subset of `f64`.
+
+use crate::error::{Mat627Error, Result};
+use serde::Deserialize;
+use std::fmt;
+
+/// IEEE 754 rounding-direction attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    NearestEven,
+    TowardZero,
+    Up,
+    Down,
+}
+
+impl RoundingMode {
+    pub const ALL: [Self; 4] = [Self::NearestEven, Self::TowardZero, Self::Up, Self::Down];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NearestEven => "nearest-even",
+            Self::TowardZero => "toward zero",
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+}
+
+/// A binary format with `exponent_bits` of biased exponent and
+/// `mantissa_bits` of stored fraction, with subnormals, infinities and NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "Widths")]
+pub struct FloatFormat {
+    exponent_bits: u32,
+    mantissa_bits: u32,
+}
+
+/// The TOML form of a [`FloatFormat`], validated by [`FloatFormat::new`].
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Widths {
+    exponent_bits: u32,
+    mantissa_bits: u32,
+}
+
+impl TryFrom<Widths> for FloatFormat {
+    type Error = Mat627Error;
+
+    fn try_from(widths: Widths) -> Result<Self> {
+        Self::new(widths.exponent_bits, widths.mantissa_bits)
+    }
+}
+
+impl FloatFormat {
+    /// IEEE binary16.
+    pub const F16: Self = Self::of(5, 10);
+    /// bfloat16: the exponent range of `f32` with 8 bits of precision.
+    pub const BF16: Self = Self::of(8, 7);
+    pub const F32: Self = Self::of(8, f32::MANTISSA_DIGITS - 1);
+    pub const F64: Self = Self::of(11, f64::MANTISSA_DIGITS - 1);
+
+    const fn of(exponent_bits: u32, mantissa_bits: u32) -> Self {
+        Self {
+            exponent_bits,
+            mantissa_bits,
+        }
+    }
+
+    /// A format that can be emulated in `f64`: 2 to 11 exponent bits and 1
+    /// to 52 mantissa bits.
+    pub fn new(exponent_bits: u32, mantissa_bits: u32) -> Result<Self> {
+        if !(2..=11).contains(&exponent_bits) || !(1..=52).contains(&mantissa_bits) {
+            return Err(Mat627Error::InvalidInput(format!(
+                "cannot emulate a format with {exponent_bits} exponent and {mantissa_bits} \
+                 mantissa bits (at most 11 and 52)"
+            )));
+        }
+        Ok(Self::of(exponent_bits, mantissa_bits))
+    }
+
+    /// `f16`, `bf16`, `f32`, `f64`, or `e{exponent}m{mantissa}` otherwise.
+    pub fn name(&self) -> String {
+        match *self {
+            Self::F16 => String::from("f16"),
+            Self::BF16 => String::from("bf16"),
+            Self::F32 => String::from("f32"),
+            Self::F64 => String::from("f64"),
+            Self {
+                exponent_bits,
+                mantissa_bits,
+            } => format!("e{exponent_bits}m{mantissa_bits}"),
+        }
+    }
+
+    pub fn exponent_bits(&self) -> u32 {
+        self.exponent_bits
+    }
+
+    pub fn mantissa_bits(&self) -> u32 {
+        self.mantissa_bits
+    }
+
+    /// Significand digits including the implicit leading bit.
+    pub fn precision(&self) -> u32 {
+        self.mantissa_bits + 1
+    }
+
+    /// The largest unbiased exponent of a finite value.
+    pub fn max_exponent(&self) -> i32 {
+        (1 << (self.exponent_bits - 1)) - 1
+    }
+
+    /// The smallest unbiased exponent of a normal value.
+    pub fn min_exponent(&self) -> i32 {
+        1 - self.max_exponent()
+    }
+
+    /// The gap between 1 and the next larger value, `2^-mantissa_bits`.
+    pub fn epsilon(&self) -> f64 {
+        pow2(-(self.mantissa_bits as i32))
+    }
+
+    /// Half of [`FloatFormat::epsilon`], the relative error bound of
+    /// round-to-nearest.
+    pub fn unit_roundoff(&self) -> f64 {
+        self.epsilon() / 2.0
+    }
+
+    pub fn min_normal(&self) -> f64 {
+        pow2(self.min_exponent())
+    }
+
+    pub fn max_normal(&self) -> f64 {
+        (2.0 - self.epsilon()) * pow2(self.max_exponent())
+    }
+
+    pub fn min_subnormal(&self) -> f64 {
+        pow2(self.min_exponent() - self.mantissa_bits as i32)
+    }
+
+    pub fn max_subnormal(&self) -> f64 {
+        self.min_normal() - self.min_subnormal()
+    }
+
+    /// The smallest positive integer the format cannot hold, `2^precision + 1`,
+    /// or `None` when every integer up to the largest finite value fits.
+    pub fn first_unrepresentable_integer(&self) -> Option<u64> {
+        let first = (1_u64 << self.precision()) + 1;
+        (self.max_exponent() >= self.precision() as i32).then_some(first)
+    }
+
+    /// The spacing of the format's values in the binade of `x`, or `None`
+    /// when `|x|` is beyond the largest finite value.
+    pub fn ulp(&self, x: f64) -> Option<f64> {
+        let a = x.abs();
+        if a.is_nan() || a > self.max_normal() {
+            return None;
+        }
+        if a < self.min_normal() {
+            return Some(self.min_subnormal());
+        }
+        Some(pow2(exponent(a) - self.mantissa_bits as i32))
+    }
+
+    /// `x` rounded to this format under `mode`, returned as an `f64`.
+    ///
+    /// Overflow gives an infinity or the largest finite value, as IEEE 754
+    /// prescribes for each mode.
+    pub fn round(&self, x: f64, mode: RoundingMode) -> f64 {
+        if x == 0.0 || !x.is_finite() {
+            return x;
+        }
+        let a = x.abs();
+        let negative = x < 0.0;
+        let quantum = if a < self.min_normal() {
+            self.min_subnormal()
+        } else {
+            pow2(exponent(a) - self.mantissa_bits as i32)
+        };
+        // At most 2^(mantissa_bits + 1) quanta, so the quotient is exact.
+        let scaled = a / quantum;
+        let away_from_zero = match mode {
+            RoundingMode::NearestEven => None,
+            RoundingMode::TowardZero => Some(false),
+            RoundingMode::Up => Some(!negative),
+            RoundingMode::Down => Some(negative),
+        };
+        let count = match away_from_zero {
+            None => scaled.round_ties_even(),
+            Some(true) => scaled.ceil(),
+            Some(false) => scaled.floor(),
+        };
+        let rounded = count * quantum;
+
+        let magnitude = if rounded <= self.max_normal() {
+            rounded
+        } else if away_from_zero == Some(false) {
+            self.max_normal()
+        } else {
+            f64::INFINITY
+        };
+        if negative { -magnitude } else { magnitude }
+    }
+
+    /// Whether `x` is a value of this format.
+    pub fn represents(&self, x: f64) -> bool {
+        self.round(x, RoundingMode::NearestEven) == x
+    }
+
+    /// One row per property: its name, the value as a power of two where it
+    /// is one, and the value in decimal.
+    pub fn properties(&self) -> Vec<(&'static str, String, String)> {
+        let m = self.mantissa_bits as i32;
+        let (emin, emax) = (self.min_exponent(), self.max_exponent());
+        let decimal = |x: f64| format!("{x:.6e}");
+        let mut rows = vec![
+            (
+                "bits (sign, exponent, mantissa)",
+                String::new(),
+                format!("1 + {} + {}", self.exponent_bits, self.mantissa_bits),
+            ),
+            ("exponent range", String::new(), format!("[{emin}, {emax}]")),
+            (
+                "machine epsilon",
+                format!("2^{}", -m),
+                decimal(self.epsilon()),
+            ),
+            (
+                "unit roundoff",
+                format!("2^{}", -m - 1),
+                decimal(self.unit_roundoff()),
+            ),
+            (
+                "min subnormal",
+                format!("2^{}", emin - m),
+                decimal(self.min_subnormal()),
+            ),
+            (
+                "max subnormal",
+                format!("2^{emin} - 2^{}", emin - m),
+                decimal(self.max_subnormal()),
+            ),
+            (
+                "min normal",
+                format!("2^{emin}"),
+                decimal(self.min_normal()),
+            ),
+            (
+                "max normal",
+                format!("(2 - 2^{}) 2^{emax}", -m),
+                decimal(self.max_normal()),
+            ),
+        ];
+        rows.push(match self.first_unrepresentable_integer() {
+            Some(n) => (
+                "first unrepresentable integer",
+                format!("2^{} + 1", self.precision()),
+                n.to_string(),
+            ),
+            None => (
+                "first unrepresentable integer",
+                String::new(),
+                String::from("none below max normal"),
+            ),
+        });
+        rows
+    }
+}
+
+impl fmt::Display for FloatFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// `2^k` for `-1074 <= k <= 1023`, built from its bit pattern so that
+/// subnormal powers are exact.
+fn pow2(k: i32) -> f64 {
+    if k >= -1022 {
+        f64::from_bits(((k + 1023) as u64) << 52)
+    } else {
+        f64::from_bits(1 << (k + 1074))
+    }
+}
+
+/// `floor(log2(a))` for a finite positive `a`, including `f64` subnormals.
+fn exponent(a: f64) -> i32 {
+    let bits = a.to_bits();
+    let biased = (bits >> 52) as i32;
+    if biased == 0 {
+        let fraction = bits & ((1 << 52) - 1);
+        -1074 + (63 - fraction.leading_zeros() as i32)
+    } else {
+        biased - 1023
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FloatFormat, RoundingMode};
+
+    #[test]
+    fn native_formats_match_the_hardware() {
+        let f32_ = FloatFormat::F32;
+        assert_eq!(f32_.epsilon(), f64::from(f32::EPSILON));
+        assert_eq!(f32_.min_normal(), f64::from(f32::MIN_POSITIVE));
+        assert_eq!(f32_.max_normal(), f64::from(f32::MAX));
+        assert_eq!(f32_.min_subnormal(), f64::from(f32::from_bits(1)));
+        assert_eq!(f32_.first_unrepresentable_integer(), Some(16_777_217));
+
+        let f64_ = FloatFormat::F64;
+        assert_eq!(f64_.epsilon(), f64::EPSILON);
+        assert_eq!(f64_.max_normal(), f64::MAX);
+        assert_eq!(f64_.min_subnormal(), f64::from_bits(1));
+        assert_eq!(f64_.ulp(1.0), Some(f64::EPSILON));
+
+        let mut x = 1.0e-46_f64;
+        while x < 1.0e39 {
+            for y in [x, -x, x * (1.0 + 1.0 / 3.0), x * 1.5] {
+                assert_eq!(
+                    f32_.round(y, RoundingMode::NearestEven),
+                    f64::from(y as f32),
+                    "{y:e}"
+                );
+            }
+            x *= 1.7;
+        }
+    }
+
+    #[test]
+    fn sixteen_bit_formats_and_rounding_modes() {
+        let half = FloatFormat::F16;
+        assert_eq!(half.max_normal(), 65504.0);
+        assert_eq!(half.min_subnormal(), 2.0_f64.powi(-24));
+        assert_eq!(half.first_unrepresentable_integer(), Some(2049));
+        assert_eq!(half.ulp(1000.0), Some(0.5));
+        assert_eq!(half.ulp(1.0e5), None);
+        assert_eq!(FloatFormat::BF16.max_normal(), 3.3895313892515355e38);
+
+        // 1 + eps/2 is a tie: nearest-even goes down, `up` goes up.
+        let tie = 1.0 + half.epsilon() / 2.0;
+        assert_eq!(half.round(tie, RoundingMode::NearestEven), 1.0);
+        assert_eq!(half.round(tie, RoundingMode::Up), 1.0 + half.epsilon());
+        assert_eq!(half.round(-tie, RoundingMode::Up), -1.0);
+        assert_eq!(half.round(-tie, RoundingMode::Down), -1.0 - half.epsilon());
+        assert_eq!(half.round(1.0e5, RoundingMode::NearestEven), f64::INFINITY);
+        assert_eq!(half.round(1.0e5, RoundingMode::TowardZero), 65504.0);
+        assert_eq!(half.round(-1.0e5, RoundingMode::Up), -65504.0);
+        assert!(half.represents(2048.0) && !half.represents(2049.0));
+
+        assert!(FloatFormat::new(12, 3).is_err());
+        let tiny = FloatFormat::new(2, 1).unwrap();
+        assert_eq!(tiny.first_unrepresentable_integer(), None);
+    }
+}
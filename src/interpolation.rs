@@ -0,0 +1,123 @@
+use crate::error::{Mat627Error, Result};
+
+/// Split `[a, b]` into `n` equal subintervals, returned as `(left, right)` pairs.
+///
+/// Fails with `InvalidInput` when `n` is zero.
+pub fn mesh(a: f64, b: f64, n: usize) -> Result<Vec<(f64, f64)>> {
+    if n == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "mesh needs at least one subinterval",
+        )));
+    }
+
+    let h = (b - a) / n as f64;
+
+    Ok((0..n)
+        .map(|i| {
+            let left = a + i as f64 * h;
+            let right = a + (i + 1) as f64 * h;
+            (left, right)
+        })
+        .collect())
+}
+
+/// Linear interpolant of `f` through `(a, f(a))` and `(b, f(b))`.
+///
+/// Fails with `InvalidInput` when `a == b`.
+pub fn linear_interp<F>(a: f64, b: f64, f: F) -> Result<impl Fn(f64) -> f64>
+where
+    F: Fn(f64) -> f64,
+{
+    if a == b {
+        return Err(Mat627Error::InvalidInput(format!(
+            "interval endpoints must be distinct, got {a} twice"
+        )));
+    }
+
+    let fa = f(a);
+    let fb = f(b);
+
+    Ok(move |x| fa * (b - x) / (b - a) + fb * (x - a) / (b - a))
+}
+
+/// `steps + 1` equally spaced points covering `[a, b]`.
+pub fn uniform_grid(a: f64, b: f64, steps: usize) -> Vec<f64> {
+    (0..=steps)
+        .map(|i| a + (b - a) * i as f64 / steps as f64)
+        .collect()
+}
+
+/// Index of the subinterval of the uniform `n`-piece mesh on `[a, b]` containing `x`.
+///
+/// The right endpoint `b` belongs to the last subinterval. Fails with
+/// `InvalidInput` when `n` is zero or `x` lies outside `[a, b]`.
+pub fn interval_index(a: f64, b: f64, n: usize, x: f64) -> Result<usize> {
+    if n == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "mesh needs at least one subinterval",
+        )));
+    }
+    if !(x >= a - 1.0e-12 && x <= b + 1.0e-12) {
+        return Err(Mat627Error::InvalidInput(format!(
+            "{x} lies outside [{a}, {b}]"
+        )));
+    }
+
+    if (x - b).abs() < 1.0e-12 {
+        return Ok(n - 1);
+    }
+
+    let h = (b - a) / n as f64;
+    Ok((((x - a) / h).floor() as usize).min(n - 1))
+}
+
+/// Evaluate the piecewise linear interpolant of `f` on the uniform `n`-piece
+/// mesh of `[a, b]` at each point of `xs`.
+pub fn piecewise_linear_values<F>(f: F, a: f64, b: f64, n: usize, xs: &[f64]) -> Result<Vec<f64>>
+where
+    F: Fn(f64) -> f64,
+{
+    let pieces = mesh(a, b, n)?
+        .into_iter()
+        .map(|(left, right)| linear_interp(left, right, &f))
+        .collect::<Result<Vec<_>>>()?;
+
+    xs.iter()
+        .copied()
+        .map(|x| Ok(pieces[interval_index(a, b, n, x)?](x)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interval_index, piecewise_linear_values};
+    use crate::error::Mat627Error;
+
+    #[test]
+    fn right_endpoint_belongs_to_last_interval() {
+        assert_eq!(interval_index(0.0, 1.0, 4, 1.0).expect("x in [0, 1]"), 3);
+        assert_eq!(interval_index(0.0, 1.0, 4, 0.5).expect("x in [0, 1]"), 2);
+    }
+
+    #[test]
+    fn points_outside_the_mesh_are_invalid_input() {
+        assert!(matches!(
+            interval_index(0.0, 1.0, 4, 1.5),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            piecewise_linear_values(f64::exp, 0.0, 1.0, 0, &[0.5]),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn interpolant_reproduces_linear_functions() {
+        let xs = [0.0, 0.3, 0.75, 1.0];
+        let q = piecewise_linear_values(|x| 2.0 * x + 1.0, 0.0, 1.0, 3, &xs).expect("xs in [0, 1]");
+
+        for (x, qx) in xs.iter().copied().zip(q) {
+            assert!((qx - (2.0 * x + 1.0)).abs() < 1.0e-12);
+        }
+    }
+}
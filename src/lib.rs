@@ -0,0 +1,24 @@
+//! Numerical methods from the MAT 627 programming assignments.
+//!
+//! The chapter drivers in the `mat627_assignments` binary are thin examples
+//! over these modules; everything here can be called from other crates.
+
+pub mod bvp;
+pub mod config;
+pub mod convergence;
+pub mod differentiation;
+pub mod error;
+pub mod extrapolation;
+pub mod float_format;
+pub mod interpolation;
+pub mod linalg;
+pub mod ode;
+pub mod parallel;
+#[cfg(feature = "native-plots")]
+pub mod plotting;
+pub mod polynomials;
+pub mod quadrature;
+pub mod report;
+pub mod series;
+pub mod summation;
+pub mod util;
@@ -0,0 +1,154 @@
+//! Dense vector helpers and structured (tridiagonal, banded) matrices.
+
+use std::fmt;
+use std::ops::{Add, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+mod banded;
+mod conditioning;
+mod refinement;
+mod tridiagonal;
+
+pub use banded::{BandedLu, BandedMatrix};
+pub use conditioning::{SolveDiagnostics, estimate_norm_one};
+pub use refinement::{
+    Refinement, RefinementOptions, RefinementStep, ResidualPrecision, refine, residual,
+};
+pub use tridiagonal::{TridiagonalLu, TridiagonalMatrix};
+
+/// Floating-point types a factorisation can be carried out in.
+pub trait Scalar:
+    Copy
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    const ZERO: Self;
+    /// Machine epsilon, the gap between 1 and the next representable value.
+    const EPSILON: Self;
+
+    fn abs(self) -> Self;
+    fn is_finite(self) -> bool;
+    /// Round an `f64` to this precision.
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const EPSILON: Self = f64::EPSILON;
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const EPSILON: Self = f32::EPSILON;
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Failures from constructing or factorising a structured matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinalgError {
+    /// A matrix with no rows.
+    Empty,
+    /// A vector or diagonal has the wrong length.
+    DimensionMismatch {
+        what: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A NaN or infinite entry was supplied.
+    NonFinite { what: &'static str, index: usize },
+    /// An entry outside the declared band was written.
+    OutOfBand { row: usize, col: usize },
+    /// Elimination produced a zero (or non-finite) pivot in column `pivot`.
+    Singular { pivot: usize },
+}
+
+impl LinalgError {
+    pub(crate) fn check_len(what: &'static str, expected: usize, found: usize) -> Result<(), Self> {
+        if expected == found {
+            Ok(())
+        } else {
+            Err(Self::DimensionMismatch {
+                what,
+                expected,
+                found,
+            })
+        }
+    }
+
+    pub(crate) fn check_finite(what: &'static str, values: &[f64]) -> Result<(), Self> {
+        match values.iter().position(|v| !v.is_finite()) {
+            Some(index) => Err(Self::NonFinite { what, index }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "matrix must have at least one row"),
+            Self::DimensionMismatch {
+                what,
+                expected,
+                found,
+            } => write!(f, "{what} has length {found}, expected {expected}"),
+            Self::NonFinite { what, index } => write!(f, "{what} entry {index} is not finite"),
+            Self::OutOfBand { row, col } => write!(f, "entry ({row}, {col}) lies outside the band"),
+            Self::Singular { pivot } => {
+                write!(f, "matrix is singular (zero pivot in column {pivot})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+/// Componentwise `y - z`.
+pub fn vector_subtract(y: &[f64], z: &[f64]) -> Vec<f64> {
+    assert_eq!(y.len(), z.len(), "vector length mismatch");
+    y.iter().zip(z).map(|(yi, zi)| yi - zi).collect()
+}
+
+/// Infinity norm of `values`.
+pub fn max_abs(values: &[f64]) -> f64 {
+    values.iter().copied().map(f64::abs).fold(0.0, f64::max)
+}
@@ -0,0 +1,200 @@
+use super::{LinalgError, Scalar, TridiagonalLu, TridiagonalMatrix, max_abs};
+use crate::summation::two_sum;
+use serde::Deserialize;
+
+/// How the residual `b - T x` is accumulated during refinement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResidualPrecision {
+    /// Plain `f64` arithmetic.
+    Working,
+    /// Error-free transformations (Ogita-Rump-Oishi `Dot2`), roughly twice the working precision.
+    Compensated,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefinementOptions {
+    pub max_iterations: usize,
+    pub residual: ResidualPrecision,
+}
+
+impl Default for RefinementOptions {
+    /// The LAPACK `dsgesv` iteration limit with `f64` residuals.
+    fn default() -> Self {
+        Self {
+            max_iterations: 30,
+            residual: ResidualPrecision::Working,
+        }
+    }
+}
+
+/// One iterate of the refinement loop; iteration 0 is the initial low-precision solve.
+#[derive(Debug, Clone, Copy)]
+pub struct RefinementStep {
+    pub iteration: usize,
+    /// `||b - T x||_inf` for this iterate.
+    pub residual_norm: f64,
+    /// Normwise backward error `||r|| / (||T|| ||x|| + ||b||)`.
+    pub backward_error: f64,
+    /// `||dx||_inf` of the correction that produced this iterate.
+    pub correction_norm: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Refinement {
+    pub solution: Vec<f64>,
+    /// Residual of the final iterate.
+    pub residual: Vec<f64>,
+    pub history: Vec<RefinementStep>,
+    pub converged: bool,
+}
+
+impl Refinement {
+    /// Number of corrections applied.
+    pub fn iterations(&self) -> usize {
+        self.history.len() - 1
+    }
+}
+
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+/// `sum_k a_k b_k` evaluated as if in twice the working precision.
+fn compensated_dot(terms: &[(f64, f64)]) -> f64 {
+    let (s, c) = terms.iter().fold((0.0, 0.0), |(s, c), &(a, b)| {
+        let (p, pe) = two_prod(a, b);
+        let (s, se) = two_sum(s, p);
+        (s, c + (pe + se))
+    });
+    s + c
+}
+
+/// Residual `b - T x` accumulated in the requested precision.
+pub fn residual(
+    matrix: &TridiagonalMatrix,
+    x: &[f64],
+    b: &[f64],
+    precision: ResidualPrecision,
+) -> Result<Vec<f64>, LinalgError> {
+    let n = matrix.size();
+    LinalgError::check_len("right-hand side", n, b.len())?;
+
+    match precision {
+        ResidualPrecision::Working => {
+            let tx = matrix.matvec(x)?;
+            Ok(b.iter().zip(tx).map(|(bi, ti)| bi - ti).collect())
+        }
+        ResidualPrecision::Compensated => {
+            LinalgError::check_len("vector", n, x.len())?;
+            Ok((0..n)
+                .map(|i| {
+                    let mut terms = vec![(b[i], 1.0), (-matrix.diag()[i], x[i])];
+                    if i > 0 {
+                        terms.push((-matrix.lower()[i - 1], x[i - 1]));
+                    }
+                    if i + 1 < n {
+                        terms.push((-matrix.upper()[i], x[i + 1]));
+                    }
+                    compensated_dot(&terms)
+                })
+                .collect())
+        }
+    }
+}
+
+/// Iterative refinement of `T x = b` from factors computed in precision `T`.
+///
+/// Each correction is solved with `lu` and the residual is formed in `f64` (or
+/// compensated `f64`). The loop stops once `||r|| <= ||x|| ||T|| eps sqrt(n)`, the
+/// `dsgesv` criterion, or after `options.max_iterations` corrections.
+pub fn refine<T: Scalar>(
+    matrix: &TridiagonalMatrix,
+    lu: &TridiagonalLu<T>,
+    b: &[f64],
+    options: RefinementOptions,
+) -> Result<Refinement, LinalgError> {
+    let n = matrix.size();
+    let matrix_norm = matrix.norm_inf();
+    let b_norm = max_abs(b);
+    let threshold = f64::EPSILON * (n as f64).sqrt() * matrix_norm;
+
+    let mut x = lu.solve(b)?;
+    let mut history = Vec::new();
+    let mut correction_norm = None;
+
+    loop {
+        let r = residual(matrix, &x, b, options.residual)?;
+        let residual_norm = max_abs(&r);
+        let x_norm = max_abs(&x);
+        history.push(RefinementStep {
+            iteration: history.len(),
+            residual_norm,
+            backward_error: residual_norm / (matrix_norm * x_norm + b_norm),
+            correction_norm,
+        });
+
+        let converged = residual_norm <= threshold * x_norm;
+        if converged || !residual_norm.is_finite() || history.len() > options.max_iterations {
+            return Ok(Refinement {
+                solution: x,
+                residual: r,
+                history,
+                converged,
+            });
+        }
+
+        let dx = lu.solve(&r)?;
+        for (xi, dxi) in x.iter_mut().zip(&dx) {
+            *xi += dxi;
+        }
+        correction_norm = Some(max_abs(&dx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RefinementOptions, ResidualPrecision, refine, residual};
+    use crate::linalg::{TridiagonalMatrix, max_abs, vector_subtract};
+
+    fn test_matrix(n: usize) -> TridiagonalMatrix {
+        let diag = (0..n).map(|i| 3.0 + (i as f64).sin()).collect();
+        TridiagonalMatrix::new(vec![-1.1; n - 1], diag, vec![0.9; n - 1]).unwrap()
+    }
+
+    #[test]
+    fn single_precision_factors_refine_to_double_accuracy() {
+        let t = test_matrix(50);
+        let x: Vec<f64> = (0..50).map(|i| 1.0 / (i as f64 + 1.0)).collect();
+        let b = t.matvec(&x).unwrap();
+        let lu = t.lu_in::<f32>().unwrap();
+
+        let unrefined = lu.solve(&b).unwrap();
+        assert!(max_abs(&vector_subtract(&unrefined, &x)) > 1.0e-9);
+
+        let refined = refine(&t, &lu, &b, RefinementOptions::default()).unwrap();
+        assert!(refined.converged);
+        assert!(refined.iterations() <= 4);
+        assert!(max_abs(&vector_subtract(&refined.solution, &x)) < 1.0e-14);
+
+        let history = &refined.history;
+        assert!(history[0].correction_norm.is_none());
+        assert!(history.last().unwrap().residual_norm < history[0].residual_norm);
+    }
+
+    #[test]
+    fn compensated_residual_recovers_cancelled_terms() {
+        let t = TridiagonalMatrix::new(vec![1.0], vec![1.0, 1.0], vec![1.0]).unwrap();
+        // 1 + 1e16 rounds to 1e16, so the working residual loses the unit term.
+        let x = [1.0, 1.0e16];
+        let b = [1.0e16, 1.0e16];
+
+        let working = residual(&t, &x, &b, ResidualPrecision::Working).unwrap();
+        let compensated = residual(&t, &x, &b, ResidualPrecision::Compensated).unwrap();
+
+        assert_eq!(working, vec![0.0, 0.0]);
+        assert_eq!(compensated, vec![-1.0, -1.0]);
+    }
+}
@@ -0,0 +1,370 @@
+use super::conditioning::{SolveDiagnostics, estimate_norm_one};
+use super::{LinalgError, Scalar, max_abs, vector_subtract};
+
+/// An `n x n` tridiagonal matrix.
+///
+/// `lower[i]` is entry `(i + 1, i)`, `diag[i]` is `(i, i)` and `upper[i]` is `(i, i + 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TridiagonalMatrix {
+    lower: Vec<f64>,
+    diag: Vec<f64>,
+    upper: Vec<f64>,
+}
+
+impl TridiagonalMatrix {
+    /// Build from the three diagonals; `lower` and `upper` have length `n - 1`.
+    pub fn new(lower: Vec<f64>, diag: Vec<f64>, upper: Vec<f64>) -> Result<Self, LinalgError> {
+        let n = diag.len();
+        if n == 0 {
+            return Err(LinalgError::Empty);
+        }
+        LinalgError::check_len("lower diagonal", n - 1, lower.len())?;
+        LinalgError::check_len("upper diagonal", n - 1, upper.len())?;
+        LinalgError::check_finite("lower diagonal", &lower)?;
+        LinalgError::check_finite("diagonal", &diag)?;
+        LinalgError::check_finite("upper diagonal", &upper)?;
+
+        Ok(Self { lower, diag, upper })
+    }
+
+    /// Build from length-`n` diagonals where `lower[0]` and `upper[n - 1]` are
+    /// padding, the row-aligned layout used by the assignment builders.
+    pub fn from_padded(
+        mut lower: Vec<f64>,
+        diag: Vec<f64>,
+        mut upper: Vec<f64>,
+    ) -> Result<Self, LinalgError> {
+        let n = diag.len();
+        LinalgError::check_len("lower diagonal", n, lower.len())?;
+        LinalgError::check_len("upper diagonal", n, upper.len())?;
+        if n > 0 {
+            lower.remove(0);
+            upper.pop();
+        }
+
+        Self::new(lower, diag, upper)
+    }
+
+    pub fn size(&self) -> usize {
+        self.diag.len()
+    }
+
+    pub fn lower(&self) -> &[f64] {
+        &self.lower
+    }
+
+    pub fn diag(&self) -> &[f64] {
+        &self.diag
+    }
+
+    pub fn upper(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Compute `T y`.
+    pub fn matvec(&self, y: &[f64]) -> Result<Vec<f64>, LinalgError> {
+        let n = self.size();
+        LinalgError::check_len("vector", n, y.len())?;
+
+        let mut out = vec![0.0; n];
+        for i in 0..n {
+            let mut value = self.diag[i] * y[i];
+            if i > 0 {
+                value += self.lower[i - 1] * y[i - 1];
+            }
+            if i + 1 < n {
+                value += self.upper[i] * y[i + 1];
+            }
+            out[i] = value;
+        }
+
+        Ok(out)
+    }
+
+    /// Entrywise absolute value `|T|`.
+    pub fn abs(&self) -> Self {
+        let abs = |values: &[f64]| values.iter().copied().map(f64::abs).collect();
+        Self {
+            lower: abs(&self.lower),
+            diag: abs(&self.diag),
+            upper: abs(&self.upper),
+        }
+    }
+
+    /// Infinity norm (maximum absolute row sum).
+    pub fn norm_inf(&self) -> f64 {
+        let ones = vec![1.0; self.size()];
+        let row_sums = self
+            .abs()
+            .matvec(&ones)
+            .expect("vector matches matrix size");
+        max_abs(&row_sums)
+    }
+
+    /// Error diagnostics for a computed solution `x` of `T x = b`.
+    ///
+    /// The condition number is the Hager/Higham estimate built from `lu`.
+    pub fn diagnostics<T: Scalar>(
+        &self,
+        lu: &TridiagonalLu<T>,
+        x: &[f64],
+        b: &[f64],
+    ) -> Result<SolveDiagnostics, LinalgError> {
+        let tx = self.matvec(x)?;
+        let residual = vector_subtract(b, &tx);
+        let abs_tx = self
+            .abs()
+            .matvec(&x.iter().copied().map(f64::abs).collect::<Vec<_>>())?;
+
+        // ||T^{-1}||_inf = ||T^{-T}||_1, so the estimator applies T^{-T} first.
+        let inverse_norm =
+            estimate_norm_one(self.size(), |v| lu.solve_transpose(v), |v| lu.solve(v))?;
+
+        Ok(SolveDiagnostics::new(
+            self.norm_inf(),
+            inverse_norm,
+            &residual,
+            &abs_tx,
+            x,
+            b,
+        ))
+    }
+
+    /// LU factorisation with partial pivoting (LAPACK `gttrf`).
+    ///
+    /// Rows are only interchanged when the sub-diagonal entry is larger than the
+    /// pivot, so a diagonally dominant matrix follows the Thomas algorithm exactly.
+    pub fn lu(&self) -> Result<TridiagonalLu, LinalgError> {
+        self.lu_in()
+    }
+
+    /// LU factorisation carried out in the precision `T`.
+    ///
+    /// The entries are rounded to `T` first, so `lu_in::<f32>()` gives the cheap
+    /// low-precision factors used by iterative refinement.
+    pub fn lu_in<T: Scalar>(&self) -> Result<TridiagonalLu<T>, LinalgError> {
+        let n = self.size();
+        let round = |values: &[f64]| values.iter().copied().map(T::from_f64).collect::<Vec<_>>();
+        let mut dl = round(&self.lower);
+        let mut d = round(&self.diag);
+        let mut du = round(&self.upper);
+        let mut du2 = vec![T::ZERO; n.saturating_sub(2)];
+        let mut swapped = vec![false; n.saturating_sub(1)];
+
+        for i in 0..n.saturating_sub(1) {
+            if d[i].abs() >= dl[i].abs() {
+                if d[i] != T::ZERO {
+                    let fact = dl[i] / d[i];
+                    dl[i] = fact;
+                    d[i + 1] -= fact * du[i];
+                }
+            } else {
+                let fact = d[i] / dl[i];
+                d[i] = dl[i];
+                dl[i] = fact;
+                let temp = du[i];
+                du[i] = d[i + 1];
+                d[i + 1] = temp - fact * d[i + 1];
+                if i + 2 < n {
+                    du2[i] = du[i + 1];
+                    du[i + 1] *= -fact;
+                }
+                swapped[i] = true;
+            }
+        }
+
+        if let Some(pivot) = d.iter().position(|&p| p == T::ZERO || !p.is_finite()) {
+            return Err(LinalgError::Singular { pivot });
+        }
+
+        Ok(TridiagonalLu {
+            dl,
+            d,
+            du,
+            du2,
+            swapped,
+        })
+    }
+
+    /// Solve `T x = b` with a one-off factorisation.
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, LinalgError> {
+        self.lu()?.solve(b)
+    }
+}
+
+/// Pivoted LU factors of a [`TridiagonalMatrix`], reusable across right-hand sides.
+///
+/// `U` has two super-diagonals because row interchanges introduce fill. The
+/// factors are stored in precision `T`; right-hand sides are rounded to `T` on the
+/// way in and solutions widened back to `f64`.
+#[derive(Debug, Clone)]
+pub struct TridiagonalLu<T: Scalar = f64> {
+    dl: Vec<T>,
+    d: Vec<T>,
+    du: Vec<T>,
+    du2: Vec<T>,
+    swapped: Vec<bool>,
+}
+
+impl<T: Scalar> TridiagonalLu<T> {
+    pub fn size(&self) -> usize {
+        self.d.len()
+    }
+
+    /// Diagonal of `U`; its smallest magnitude indicates how close to singular the matrix is.
+    pub fn pivots(&self) -> &[T] {
+        &self.d
+    }
+
+    /// Solve `T x = b` using the stored factors.
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, LinalgError> {
+        let n = self.size();
+        LinalgError::check_len("right-hand side", n, b.len())?;
+
+        let mut x: Vec<T> = b.iter().copied().map(T::from_f64).collect();
+        for i in 0..n.saturating_sub(1) {
+            if self.swapped[i] {
+                let temp = x[i] - self.dl[i] * x[i + 1];
+                x[i] = x[i + 1];
+                x[i + 1] = temp;
+            } else {
+                let xi = x[i];
+                x[i + 1] -= self.dl[i] * xi;
+            }
+        }
+
+        x[n - 1] /= self.d[n - 1];
+        if n > 1 {
+            x[n - 2] = (x[n - 2] - self.du[n - 2] * x[n - 1]) / self.d[n - 2];
+        }
+        for i in (0..n.saturating_sub(2)).rev() {
+            x[i] = (x[i] - self.du[i] * x[i + 1] - self.du2[i] * x[i + 2]) / self.d[i];
+        }
+
+        Ok(x.into_iter().map(T::to_f64).collect())
+    }
+
+    /// Solve `T^T x = b` using the stored factors.
+    pub fn solve_transpose(&self, b: &[f64]) -> Result<Vec<f64>, LinalgError> {
+        let n = self.size();
+        LinalgError::check_len("right-hand side", n, b.len())?;
+
+        let mut x: Vec<T> = b.iter().copied().map(T::from_f64).collect();
+        x[0] /= self.d[0];
+        if n > 1 {
+            x[1] = (x[1] - self.du[0] * x[0]) / self.d[1];
+        }
+        for i in 2..n {
+            x[i] = (x[i] - self.du[i - 1] * x[i - 1] - self.du2[i - 2] * x[i - 2]) / self.d[i];
+        }
+
+        for i in (0..n.saturating_sub(1)).rev() {
+            let temp = x[i] - self.dl[i] * x[i + 1];
+            if self.swapped[i] {
+                x[i] = x[i + 1];
+                x[i + 1] = temp;
+            } else {
+                x[i] = temp;
+            }
+        }
+
+        Ok(x.into_iter().map(T::to_f64).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TridiagonalMatrix;
+    use crate::linalg::{LinalgError, max_abs, vector_subtract};
+
+    fn poisson(n: usize) -> TridiagonalMatrix {
+        TridiagonalMatrix::new(vec![-1.0; n - 1], vec![2.0; n], vec![-1.0; n - 1])
+            .expect("valid matrix")
+    }
+
+    #[test]
+    fn construction_validates_lengths() {
+        let err = TridiagonalMatrix::new(vec![1.0; 3], vec![2.0; 3], vec![1.0; 2]).unwrap_err();
+
+        assert_eq!(
+            err,
+            LinalgError::DimensionMismatch {
+                what: "lower diagonal",
+                expected: 2,
+                found: 3
+            }
+        );
+        assert_eq!(
+            TridiagonalMatrix::new(vec![], vec![], vec![]).unwrap_err(),
+            LinalgError::Empty
+        );
+    }
+
+    #[test]
+    fn factorisation_is_reused_across_right_hand_sides() {
+        let t = poisson(6);
+        let lu = t.lu().expect("nonsingular");
+
+        for k in 0..3 {
+            let x: Vec<f64> = (0..6).map(|i| ((i + k) as f64).sin()).collect();
+            let b = t.matvec(&x).unwrap();
+            let solved = lu.solve(&b).unwrap();
+            assert!(max_abs(&vector_subtract(&solved, &x)) < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn pivoting_handles_zero_leading_diagonal() {
+        let t = TridiagonalMatrix::new(vec![1.0, 1.0], vec![0.0, 0.0, 1.0], vec![1.0, 2.0])
+            .expect("valid matrix");
+        let x = [1.0, 2.0, 3.0];
+        let b = t.matvec(&x).unwrap();
+        let solved = t.solve(&b).expect("matrix is nonsingular");
+
+        assert!(max_abs(&vector_subtract(&solved, &x)) < 1.0e-12);
+    }
+
+    #[test]
+    fn transpose_solve_matches_transposed_matrix() {
+        let t = TridiagonalMatrix::new(vec![1.0, 1.0], vec![0.0, 0.0, 1.0], vec![1.0, 2.0])
+            .expect("valid matrix");
+        let tt = TridiagonalMatrix::new(vec![1.0, 2.0], vec![0.0, 0.0, 1.0], vec![1.0, 1.0])
+            .expect("valid matrix");
+        let b = [1.0, -1.0, 2.0];
+
+        let x = t.lu().unwrap().solve_transpose(&b).unwrap();
+        assert!(max_abs(&vector_subtract(&tt.matvec(&x).unwrap(), &b)) < 1.0e-12);
+    }
+
+    #[test]
+    fn condition_estimate_matches_exact_inverse_norm() {
+        // The inverse of the 1D Laplacian is known in closed form:
+        // (T^{-1})_{ij} = min(i, j) (n + 1 - max(i, j)) / (n + 1) with 1-based indices.
+        let n = 7;
+        let t = poisson(n);
+        let exact_inverse_norm = (1..=n)
+            .map(|i| {
+                (1..=n)
+                    .map(|j| (i.min(j) * (n + 1 - i.max(j))) as f64 / (n + 1) as f64)
+                    .sum::<f64>()
+            })
+            .fold(0.0, f64::max);
+        let lu = t.lu().unwrap();
+        let b = vec![1.0; n];
+        let x = lu.solve(&b).unwrap();
+        let diag = t.diagnostics(&lu, &x, &b).unwrap();
+
+        assert!((diag.condition - 4.0 * exact_inverse_norm).abs() < 1.0e-10);
+        assert!(diag.normwise_backward < 1.0e-15);
+        assert!(diag.componentwise_backward < 1.0e-15);
+    }
+
+    #[test]
+    fn singular_matrix_is_reported() {
+        // Rows 0 and 1 are identical.
+        let t = TridiagonalMatrix::new(vec![1.0, 1.0], vec![1.0, 1.0, 1.0], vec![1.0, 0.0])
+            .expect("valid matrix");
+
+        assert!(matches!(t.lu(), Err(LinalgError::Singular { .. })));
+    }
+}
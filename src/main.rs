@@ -6,155 +6,411 @@ mod ch2_4;
 mod ch2_5;
 mod ch2_6;
 mod ch2_7;
-mod util;
-use std::env;
-use std::{fs, io};
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    // let [a, b, c] = ch2_1::magnitudes(1.92, 2.08);
-    // println!("{:.4e} {:.4e} {:.4e}", a, b, c);
-    if args.len() < 2 {
-        eprintln!(
-            "Please provide the chapter/section number (e.g. '1', '2.1', '2.2', '2.3', '2.4', '2.5', '2.6')"
-        );
-        return;
-    }
-    match args[1].as_str() {
-        "1" => {
-            make_dirs("ch1").expect("Error making directories.");
-            ch1p1();
-            ch1p2();
-        }
-        "2.1" => {
-            make_dirs("ch2_1").expect("Error making directories.");
-            ch2_1();
+use clap::{Args, Parser, Subcommand};
+use mat627::config::{self, Export, Output};
+use mat627::error::Result;
+use mat627::util::{
+    self,
+    compare::{self, Tolerance},
+};
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::{self, ExitCode};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[derive(Debug, Parser)]
+#[command(name = "mat627", about = "MAT 627 programming projects")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run one or more sections (e.g. `run 2.3 2.6`, or `run all`).
+    Run(RunArgs),
+    /// List every section with a one-line description.
+    List,
+    /// Compare the .npy files of two data directories and report any drift.
+    Compare(CompareArgs),
+}
+
+#[derive(Debug, Args)]
+struct CompareArgs {
+    /// The reference run, e.g. `data/ch2_7`.
+    old: PathBuf,
+    /// The run to check, e.g. `/tmp/new/data/ch2_7`.
+    new: PathBuf,
+    /// Relative tolerance for floating-point values.
+    #[arg(long, default_value_t = Tolerance::default().rel)]
+    rtol: f64,
+    /// Absolute tolerance for floating-point values.
+    #[arg(long, default_value_t = Tolerance::default().abs)]
+    atol: f64,
+    /// Also accept floating-point values within this many units in the last place.
+    #[arg(long, default_value_t = Tolerance::default().ulps)]
+    ulps: u64,
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Section numbers as shown by `list`, or `all`.
+    #[arg(required = true, value_name = "SECTION")]
+    sections: Vec<String>,
+    /// Skip the Python plotting stage.
+    #[arg(long)]
+    no_plot: bool,
+    /// Skip the table generation and latexmk stage.
+    #[arg(long)]
+    no_report: bool,
+    /// Only write the .npy data (same as `--no-plot --no-report`).
+    #[arg(long)]
+    data_only: bool,
+    /// Write into DIR/data, DIR/plots and DIR/reports instead of the repo root.
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+    /// Also write each result table as csv and/or jsonl next to its .npy file.
+    #[arg(long, value_name = "FORMAT", value_delimiter = ',')]
+    export: Vec<Export>,
+    /// Run up to N sections at once, each in its own process; defaults to the
+    /// number of CPUs. Output is still printed section by section, in order.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+}
+
+impl RunArgs {
+    /// Apply the command-line overrides on top of a chapter's configured
+    /// output and create the resulting directories.
+    fn apply(&self, output: &mut Output, chapter: &str) -> Result<()> {
+        if let Some(root) = &self.out_dir {
+            output.relocate(root, chapter);
         }
-        "2.2" => {
-            make_dirs("ch2_2").expect("Error making directories.");
-            ch2_2();
+        if self.no_plot || self.data_only {
+            output.plot = false;
         }
-        "2.3" => {
-            make_dirs("ch2_3").expect("Error making directories.");
-            ch2_3();
+        if self.no_report || self.data_only {
+            output.report = false;
         }
-        "2.4" => {
-            make_dirs("ch2_4").expect("Error making directories.");
-            ch2_4();
+        if !self.export.is_empty() {
+            output.export = self.export.clone();
         }
-        "2.5" => {
-            make_dirs("ch2_5").expect("Error making directories.");
-            ch2_5();
+        output.paths(chapter).create_dirs()?;
+        Ok(())
+    }
+
+    /// The arguments that rerun `section` alone with the same options.
+    fn child_args(&self, section: &str) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec!["run".into(), section.into(), "--jobs=1".into()];
+        for (set, flag) in [
+            (self.no_plot, "--no-plot"),
+            (self.no_report, "--no-report"),
+            (self.data_only, "--data-only"),
+        ] {
+            if set {
+                args.push(flag.into());
+            }
         }
-        "2.6" => {
-            make_dirs("ch2_6").expect("Error making directories.");
-            ch2_6();
+        if let Some(root) = &self.out_dir {
+            args.push("--out-dir".into());
+            args.push(root.into());
         }
-        "2.7" => {
-            make_dirs("ch2_7").expect("Error making directories.");
-            ch2_7();
+        if !self.export.is_empty() {
+            let formats: Vec<String> = self.export.iter().map(Export::to_string).collect();
+            args.push(format!("--export={}", formats.join(",")).into());
         }
-
-        _ => println!("Chapter/section unrecognized."),
+        args
     }
 }
 
-fn make_dirs(name: &str) -> io::Result<()> {
-    let dirs: [&'static str; 4] = ["scripts", "reports", "plots", "data"];
-    for d in dirs {
-        let path = format!("{}/{}", d, name);
-        fs::create_dir_all(path)?;
+struct Section {
+    id: &'static str,
+    description: &'static str,
+    run: fn(&RunArgs) -> Result<()>,
+}
+
+const SECTIONS: [Section; 8] = [
+    Section {
+        id: "1",
+        description: "Taylor series for e^{-x} and floating-point format properties",
+        run: ch1,
+    },
+    Section {
+        id: "2.1",
+        description: "Standard vs. Horner polynomial evaluation near a multiple root",
+        run: ch2_1,
+    },
+    Section {
+        id: "2.2",
+        description: "Finite-difference derivatives and their observed order",
+        run: ch2_2,
+    },
+    Section {
+        id: "2.3",
+        description: "ODE integrators, Richardson extrapolation and adaptive stepping",
+        run: ch2_3,
+    },
+    Section {
+        id: "2.4",
+        description: "Piecewise-linear interpolation of x^{1/3} on smooth and singular intervals",
+        run: ch2_4,
+    },
+    Section {
+        id: "2.5",
+        description: "Romberg, Gauss and adaptive quadrature",
+        run: ch2_5,
+    },
+    Section {
+        id: "2.6",
+        description: "Tridiagonal solves, conditioning and iterative refinement",
+        run: ch2_6,
+    },
+    Section {
+        id: "2.7",
+        description: "Finite-difference boundary value problems",
+        run: ch2_7,
+    },
+];
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::List => {
+            for section in &SECTIONS {
+                println!("{:<5} {}", section.id, section.description);
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Run(args) => run(&args),
+        Command::Compare(args) => compare(&args),
     }
+}
 
-    Ok(())
+/// Print every difference between the two runs; exit code 1 if there is any.
+fn compare(args: &CompareArgs) -> ExitCode {
+    let tolerance = Tolerance {
+        rel: args.rtol,
+        abs: args.atol,
+        ulps: args.ulps,
+    };
+    match compare::compare_dirs(&args.old, &args.new, tolerance) {
+        Ok(drift) if drift.is_empty() => {
+            println!("{} and {} agree", args.old.display(), args.new.display());
+            ExitCode::SUCCESS
+        }
+        Ok(drift) => {
+            for d in &drift {
+                println!("{d}");
+            }
+            eprintln!("{} difference(s) found", drift.len());
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
-fn ch1p1() {
-    println!("\n=== Chapter 1 Problem 1 ===");
-    ch1::test_p1(ch1::ApproximationType::Alternating);
-    ch1::test_p1(ch1::ApproximationType::Reciprocal);
-    println!("Done.");
 
-    println!("Plotting data...");
+/// Run the requested sections. Unknown sections are rejected before anything
+/// runs (exit code 2); a failing section is reported and the rest still run
+/// (exit code 1).
+fn run(args: &RunArgs) -> ExitCode {
+    let mut selected: Vec<&Section> = Vec::new();
+    for id in &args.sections {
+        if id == "all" {
+            selected.extend(SECTIONS.iter());
+            continue;
+        }
+        match SECTIONS.iter().find(|s| s.id == id) {
+            Some(section) => selected.push(section),
+            None => {
+                eprintln!("error: unknown section '{id}' (see `list` for the available sections)");
+                return ExitCode::from(2);
+            }
+        }
+    }
 
-    util::plot("ch0").expect("plot error");
+    let jobs = args
+        .jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .min(selected.len());
+    let failed = if jobs > 1 {
+        run_concurrently(args, &selected, jobs)
+    } else {
+        run_in_order(args, &selected)
+    };
 
-    println!("Done.");
+    if failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        if selected.len() > 1 {
+            eprintln!("{} section(s) failed: {}", failed.len(), failed.join(", "));
+        }
+        ExitCode::FAILURE
+    }
 }
 
-fn ch1p2() {
-    println!("\n=== Chapter 1 Problem 2 ===");
-
-    ch1::test_p2().expect("error");
+/// Run `selected` one after another in this process; returns the failed ids.
+fn run_in_order(args: &RunArgs, selected: &[&Section]) -> Vec<&'static str> {
+    let mut failed = Vec::new();
+    for section in selected {
+        if let Err(e) = (section.run)(args) {
+            eprintln!("error: section {} failed: {e}", section.id);
+            failed.push(section.id);
+        }
+    }
+    failed
 }
 
-fn ch2_1() {
-    let a = 1.92;
-    let b = 2.08;
-    println!("Polynomial p(x) = (x-2)^9 on the interval [1.92, 2.08]");
-    println!("Comparing Standard vs. Horners at N=1000...\n");
-    {
-        let [standard, horners] = ch2_1::compare_methods::<1000>(a, b);
+/// Run each of `selected` as `run <id>` in a child process, `jobs` at a time.
+///
+/// The chapters share no state, so they may overlap freely; capturing each
+/// child's output keeps it in one piece, and it is replayed in section order
+/// once everything has finished. Returns the failed ids.
+fn run_concurrently(args: &RunArgs, selected: &[&Section], jobs: usize) -> Vec<&'static str> {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("warning: cannot start sections concurrently ({e}); running them in order");
+            return run_in_order(args, selected);
+        }
+    };
+
+    let outputs: Vec<Mutex<Option<io::Result<process::Output>>>> =
+        selected.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(section) = selected.get(i) else {
+                        break;
+                    };
+                    let output = process::Command::new(&exe)
+                        .args(args.child_args(section.id))
+                        .output();
+                    *outputs[i]
+                        .lock()
+                        .expect("no worker panics while holding a slot") = Some(output);
+                }
+            });
+        }
+    });
 
-        println!("Standard Evaluation Max Absolute Error: {:.4e}", standard);
-        println!("Horners Evaluation Max Absolute Error:  {:.4e}", horners);
+    let mut failed = Vec::new();
+    for (section, output) in selected.iter().zip(outputs) {
+        let output = output
+            .into_inner()
+            .expect("no worker panics while holding a slot")
+            .expect("every section was started");
+        match output {
+            Ok(output) => {
+                let _ = io::stdout().write_all(&output.stdout);
+                let _ = io::stderr().write_all(&output.stderr);
+                if !output.status.success() {
+                    failed.push(section.id);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: section {} could not be started: {e}", section.id);
+                failed.push(section.id);
+            }
+        }
     }
+    failed
+}
+
+fn ch1(args: &RunArgs) -> Result<()> {
+    let mut config: ch1::Config = config::load("ch1")?;
+    args.apply(&mut config.output, "ch1")?;
 
-    println!("\nComparing Standard vs. Horners at N=100,000...\n");
-    {
-        let [standard, horners] = ch2_1::compare_methods::<100_000>(a, b);
+    println!("\n=== Chapter 1 Problem 1 ===");
+    ch1::test_p1(&config, ch1::ApproximationType::Alternating)?;
+    ch1::test_p1(&config, ch1::ApproximationType::Reciprocal)?;
+    ch1::compare_summations(&config, ch1::ApproximationType::Alternating)?;
+    ch1::compare_summations(&config, ch1::ApproximationType::Reciprocal)?;
+    ch1::compare_accelerations(&config, ch1::ApproximationType::Alternating)?;
+    ch1::compare_accelerations(&config, ch1::ApproximationType::Reciprocal)?;
+    println!("Done.");
 
-        println!("Standard Evaluation Max Absolute Error: {:.4e}", standard);
-        println!("Horners Evaluation Max Absolute Error:  {:.4e}", horners);
+    if config.output.plot {
+        println!("Plotting data...");
+        util::plot("ch1", &config.output.paths("ch1"))?;
+        println!("Done.");
     }
 
-    println!("\n(Grad) Plotting Exact, Standard, and Horners Evalutations at N=100_000...");
-    ch2_1::plot_methods::<100_000>(a, b).expect("Error plotting");
+    println!("\n=== Chapter 1 Problem 2 ===");
+    ch1::test_p2(&config)
+}
+
+fn ch2_1(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_1::Config = config::load("ch2_1")?;
+    args.apply(&mut config.output, "ch2_1")?;
+    ch2_1::generate(&config)
 }
-fn ch2_2() {
+
+fn ch2_2(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_2::Config = config::load("ch2_2")?;
+    args.apply(&mut config.output, "ch2_2")?;
     println!("\n=== Chapter 2.2 Programming Project ===");
-    let rows = ch2_2::compare(1.0);
-    for r in &rows {
-        println!(
-            "{:<10} {:<8} h={:<12.5e} approx={:<16.8e} err={:<16.8e} order={:?}",
-            r.case,
-            r.method,
-            r.h,
-            r.approx,
-            r.err,
-            r.order.unwrap_or(0.0)
-        );
-    }
-    println!("Plotting Data...");
-    ch2_2::write_compare_npy(&rows);
-    util::plot("ch2_2").expect("Error plotting values.");
-    println!("View report in reports/ch2_2/2.2.pdf");
+    ch2_2::generate(&config)
 }
 
-fn ch2_3() {
+fn ch2_3(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_3::Config = config::load("ch2_3")?;
+    args.apply(&mut config.output, "ch2_3")?;
     println!("\n=== Chapter 2.3 Programming Project ===");
-    ch2_3::generate().expect("Error generating chapter 2.3 outputs");
-    println!("View report in reports/ch2_3/2.3.pdf");
+    ch2_3::generate(&config)?;
+    report_location(&config.output, "ch2_3", "2.3.pdf");
+    Ok(())
 }
 
-fn ch2_4() {
+fn ch2_4(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_4::Config = config::load("ch2_4")?;
+    args.apply(&mut config.output, "ch2_4")?;
     println!("\n=== Chapter 2.4 Programming Project ===");
-    ch2_4::generate().expect("Error generating chapter 2.4 outputs");
-    println!("View report in reports/ch2_4/2.4.pdf");
+    ch2_4::generate(&config)?;
+    report_location(&config.output, "ch2_4", "2.4.pdf");
+    Ok(())
 }
 
-fn ch2_5() {
+fn ch2_5(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_5::Config = config::load("ch2_5")?;
+    args.apply(&mut config.output, "ch2_5")?;
     println!("\n=== Chapter 2.5 Programming Project ===");
-    ch2_5::generate().expect("Error generating chapter 2.5 outputs");
-    println!("View report in reports/ch2_5/2.5.pdf");
+    ch2_5::generate(&config)?;
+    report_location(&config.output, "ch2_5", "2.5.pdf");
+    Ok(())
 }
 
-fn ch2_6() {
+fn ch2_6(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_6::Config = config::load("ch2_6")?;
+    args.apply(&mut config.output, "ch2_6")?;
     println!("\n=== Chapter 2.6 Programming Project ===");
-    ch2_6::generate().expect("Error generating chapter 2.6 outputs");
-    println!("View report in reports/ch2_6/2.6.pdf");
+    ch2_6::generate(&config)?;
+    report_location(&config.output, "ch2_6", "2.6.pdf");
+    Ok(())
 }
 
-fn ch2_7() {
+fn ch2_7(args: &RunArgs) -> Result<()> {
+    let mut config: ch2_7::Config = config::load("ch2_7")?;
+    args.apply(&mut config.output, "ch2_7")?;
     println!("\n=== Chapter 2.7 Programming Project ===");
-    ch2_7::generate().expect("Error generating chapter 2.7 outputs");
-    println!("View report in reports/ch2_7/2.7.pdf");
+    ch2_7::generate(&config)?;
+    report_location(&config.output, "ch2_7", "2.7.pdf");
+    Ok(())
+}
+
+fn report_location(output: &Output, chapter: &str, name: &str) {
+    if output.report {
+        println!("View report in {}/{name}", output.paths(chapter).reports);
+    } else {
+        println!("Data written to {}", output.paths(chapter).data);
+    }
 }
@@ -0,0 +1,590 @@
+use crate::error::{Mat627Error, Result};
+use crate::extrapolation::{self, Tableau};
+
+/// An initial value problem `y' = f(t, y)`, `y(t0) = y0` on `[t0, t1]` with
+/// state `y` in `R^N`.
+pub trait OdeProblem<const N: usize> {
+    /// Right-hand side `f(t, y)`.
+    fn rhs(&self, t: f64, y: &[f64; N]) -> [f64; N];
+
+    /// Integration interval `(t0, t1)`.
+    fn t_span(&self) -> (f64, f64);
+
+    /// Initial condition `y(t0)`.
+    fn initial(&self) -> [f64; N];
+}
+
+/// An [`OdeProblem`] built from a right-hand side closure.
+#[derive(Debug, Clone, Copy)]
+pub struct Ivp<F, const N: usize> {
+    pub f: F,
+    pub t0: f64,
+    pub t1: f64,
+    pub y0: [f64; N],
+}
+
+impl<F, const N: usize> Ivp<F, N>
+where
+    F: Fn(f64, &[f64; N]) -> [f64; N],
+{
+    pub fn new(f: F, t0: f64, t1: f64, y0: [f64; N]) -> Self {
+        Self { f, t0, t1, y0 }
+    }
+}
+
+impl<F, const N: usize> OdeProblem<N> for Ivp<F, N>
+where
+    F: Fn(f64, &[f64; N]) -> [f64; N],
+{
+    fn rhs(&self, t: f64, y: &[f64; N]) -> [f64; N] {
+        (self.f)(t, y)
+    }
+
+    fn t_span(&self) -> (f64, f64) {
+        (self.t0, self.t1)
+    }
+
+    fn initial(&self) -> [f64; N] {
+        self.y0
+    }
+}
+
+/// A one-step method advancing `problem` from `(t, y)` by `h`.
+pub type StepFn<P, const N: usize> = fn(&P, f64, &[f64; N], f64) -> [f64; N];
+
+/// `y + a * k`, componentwise.
+pub(crate) fn axpy<const N: usize>(y: &[f64; N], a: f64, k: &[f64; N]) -> [f64; N] {
+    std::array::from_fn(|i| y[i] + a * k[i])
+}
+
+/// Explicit Euler step, first order in `h`.
+pub fn euler_step<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    t: f64,
+    y: &[f64; N],
+    h: f64,
+) -> [f64; N] {
+    axpy(y, h, &problem.rhs(t, y))
+}
+
+/// Classical fourth-order Runge-Kutta step.
+pub fn rk4_step<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    t: f64,
+    y: &[f64; N],
+    h: f64,
+) -> [f64; N] {
+    let k1 = problem.rhs(t, y);
+    let k2 = problem.rhs(t + 0.5 * h, &axpy(y, 0.5 * h, &k1));
+    let k3 = problem.rhs(t + 0.5 * h, &axpy(y, 0.5 * h, &k2));
+    let k4 = problem.rhs(t + h, &axpy(y, h, &k3));
+
+    std::array::from_fn(|i| y[i] + h * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) / 6.0)
+}
+
+/// Number of steps of size `h` covering `[start, end]`.
+///
+/// Fails with `InvalidInput` unless `(end - start) / h` is a non-negative integer.
+pub fn step_count(start: f64, end: f64, h: f64) -> Result<usize> {
+    let n = (end - start) / h;
+    let rounded = n.round();
+    if !(n.is_finite() && rounded >= 0.0 && (n - rounded).abs() < 1.0e-12) {
+        return Err(Mat627Error::InvalidInput(format!(
+            "step {h} does not divide [{start}, {end}]"
+        )));
+    }
+    Ok(rounded as usize)
+}
+
+/// Integrate `problem` over its time span with a fixed step `h`.
+///
+/// Returns every `(t, y)` pair including the initial condition.
+pub fn solve<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    step: StepFn<P, N>,
+    h: f64,
+) -> Result<Vec<(f64, [f64; N])>> {
+    let (t0, t1) = problem.t_span();
+    let n = step_count(t0, t1, h)?;
+    let mut rows = Vec::with_capacity(n + 1);
+    let mut t = t0;
+    let mut y = problem.initial();
+
+    rows.push((t, y));
+    for k in 0..n {
+        y = step(problem, t, &y, h);
+        t = t0 + (k + 1) as f64 * h;
+        rows.push((t, y));
+    }
+
+    Ok(rows)
+}
+
+/// Euler's method with one Richardson step per pair of steps, `2 z2 - z_bar`.
+///
+/// Returns the approximation at the end of the time span.
+pub fn extrapolated_euler<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    h: f64,
+) -> Result<[f64; N]> {
+    let (t0, t1) = problem.t_span();
+    let n = step_count(t0, t1, 2.0 * h)?;
+    let mut t = t0;
+    let mut yr = problem.initial();
+
+    for _ in 0..n {
+        let z1 = euler_step(problem, t, &yr, h);
+        let z2 = euler_step(problem, t + h, &z1, h);
+        let z_bar = euler_step(problem, t, &yr, 2.0 * h);
+
+        yr = std::array::from_fn(|i| extrapolation::richardson_step(z_bar[i], z2[i], 2.0, 1.0));
+        t += 2.0 * h;
+    }
+
+    Ok(yr)
+}
+
+/// Richardson extrapolation of the endpoint value of a fixed-step method.
+///
+/// Solves with `h0 / 2^i` for `i = 0..=orders.len()` and builds one tableau per
+/// state component. `orders` is the global error expansion of `step`, e.g.
+/// `[1, 2, 3, ...]` for Euler.
+pub fn richardson_endpoint<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    step: StepFn<P, N>,
+    h0: f64,
+    orders: &[f64],
+) -> Result<[Tableau; N]> {
+    let ends = (0..=orders.len())
+        .map(|i| {
+            let h = h0 / 2.0_f64.powi(i as i32);
+            let rows = solve(problem, step, h)?;
+            Ok(rows.last().expect("solve includes the initial condition").1)
+        })
+        .collect::<Result<Vec<[f64; N]>>>()?;
+
+    let tableaus = (0..N)
+        .map(|c| {
+            let column: Vec<f64> = ends.iter().map(|y| y[c]).collect();
+            extrapolation::tableau_from(&column, h0, 2.0, orders)
+        })
+        .collect::<Result<Vec<Tableau>>>()?;
+    Ok(tableaus.try_into().expect("one tableau per component"))
+}
+
+/// Error tolerances for adaptive integration: each component must satisfy
+/// `|err_i| <= atol + rtol * |y_i|`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub rtol: f64,
+    pub atol: f64,
+}
+
+/// One attempted step of an adaptive integrator.
+#[derive(Debug, Clone, Copy)]
+pub struct StepRecord {
+    pub t: f64,
+    pub h: f64,
+    /// Embedded error estimate scaled by the tolerance; the step is accepted when `<= 1`.
+    pub err: f64,
+    pub accepted: bool,
+}
+
+/// Output of [`dormand_prince`]: accepted mesh, full step history and dense output.
+#[derive(Debug, Clone)]
+pub struct AdaptiveSolution<const N: usize> {
+    pub t: Vec<f64>,
+    pub y: Vec<[f64; N]>,
+    pub steps: Vec<StepRecord>,
+    pub evaluations: usize,
+    dense: Vec<[[f64; N]; 5]>,
+}
+
+impl<const N: usize> AdaptiveSolution<N> {
+    pub fn accepted(&self) -> usize {
+        self.steps.iter().filter(|step| step.accepted).count()
+    }
+
+    pub fn rejected(&self) -> usize {
+        self.steps.len() - self.accepted()
+    }
+
+    /// Approximation at the end of the time span.
+    pub fn last(&self) -> [f64; N] {
+        *self.y.last().expect("adaptive solution has no states")
+    }
+
+    /// Fourth-order continuous extension evaluated anywhere in the time span.
+    ///
+    /// On an empty span (`t0 == t1`) there is no step to interpolate and the
+    /// initial state is returned. Fails with `InvalidInput` when `t` lies
+    /// outside the span.
+    pub fn eval(&self, t: f64) -> Result<[f64; N]> {
+        let t0 = self.t[0];
+        let t1 = self.t[self.t.len() - 1];
+        if (t - t0) * (t - t1) > 1.0e-12 * (t1 - t0).abs() || t.is_nan() {
+            return Err(Mat627Error::InvalidInput(format!(
+                "t = {t} lies outside the integration interval [{t0}, {t1}]"
+            )));
+        }
+        if self.dense.is_empty() {
+            return Ok(self.y[0]);
+        }
+
+        let i = self.t[1..]
+            .partition_point(|&tk| (tk - t) * (t1 - t0) < 0.0)
+            .min(self.dense.len() - 1);
+        let h = self.t[i + 1] - self.t[i];
+        let theta = (t - self.t[i]) / h;
+        let theta1 = 1.0 - theta;
+        let [r1, r2, r3, r4, r5] = &self.dense[i];
+
+        Ok(std::array::from_fn(|j| {
+            r1[j] + theta * (r2[j] + theta1 * (r3[j] + theta * (r4[j] + theta1 * r5[j])))
+        }))
+    }
+}
+
+/// Root-mean-square of `v / scale`, componentwise.
+fn scaled_norm<const N: usize>(v: &[f64; N], scale: &[f64; N]) -> f64 {
+    let sum: f64 = v.iter().zip(scale).map(|(vi, si)| (vi / si).powi(2)).sum();
+    (sum / N as f64).sqrt()
+}
+
+/// Starting step size from Hairer, Norsett & Wanner, Section II.4.
+fn initial_step<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    t0: f64,
+    y0: &[f64; N],
+    f0: &[f64; N],
+    span: f64,
+    tol: Tolerance,
+) -> f64 {
+    let scale: [f64; N] = std::array::from_fn(|i| tol.atol + tol.rtol * y0[i].abs());
+    let d0 = scaled_norm(y0, &scale);
+    let d1 = scaled_norm(f0, &scale);
+    let h0 = if d0 < 1.0e-5 || d1 < 1.0e-5 {
+        1.0e-6
+    } else {
+        0.01 * d0 / d1
+    };
+
+    let f1 = problem.rhs(t0 + h0, &axpy(y0, h0, f0));
+    let df: [f64; N] = std::array::from_fn(|i| f1[i] - f0[i]);
+    let d2 = scaled_norm(&df, &scale) / h0;
+    let h1 = if d1.max(d2) <= 1.0e-15 {
+        (h0 * 1.0e-3).max(1.0e-6)
+    } else {
+        (0.01 / d1.max(d2)).powf(0.2)
+    };
+
+    (100.0 * h0).min(h1).min(span)
+}
+
+/// Adaptive Dormand-Prince RK5(4) integrator with dense output.
+///
+/// The fifth-order solution is propagated and the embedded fourth-order
+/// solution supplies the local error estimate. Negative or all-zero tolerances
+/// are `InvalidInput`; a step size shrinking below rounding level is a `Breakdown`.
+/// A step whose error estimate is NaN (e.g. the right-hand side returned NaN)
+/// is rejected with the largest reduction, so a persistent NaN ends in that
+/// `Breakdown` rather than looping.
+pub fn dormand_prince<P: OdeProblem<N>, const N: usize>(
+    problem: &P,
+    tol: Tolerance,
+) -> Result<AdaptiveSolution<N>> {
+    const C: [f64; 5] = [1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0];
+    const A2: [f64; 1] = [1.0 / 5.0];
+    const A3: [f64; 2] = [3.0 / 40.0, 9.0 / 40.0];
+    const A4: [f64; 3] = [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0];
+    const A5: [f64; 4] = [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ];
+    const A6: [f64; 5] = [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ];
+    const B: [f64; 6] = [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ];
+    const E: [f64; 7] = [
+        71.0 / 57600.0,
+        0.0,
+        -71.0 / 16695.0,
+        71.0 / 1920.0,
+        -17253.0 / 339200.0,
+        22.0 / 525.0,
+        -1.0 / 40.0,
+    ];
+    const D: [f64; 7] = [
+        -12715105075.0 / 11282082432.0,
+        0.0,
+        87487479700.0 / 32700410799.0,
+        -10690763975.0 / 1880347072.0,
+        701980252875.0 / 199316789632.0,
+        -1453857185.0 / 822651844.0,
+        69997945.0 / 29380423.0,
+    ];
+
+    if !(tol.rtol >= 0.0 && tol.atol >= 0.0 && (tol.rtol > 0.0 || tol.atol > 0.0)) {
+        return Err(Mat627Error::InvalidInput(format!(
+            "tolerances must be non-negative and not both zero (rtol = {}, atol = {})",
+            tol.rtol, tol.atol
+        )));
+    }
+
+    let (t0, t1) = problem.t_span();
+    let span = t1 - t0;
+    let direction = span.signum();
+    let combine = |y: &[f64; N], h: f64, coeffs: &[f64], ks: &[[f64; N]]| -> [f64; N] {
+        std::array::from_fn(|i| {
+            y[i] + h * coeffs.iter().zip(ks).map(|(c, k)| c * k[i]).sum::<f64>()
+        })
+    };
+
+    let mut t = t0;
+    let mut y = problem.initial();
+    let mut k1 = problem.rhs(t, &y);
+    let mut evaluations = 1;
+    let mut h = direction * initial_step(problem, t0, &y, &k1, span.abs(), tol);
+    evaluations += 1;
+
+    let mut solution = AdaptiveSolution {
+        t: vec![t],
+        y: vec![y],
+        steps: Vec::new(),
+        evaluations: 0,
+        dense: Vec::new(),
+    };
+    let mut last_rejected = false;
+
+    while (t1 - t) * direction > 0.0 {
+        if h.is_nan() || h.abs() <= 16.0 * f64::EPSILON * t.abs().max(1.0) {
+            return Err(Mat627Error::Breakdown(format!(
+                "step size underflow at t = {t}"
+            )));
+        }
+        if (t + h - t1) * direction > 0.0 {
+            h = t1 - t;
+        }
+
+        let k2 = problem.rhs(t + C[0] * h, &combine(&y, h, &A2, &[k1]));
+        let k3 = problem.rhs(t + C[1] * h, &combine(&y, h, &A3, &[k1, k2]));
+        let k4 = problem.rhs(t + C[2] * h, &combine(&y, h, &A4, &[k1, k2, k3]));
+        let k5 = problem.rhs(t + C[3] * h, &combine(&y, h, &A5, &[k1, k2, k3, k4]));
+        let k6 = problem.rhs(t + C[4] * h, &combine(&y, h, &A6, &[k1, k2, k3, k4, k5]));
+        let y_new = combine(&y, h, &B, &[k1, k2, k3, k4, k5, k6]);
+        let k7 = problem.rhs(t + h, &y_new);
+        evaluations += 6;
+
+        let ks = [k1, k2, k3, k4, k5, k6, k7];
+        let err_vec = combine(&[0.0; N], h, &E, &ks);
+        let scale: [f64; N] =
+            std::array::from_fn(|i| tol.atol + tol.rtol * y[i].abs().max(y_new[i].abs()));
+        let err = scaled_norm(&err_vec, &scale);
+        let accepted = err <= 1.0;
+
+        solution.steps.push(StepRecord {
+            t,
+            h,
+            err,
+            accepted,
+        });
+
+        let factor = if err == 0.0 {
+            5.0
+        } else if err.is_nan() {
+            0.2
+        } else {
+            (0.9 * err.powf(-0.2)).clamp(0.2, 5.0)
+        };
+
+        if accepted {
+            let ydiff: [f64; N] = std::array::from_fn(|i| y_new[i] - y[i]);
+            let bspl: [f64; N] = std::array::from_fn(|i| h * k1[i] - ydiff[i]);
+            let r4: [f64; N] = std::array::from_fn(|i| ydiff[i] - h * k7[i] - bspl[i]);
+            let r5 = combine(&[0.0; N], h, &D, &ks);
+            solution.dense.push([y, ydiff, bspl, r4, r5]);
+
+            t = if (t1 - (t + h)) * direction <= 0.0 {
+                t1
+            } else {
+                t + h
+            };
+            y = y_new;
+            k1 = k7;
+            solution.t.push(t);
+            solution.y.push(y);
+
+            h *= if last_rejected {
+                factor.min(1.0)
+            } else {
+                factor
+            };
+            last_rejected = false;
+        } else {
+            h *= factor.min(1.0);
+            last_rejected = true;
+        }
+    }
+
+    solution.evaluations = evaluations;
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Ivp, Tolerance, dormand_prince, euler_step, extrapolated_euler, rk4_step, solve, step_count,
+    };
+    use crate::error::Mat627Error;
+
+    #[test]
+    fn solve_includes_both_endpoints() {
+        let problem = Ivp::new(|_, y: &[f64; 1]| *y, 0.0, 1.0, [1.0]);
+        let rows = solve(&problem, euler_step, 0.25).expect("0.25 divides [0, 1]");
+
+        assert_eq!(rows.len(), 5);
+        assert!((rows[4].0 - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn rk4_integrates_exponential_growth() {
+        let problem = Ivp::new(|_, y: &[f64; 1]| *y, 0.0, 1.0, [1.0]);
+        let rows = solve(&problem, rk4_step, 0.01).expect("0.01 divides [0, 1]");
+        let (_, [y]) = rows.last().copied().expect("missing rk4 rows");
+
+        assert!((y - 1.0_f64.exp()).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn rk4_integrates_harmonic_oscillator_system() {
+        let pi = std::f64::consts::PI;
+        let problem = Ivp::new(|_, y: &[f64; 2]| [y[1], -y[0]], 0.0, 2.0 * pi, [1.0, 0.0]);
+        let rows = solve(&problem, rk4_step, 2.0 * pi / 200.0).expect("200 steps");
+        let (_, [x, v]) = rows.last().copied().expect("missing rk4 rows");
+
+        assert!((x - 1.0).abs() < 1.0e-7);
+        assert!(v.abs() < 1.0e-7);
+    }
+
+    #[test]
+    fn extrapolated_euler_preserves_lotka_volterra_invariant() {
+        let (a, b, c, d) = (1.0, 0.5, 1.0, 0.5);
+        let lv = Ivp::new(
+            move |_, y: &[f64; 2]| [a * y[0] - b * y[0] * y[1], d * y[0] * y[1] - c * y[1]],
+            0.0,
+            2.0,
+            [2.0, 1.0],
+        );
+        let invariant = |y: [f64; 2]| d * y[0] - c * y[0].ln() + b * y[1] - a * y[1].ln();
+
+        let end = extrapolated_euler(&lv, 1.0e-3).expect("2e-3 divides [0, 2]");
+        assert!((invariant(end) - invariant(lv.y0)).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn dormand_prince_meets_tolerance_and_lands_on_endpoint() {
+        let problem = Ivp::new(|_, y: &[f64; 1]| *y, 0.0, 1.0, [1.0]);
+        let tol = Tolerance {
+            rtol: 1.0e-8,
+            atol: 1.0e-10,
+        };
+        let solution = dormand_prince(&problem, tol).expect("valid tolerances");
+        let [y] = solution.last();
+
+        assert_eq!(*solution.t.last().expect("missing mesh"), 1.0);
+        assert!((y - 1.0_f64.exp()).abs() < 1.0e-6);
+        assert_eq!(solution.accepted(), solution.t.len() - 1);
+    }
+
+    #[test]
+    fn dense_output_matches_mesh_values_and_interpolates() {
+        let problem = Ivp::new(|_, y: &[f64; 2]| [y[1], -y[0]], 0.0, 3.0, [0.0, 1.0]);
+        let tol = Tolerance {
+            rtol: 1.0e-9,
+            atol: 1.0e-9,
+        };
+        let solution = dormand_prince(&problem, tol).expect("valid tolerances");
+
+        for (t, y) in solution.t.iter().copied().zip(&solution.y) {
+            assert!((solution.eval(t).unwrap()[0] - y[0]).abs() < 1.0e-12);
+        }
+        for t in [0.1, 0.77, 1.5, 2.9] {
+            assert!((solution.eval(t).unwrap()[0] - f64::sin(t)).abs() < 1.0e-7);
+        }
+    }
+
+    #[test]
+    fn empty_span_returns_the_initial_state() {
+        let problem = Ivp::new(|_, y: &[f64; 1]| *y, 1.0, 1.0, [2.0]);
+        let tol = Tolerance {
+            rtol: 1.0e-8,
+            atol: 1.0e-8,
+        };
+        let solution = dormand_prince(&problem, tol).expect("valid tolerances");
+
+        assert_eq!(solution.t, vec![1.0]);
+        assert_eq!(solution.eval(1.0).unwrap(), [2.0]);
+    }
+
+    #[test]
+    fn nan_right_hand_side_breaks_down() {
+        let tol = Tolerance {
+            rtol: 1.0e-6,
+            atol: 1.0e-6,
+        };
+        let everywhere = Ivp::new(|_, _: &[f64; 1]| [f64::NAN], 0.0, 1.0, [1.0]);
+        assert!(matches!(
+            dormand_prince(&everywhere, tol),
+            Err(Mat627Error::Breakdown(_))
+        ));
+
+        let past_half = Ivp::new(
+            |t, y: &[f64; 1]| if t > 0.5 { [f64::NAN] } else { *y },
+            0.0,
+            1.0,
+            [1.0],
+        );
+        assert!(matches!(
+            dormand_prince(&past_half, tol),
+            Err(Mat627Error::Breakdown(_))
+        ));
+    }
+
+    #[test]
+    fn bad_steps_and_times_are_errors() {
+        assert_eq!(step_count(1.0, 2.0, 0.25).unwrap(), 4);
+        assert!(matches!(
+            step_count(1.0, 2.0, 0.3),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+        assert!(step_count(1.0, 2.0, 0.0).is_err());
+
+        let problem = Ivp::new(|_, y: &[f64; 1]| *y, 0.0, 1.0, [1.0]);
+        let tol = Tolerance {
+            rtol: 1.0e-6,
+            atol: 1.0e-6,
+        };
+        let solution = dormand_prince(&problem, tol).expect("valid tolerances");
+        assert!(solution.eval(1.5).is_err());
+        assert!(solution.eval(f64::NAN).is_err());
+
+        let zero = Tolerance {
+            rtol: 0.0,
+            atol: 0.0,
+        };
+        assert!(dormand_prince(&problem, zero).is_err());
+    }
+}
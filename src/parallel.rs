@@ -0,0 +1,69 @@
+//! Data-parallel maps over independent cases.
+//!
+//! With the `parallel` feature the work is spread over rayon's thread pool;
+//! without it these are plain serial maps. Either way the results keep the
+//! order of the input, so output files do not depend on scheduling.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `items.iter().map(f).collect()`, in parallel with the `parallel` feature.
+pub fn map<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// [`map`] for a fallible `f`.
+///
+/// Every item may be evaluated even after one fails; which error is returned
+/// when several fail is unspecified in parallel builds.
+pub fn try_map<T, U, E, F>(items: &[T], f: F) -> Result<Vec<U>, E>
+where
+    T: Sync,
+    U: Send,
+    E: Send,
+    F: Fn(&T) -> Result<U, E> + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{map, try_map};
+
+    #[test]
+    fn results_keep_input_order() {
+        let items: Vec<u64> = (0..10_000).collect();
+        let squares = map(&items, |&i| i * i);
+
+        assert!(
+            squares
+                .iter()
+                .enumerate()
+                .all(|(i, &s)| s == (i * i) as u64)
+        );
+        assert_eq!(
+            try_map(&items[..3], |&i| Ok::<_, ()>(i + 1)),
+            Ok(vec![1, 2, 3])
+        );
+        assert!(try_map(&items, |&i| if i == 7 { Err(i) } else { Ok(i) }).is_err());
+    }
+}
@@ -0,0 +1,462 @@
+//! Native figure rendering with `plotters` (feature `native-plots`).
+//!
+//! [`util::plot`](crate::util::plot) falls back to [`render_chapter`] when uv,
+//! python or matplotlib are missing. The figures mirror `scripts/<chapter>/plot.py`:
+//! they read the same `.npy` files and write PNGs with the same names, so the
+//! reports build unchanged.
+//!
+//! Log axes are drawn by plotting `log10` of the data on a linear axis with
+//! decade tick marks; non-positive values are dropped from log axes the way
+//! matplotlib masks them.
+
+mod chapters;
+
+pub use chapters::render_chapter;
+
+use crate::error::{Mat627Error, Result};
+use plotters::coord::Shift;
+use plotters::coord::ranged1d::{KeyPointHint, NoDefaultFormatting, Ranged, ValueFormatter};
+use plotters::prelude::*;
+use std::ops::Range;
+use std::path::Path;
+
+/// Matplotlib's `tab10` cycle, used for series without an explicit colour.
+pub const PALETTE: [RGBColor; 10] = [
+    RGBColor(31, 119, 180),
+    RGBColor(255, 127, 14),
+    RGBColor(44, 160, 44),
+    RGBColor(214, 39, 40),
+    RGBColor(148, 103, 189),
+    RGBColor(140, 86, 75),
+    RGBColor(227, 119, 194),
+    RGBColor(127, 127, 127),
+    RGBColor(188, 189, 34),
+    RGBColor(23, 190, 207),
+];
+pub const GRAY: RGBColor = RGBColor(128, 128, 128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Line,
+    Dashed,
+    Dotted,
+    Markers,
+    LineMarkers,
+    DashedMarkers,
+    /// Filled area between the curve and `y = 0`.
+    Area,
+}
+
+/// One curve of a panel.
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub style: Style,
+    pub label: Option<String>,
+    pub color: Option<RGBColor>,
+}
+
+impl Series {
+    pub fn new(x: Vec<f64>, y: Vec<f64>, style: Style) -> Self {
+        Self {
+            x,
+            y,
+            style,
+            label: None,
+            color: None,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn color(mut self, color: RGBColor) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// One set of axes.
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub x_scale: Scale,
+    pub y_scale: Scale,
+    /// Draw the x axis decreasing left to right (step sizes `h` shrinking).
+    pub invert_x: bool,
+    pub series: Vec<Series>,
+}
+
+impl Panel {
+    pub fn new(title: impl Into<String>, x_label: &str, y_label: &str) -> Self {
+        Self {
+            title: title.into(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            x_scale: Scale::Linear,
+            y_scale: Scale::Linear,
+            invert_x: false,
+            series: Vec::new(),
+        }
+    }
+
+    pub fn log_x(mut self) -> Self {
+        self.x_scale = Scale::Log;
+        self
+    }
+
+    pub fn log_y(mut self) -> Self {
+        self.y_scale = Scale::Log;
+        self
+    }
+
+    pub fn loglog(self) -> Self {
+        self.log_x().log_y()
+    }
+
+    pub fn invert_x(mut self) -> Self {
+        self.invert_x = true;
+        self
+    }
+
+    pub fn with(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Points of `series` in axis coordinates (`log10` on log axes).
+    fn points(&self, series: &Series) -> Vec<(f64, f64)> {
+        let map = |v: f64, scale: Scale| match scale {
+            Scale::Linear => Some(v),
+            Scale::Log if v > 0.0 => Some(v.log10()),
+            Scale::Log => None,
+        };
+        series
+            .x
+            .iter()
+            .zip(&series.y)
+            .filter_map(|(&x, &y)| Some((map(x, self.x_scale)?, map(y, self.y_scale)?)))
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect()
+    }
+}
+
+/// A PNG or SVG file made of one or more panels laid out in a grid.
+#[derive(Debug, Clone)]
+pub struct Figure {
+    /// File name inside the plot directory; `.svg` selects the SVG backend.
+    pub name: String,
+    pub size: (u32, u32),
+    pub columns: usize,
+    pub panels: Vec<Panel>,
+}
+
+impl Figure {
+    /// An 8x5 inch figure at 150 dpi, matching the Python scripts.
+    pub fn single(name: &str, panel: Panel) -> Self {
+        Self::grid(name, (1200, 750), 1, vec![panel])
+    }
+
+    pub fn grid(name: &str, size: (u32, u32), columns: usize, panels: Vec<Panel>) -> Self {
+        Self {
+            name: name.to_string(),
+            size,
+            columns: columns.max(1),
+            panels,
+        }
+    }
+
+    /// Render into `dir/name`.
+    pub fn render(&self, dir: &str) -> Result<()> {
+        let path = Path::new(dir).join(&self.name);
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            self.draw(SVGBackend::new(&path, self.size).into_drawing_area())
+        } else {
+            self.draw(BitMapBackend::new(&path, self.size).into_drawing_area())
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(&self, root: DrawingArea<DB, Shift>) -> Result<()> {
+        root.fill(&WHITE).map_err(plot_error)?;
+        let rows = self.panels.len().div_ceil(self.columns);
+        let areas = root.split_evenly((rows, self.columns));
+        for (panel, area) in self.panels.iter().zip(&areas) {
+            draw_panel(panel, area)?;
+        }
+        root.present().map_err(plot_error)
+    }
+}
+
+fn plot_error(err: impl std::fmt::Display) -> Mat627Error {
+    Mat627Error::Plot(err.to_string())
+}
+
+fn draw_panel<DB: DrawingBackend>(panel: &Panel, area: &DrawingArea<DB, Shift>) -> Result<()> {
+    let points: Vec<Vec<(f64, f64)>> = panel.series.iter().map(|s| panel.points(s)).collect();
+    let all = points.iter().flatten();
+    let (x_lo, x_hi) = padded_range(all.clone().map(|p| p.0), panel.x_scale);
+    let (y_lo, y_hi) = padded_range(all.map(|p| p.1), panel.y_scale);
+    let x_axis = Axis::new(x_lo, x_hi, panel.x_scale, panel.invert_x);
+    let y_axis = Axis::new(y_lo, y_hi, panel.y_scale, false);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(&panel.title, ("sans-serif", 26))
+        .margin(15)
+        .x_label_area_size(55)
+        .y_label_area_size(90)
+        .build_cartesian_2d(x_axis, y_axis)
+        .map_err(plot_error)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&panel.x_label)
+        .y_desc(&panel.y_label)
+        .label_style(("sans-serif", 16))
+        .axis_desc_style(("sans-serif", 20))
+        .light_line_style(BLACK.mix(0.05))
+        .bold_line_style(BLACK.mix(0.15))
+        .draw()
+        .map_err(plot_error)?;
+
+    let baseline = match panel.y_scale {
+        Scale::Linear => 0.0,
+        Scale::Log => y_lo,
+    };
+    let mut labelled = false;
+    for (index, (series, points)) in panel.series.iter().zip(points).enumerate() {
+        let color = series.color.unwrap_or(PALETTE[index % PALETTE.len()]);
+        let line = color.stroke_width(2);
+        let annotation = match series.style {
+            Style::Line | Style::LineMarkers => chart
+                .draw_series(LineSeries::new(points.clone(), line))
+                .map_err(plot_error)?,
+            Style::Dashed | Style::DashedMarkers => chart
+                .draw_series(DashedLineSeries::new(points.clone(), 10, 6, line))
+                .map_err(plot_error)?,
+            Style::Dotted => chart
+                .draw_series(DashedLineSeries::new(points.clone(), 2, 5, line))
+                .map_err(plot_error)?,
+            Style::Area => chart
+                .draw_series(AreaSeries::new(
+                    points.clone(),
+                    baseline,
+                    color.mix(0.25).filled(),
+                ))
+                .map_err(plot_error)?,
+            Style::Markers => chart
+                .draw_series(points.iter().map(|&p| Circle::new(p, 4, color.filled())))
+                .map_err(plot_error)?,
+        };
+        if let Some(label) = &series.label {
+            labelled = true;
+            annotation
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+        }
+        if matches!(series.style, Style::LineMarkers | Style::DashedMarkers) {
+            chart
+                .draw_series(points.iter().map(|&p| Circle::new(p, 4, color.filled())))
+                .map_err(plot_error)?;
+        }
+    }
+
+    if labelled {
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK.mix(0.3))
+            .label_font(("sans-serif", 16))
+            .draw()
+            .map_err(plot_error)?;
+    }
+    Ok(())
+}
+
+/// A linear axis over (possibly `log10`-transformed) data with our own ticks.
+struct Axis {
+    start: f64,
+    end: f64,
+    scale: Scale,
+    major: Vec<f64>,
+    minor: Vec<f64>,
+}
+
+impl Axis {
+    fn new(lo: f64, hi: f64, scale: Scale, inverted: bool) -> Self {
+        let (start, end) = if inverted { (hi, lo) } else { (lo, hi) };
+        Self {
+            start,
+            end,
+            scale,
+            major: ticks(lo, hi, scale),
+            minor: minor_ticks(lo, hi, scale),
+        }
+    }
+}
+
+impl Ranged for Axis {
+    type FormatOption = NoDefaultFormatting;
+    type ValueType = f64;
+
+    fn map(&self, value: &f64, limit: (i32, i32)) -> i32 {
+        let t = (value - self.start) / (self.end - self.start);
+        limit.0 + (t * f64::from(limit.1 - limit.0)).round() as i32
+    }
+
+    fn key_points<Hint: KeyPointHint>(&self, hint: Hint) -> Vec<f64> {
+        if hint.weight().allow_light_points() {
+            self.minor.clone()
+        } else {
+            self.major.clone()
+        }
+    }
+
+    fn range(&self) -> Range<f64> {
+        self.start..self.end
+    }
+}
+
+impl ValueFormatter<f64> for Axis {
+    fn format_ext(&self, value: &f64) -> String {
+        tick_label(*value, self.scale)
+    }
+}
+
+/// Data range widened by 5% of its span (of the decades on log axes).
+fn padded_range(values: impl Iterator<Item = f64>, scale: Scale) -> (f64, f64) {
+    let (lo, hi) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if !lo.is_finite() {
+        return (0.0, 1.0);
+    }
+    let span = hi - lo;
+    let pad = if span > 0.0 {
+        0.05 * span
+    } else {
+        match scale {
+            Scale::Linear => 0.5 * lo.abs().max(1.0),
+            Scale::Log => 0.5,
+        }
+    };
+    (lo - pad, hi + pad)
+}
+
+/// Labelled tick positions: whole decades on log axes (`1, 2, 5 x 10^k` when
+/// the axis spans less than two decades), 1-2-5 steps otherwise.
+fn ticks(lo: f64, hi: f64, scale: Scale) -> Vec<f64> {
+    match scale {
+        Scale::Log if hi - lo >= 2.0 => {
+            let stride = ((hi - lo) / 8.0).ceil().max(1.0);
+            let first = (lo / stride).ceil() as i64;
+            let last = (hi / stride).floor() as i64;
+            (first..=last).map(|k| k as f64 * stride).collect()
+        }
+        Scale::Log => (lo.floor() as i64..=hi.ceil() as i64)
+            .flat_map(|k| [1.0, 2.0, 5.0].map(|m: f64| k as f64 + m.log10()))
+            .filter(|&v| v >= lo && v <= hi)
+            .collect(),
+        Scale::Linear => {
+            let raw = (hi - lo) / 6.0;
+            let magnitude = 10f64.powf(raw.log10().floor());
+            let step = [1.0, 2.0, 5.0, 10.0]
+                .into_iter()
+                .map(|m| m * magnitude)
+                .find(|&s| s >= raw)
+                .unwrap_or(10.0 * magnitude);
+            let first = (lo / step).ceil() as i64;
+            let last = (hi / step).floor() as i64;
+            (first..=last).map(|k| k as f64 * step).collect()
+        }
+    }
+}
+
+/// Unlabelled grid lines at `2..9 x 10^k` on log axes.
+fn minor_ticks(lo: f64, hi: f64, scale: Scale) -> Vec<f64> {
+    if scale == Scale::Linear || hi - lo > 12.0 {
+        return Vec::new();
+    }
+    (lo.floor() as i64..=hi.ceil() as i64)
+        .flat_map(|k| (1..10).map(move |m| k as f64 + f64::log10(m as f64)))
+        .filter(|&v| v >= lo && v <= hi)
+        .collect()
+}
+
+fn tick_label(v: f64, scale: Scale) -> String {
+    match scale {
+        Scale::Log => {
+            let value = 10f64.powf(v);
+            if (v - v.round()).abs() < 1.0e-9 {
+                format!("1e{}", v.round() as i64)
+            } else {
+                format!("{value:.0e}")
+            }
+        }
+        Scale::Linear if v == 0.0 => String::from("0"),
+        Scale::Linear if v.abs() >= 1.0e4 || v.abs() < 1.0e-3 => format!("{v:.1e}"),
+        Scale::Linear => {
+            let text = format!("{v:.4}");
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Figure, Panel, Scale, Series, Style, padded_range, ticks};
+    use std::fs;
+
+    #[test]
+    fn log_ticks_fall_on_decades() {
+        let (lo, hi) = padded_range([1.0e-8_f64, 0.5].iter().map(|v| v.log10()), Scale::Log);
+        let ticks = ticks(lo, hi, Scale::Log);
+        assert_eq!(ticks.first(), Some(&-8.0));
+        assert_eq!(ticks.last(), Some(&0.0));
+        assert!(ticks.iter().all(|t| t.fract() == 0.0));
+    }
+
+    #[test]
+    fn linear_ticks_use_round_steps() {
+        let ticks = ticks(0.0, 1.0, Scale::Linear);
+        let expected = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
+        assert_eq!(ticks.len(), expected.len());
+        assert!(
+            ticks
+                .iter()
+                .zip(expected)
+                .all(|(t, e)| (t - e).abs() < 1.0e-12)
+        );
+    }
+
+    #[test]
+    fn renders_svg_with_log_axes_and_masked_values() {
+        let dir = std::env::temp_dir().join(format!("mat627-plot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let h = vec![0.5, 0.25, 0.125];
+        let panel = Panel::new("error", "h", "E_h")
+            .loglog()
+            .invert_x()
+            .with(Series::new(h.clone(), vec![1.0e-2, 0.0, 6.0e-4], Style::LineMarkers).label("E"))
+            .with(Series::new(h, vec![1.0e-2, 2.5e-3, 6.25e-4], Style::Dashed));
+        Figure::single("error.svg", panel)
+            .render(&dir.display().to_string())
+            .unwrap();
+
+        let svg = fs::read_to_string(dir.join("error.svg")).unwrap();
+        assert!(svg.contains("1e-3"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,587 @@
+//! Rust versions of the figures in `scripts/<chapter>/plot.py`.
+
+use super::{Figure, GRAY, PALETTE, Panel, Series, Style};
+use crate::config::Paths;
+use crate::error::{Mat627Error, Result};
+use crate::util;
+use plotters::style::{BLACK, RGBColor};
+use std::fs;
+
+const RED: RGBColor = PALETTE[3];
+const BLUE: RGBColor = PALETTE[0];
+const ORANGE: RGBColor = PALETTE[1];
+
+/// Render every figure of `chapter` from `paths.data` into `paths.plots`.
+pub fn render_chapter(chapter: &str, paths: &Paths) -> Result<()> {
+    let data = paths.data.as_str();
+    let figures = match chapter {
+        "ch1" => ch1(data)?,
+        "ch2_1" => ch2_1(data)?,
+        "ch2_2" => ch2_2(data)?,
+        "ch2_3" => ch2_3(data)?,
+        "ch2_4" => ch2_4(data)?,
+        "ch2_5" => ch2_5(data)?,
+        "ch2_6" => ch2_6(data)?,
+        "ch2_7" => ch2_7(data)?,
+        _ => {
+            return Err(Mat627Error::InvalidInput(format!(
+                "no native figures for chapter '{chapter}'"
+            )));
+        }
+    };
+
+    fs::create_dir_all(&paths.plots)?;
+    for figure in &figures {
+        figure.render(&paths.plots)?;
+    }
+    println!("Saved plots to {}", paths.plots);
+    Ok(())
+}
+
+/// `err[last] * (h / h[last])^order`, the dashed reference lines of the error plots.
+fn reference(h: &[f64], err: &[f64], order: f64) -> Vec<f64> {
+    match (h.last(), err.last()) {
+        (Some(&h_last), Some(&e_last)) => h
+            .iter()
+            .map(|&hi| e_last * (hi / h_last).powf(order))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Stems `{prefix}{key}{suffix}.npy` in `dir`, returning the `key`s.
+fn keys(dir: &str, prefix: &str, suffix: &str) -> Result<Vec<String>> {
+    let mut keys = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if let Some(key) = name
+            .strip_suffix(".npy")
+            .and_then(|stem| stem.strip_prefix(prefix))
+            .and_then(|rest| rest.strip_suffix(suffix))
+        {
+            keys.push(key.to_string());
+        }
+    }
+    Ok(keys)
+}
+
+/// Integer keys (`x` values, system sizes) in increasing order.
+fn numeric_keys(dir: &str, prefix: &str, suffix: &str) -> Result<Vec<i64>> {
+    let mut keys: Vec<i64> = keys(dir, prefix, suffix)?
+        .iter()
+        .filter_map(|key| key.parse().ok())
+        .collect();
+    keys.sort_unstable();
+    Ok(keys)
+}
+
+fn ch1(data: &str) -> Result<Vec<Figure>> {
+    let alternating = format!("{data}/Alternating");
+    let reciprocal = format!("{data}/Reciprocal");
+    let mut panels = Vec::new();
+    for x in numeric_keys(&alternating, "", "")? {
+        let a = util::read_data(&alternating, &x.to_string())?;
+        let r = util::read_data(&reciprocal, &x.to_string())?;
+        let n: Vec<f64> = (0..a.len()).map(|i| i as f64).collect();
+        panels.push(
+            Panel::new(format!("X={x}"), "", "")
+                .with(Series::new(n.clone(), a, Style::Line).label("Alternating"))
+                .with(Series::new(n, r, Style::Line).label("Reciprocal")),
+        );
+    }
+    Ok(vec![Figure::grid("plot.png", (1500, 1500), 3, panels)])
+}
+
+fn ch2_1(data: &str) -> Result<Vec<Figure>> {
+    let mut panel = Panel::new("", "", "");
+    for (name, color) in [("standard", RED), ("horners", BLUE), ("exact", BLACK)] {
+        let y = util::read_data(data, name)?;
+        // The x grid is not saved; scripts/ch2_1/plot.py uses the same one.
+        let n = y.len().max(2);
+        let x = (0..y.len())
+            .map(|i| 1.92 + 0.16 * i as f64 / (n - 1) as f64)
+            .collect();
+        panel = panel.with(Series::new(x, y, Style::Line).label(name).color(color));
+    }
+    Ok(vec![Figure::single("plot.png", panel)])
+}
+
+fn ch2_2(data: &str) -> Result<Vec<Figure>> {
+    let pretty_case = |case: &str| match case {
+        "sqrt_x_1" | "sqrt_x_plus_1" => String::from("sqrt(x+1)"),
+        "exp_x" | "exp_x_" => String::from("e^x"),
+        other => other.replace('_', " "),
+    };
+    let pretty_method = |method: &str| match method {
+        "center" => String::from("centered"),
+        "richardson" => String::from("centered + Richardson"),
+        other => other.replace('_', " "),
+    };
+
+    let mut cases = keys(data, "", "__exact")?;
+    cases.sort();
+    let mut figures = Vec::new();
+    for case in cases {
+        let exact = util::read_data(data, &format!("{case}__exact"))?[0];
+        let mut methods = keys(data, &format!("{case}__"), "")?;
+        methods.retain(|method| method != "exact");
+        methods.sort();
+
+        let mut approx_panel = Panel::new(
+            format!(
+                "Finite-difference approximations for {}",
+                pretty_case(&case)
+            ),
+            "h",
+            "Derivative approximation",
+        )
+        .log_x()
+        .invert_x();
+        let mut error_panel = Panel::new(
+            format!("Finite-difference error for {}", pretty_case(&case)),
+            "h",
+            "|error|",
+        )
+        .loglog()
+        .invert_x();
+
+        let mut h_all = Vec::new();
+        for method in &methods {
+            let base = format!("{case}__{method}");
+            let h = util::read_field(data, &base, "h")?;
+            let approx = util::read_field(data, &base, "approx")?;
+            let abs_err = util::read_field(data, &base, "abs_err")?;
+            h_all.extend_from_slice(&h);
+            approx_panel = approx_panel.with(
+                Series::new(h.clone(), approx, Style::LineMarkers).label(pretty_method(method)),
+            );
+            error_panel = error_panel
+                .with(Series::new(h, abs_err, Style::LineMarkers).label(pretty_method(method)));
+        }
+
+        let h_min = h_all.iter().copied().fold(f64::INFINITY, f64::min);
+        let h_max = h_all.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        approx_panel = approx_panel.with(
+            Series::new(vec![h_min, h_max], vec![exact, exact], Style::Dashed)
+                .label(format!("exact = {exact:.8e}"))
+                .color(BLACK),
+        );
+        figures.push(Figure::single(&format!("{case}_approx.png"), approx_panel));
+        figures.push(Figure::single(&format!("{case}_error.png"), error_panel));
+    }
+    Ok(figures)
+}
+
+fn ch2_3(data: &str) -> Result<Vec<Figure>> {
+    let t_euler = util::read_data(data, "plot__euler_t")?;
+    let h = t_euler.get(1).map_or(0.0, |t1| t1 - t_euler[0]);
+    let solution = Panel::new("Euler approximation versus the exact solution", "t", "y(t)")
+        .with(
+            Series::new(
+                util::read_data(data, "plot__exact_t")?,
+                util::read_data(data, "plot__exact_y")?,
+                Style::Line,
+            )
+            .label("exact solution")
+            .color(BLACK),
+        )
+        .with(
+            Series::new(
+                t_euler,
+                util::read_data(data, "plot__euler_y")?,
+                Style::DashedMarkers,
+            )
+            .label(format!("Euler, h={h}"))
+            .color(RED),
+        );
+
+    let t = util::read_field(data, "history", "t")?;
+    let step = util::read_field(data, "history", "h")?;
+    let accepted = util::read_field(data, "history", "accepted")?;
+    let split = |keep: bool| -> (Vec<f64>, Vec<f64>) {
+        t.iter()
+            .zip(&step)
+            .zip(&accepted)
+            .filter(|(_, flag)| (**flag > 0.5) == keep)
+            .map(|((t, h), _)| (*t, *h))
+            .unzip()
+    };
+    let (t_ok, h_ok) = split(true);
+    let (t_rejected, h_rejected) = split(false);
+    let steps = Panel::new("Dormand-Prince step sizes", "t", "h")
+        .log_y()
+        .with(Series::new(t_ok, h_ok, Style::LineMarkers).label("accepted"))
+        .with(
+            Series::new(t_rejected, h_rejected, Style::Markers)
+                .label("rejected")
+                .color(RED),
+        );
+
+    let dense_err = util::read_data(data, "dense__err")?;
+    let dense = Panel::new("Dense output error", "t", "|y(t) - y~(t)|")
+        .log_y()
+        .with(
+            Series::new(
+                util::read_data(data, "dense__t")?,
+                dense_err.iter().map(|e| e.abs()).collect(),
+                Style::Line,
+            )
+            .color(BLACK),
+        );
+
+    Ok(vec![
+        Figure::single("plot.png", solution),
+        Figure::grid("adaptive_steps.png", (1200, 1050), 1, vec![steps, dense]),
+    ])
+}
+
+fn summary(data: &str, name: &str) -> Result<(Vec<f64>, Vec<f64>)> {
+    Ok((
+        util::read_field(data, name, "h")?,
+        util::read_field(data, name, "err")?,
+    ))
+}
+
+fn ch2_4(data: &str) -> Result<Vec<Figure>> {
+    let x = util::read_data(data, "plot__x")?;
+    let approximation = Panel::new(
+        "Piecewise linear interpolation of x^(1/3) on [0,2]",
+        "x",
+        "y",
+    )
+    .with(
+        Series::new(
+            x.clone(),
+            util::read_data(data, "plot__exact")?,
+            Style::Line,
+        )
+        .label("f(x) = x^(1/3)")
+        .color(BLACK),
+    )
+    .with(
+        Series::new(x, util::read_data(data, "plot__approx")?, Style::Dashed)
+            .label("q_4(x)")
+            .color(RED),
+    )
+    .with(
+        Series::new(
+            util::read_data(data, "plot__nodes_x")?,
+            util::read_data(data, "plot__nodes_y")?,
+            Style::Markers,
+        )
+        .label("mesh points")
+        .color(BLUE),
+    );
+
+    let (smooth_h, smooth_err) = summary(data, "smooth")?;
+    let (singular_h, singular_err) = summary(data, "singular")?;
+    let error = Panel::new("Maximum interpolation error on the fine mesh", "h", "E_h")
+        .loglog()
+        .invert_x()
+        .with(Series::new(smooth_h.clone(), smooth_err.clone(), Style::LineMarkers).label("[1,2]"))
+        .with(
+            Series::new(singular_h.clone(), singular_err.clone(), Style::LineMarkers)
+                .label("[0,1]"),
+        )
+        .with(
+            Series::new(
+                smooth_h.clone(),
+                reference(&smooth_h, &smooth_err, 2.0),
+                Style::Dashed,
+            )
+            .label("reference slope 2")
+            .color(GRAY),
+        )
+        .with(
+            Series::new(
+                singular_h.clone(),
+                reference(&singular_h, &singular_err, 1.0 / 3.0),
+                Style::Dotted,
+            )
+            .label("reference slope 1/3")
+            .color(BLACK),
+        );
+
+    Ok(vec![
+        Figure::single("approximation.png", approximation),
+        Figure::single("error.png", error),
+    ])
+}
+
+fn ch2_5(data: &str) -> Result<Vec<Figure>> {
+    let cases = [
+        ("part1", "Part 1", "x^2 e^-x"),
+        ("part2", "Part 2", "x^2 e^-x"),
+        ("part3", "Part 3", "sqrt(x)"),
+    ];
+
+    let mut trapezoids = Vec::new();
+    let mut titles = Vec::new();
+    for (name, _, f) in cases {
+        let x = util::read_data(data, &format!("plot__{name}__x"))?;
+        let interval = format!("[{}, {}]", x[0], x[x.len() - 1]);
+        titles.push(interval.clone());
+        let interp = util::read_data(data, &format!("plot__{name}__interp"))?;
+        trapezoids.push(
+            Panel::new(format!("f(x) = {f} on {interval} with N=4"), "x", "y")
+                .with(
+                    Series::new(x.clone(), interp.clone(), Style::Area)
+                        .label("trapezoid-rule area")
+                        .color(ORANGE),
+                )
+                .with(
+                    Series::new(
+                        x.clone(),
+                        util::read_data(data, &format!("plot__{name}__exact"))?,
+                        Style::Line,
+                    )
+                    .label("exact integrand")
+                    .color(BLACK),
+                )
+                .with(
+                    Series::new(x, interp, Style::Dashed)
+                        .label("piecewise linear interpolant")
+                        .color(RED),
+                )
+                .with(
+                    Series::new(
+                        util::read_data(data, &format!("plot__{name}__nodes_x"))?,
+                        util::read_data(data, &format!("plot__{name}__nodes_y"))?,
+                        Style::Markers,
+                    )
+                    .label("mesh points")
+                    .color(BLUE),
+                ),
+        );
+    }
+
+    let mut error = Panel::new(
+        "Composite trapezoid-rule error decay",
+        "h",
+        "E_h = |T_N(f) - I(f)|",
+    )
+    .loglog()
+    .invert_x();
+    let mut romberg = Panel::new("Romberg diagonal error", "N", "|R_kk - I(f)|").loglog();
+    let mut gauss = Panel::new(
+        "Composite trapezoid versus two-point Gauss-Legendre",
+        "h",
+        "absolute error",
+    )
+    .loglog()
+    .invert_x();
+    let mut references = Vec::new();
+    for (((name, part, _), interval), (order, slope, style, color)) in
+        cases.iter().zip(&titles).zip([
+            (2.0, "2", Style::Dashed, GRAY),
+            (4.0, "4", Style::Dashed, BLACK),
+            (1.5, "3/2", Style::Dotted, BLACK),
+        ])
+    {
+        let label = format!("{part}: {interval}");
+        let (h, err) = summary(data, name)?;
+        references.push(
+            Series::new(h.clone(), reference(&h, &err, order), style)
+                .label(format!("reference slope {slope}"))
+                .color(color),
+        );
+        error = error.with(Series::new(h.clone(), err.clone(), Style::LineMarkers).label(&label));
+
+        romberg = romberg.with(
+            Series::new(
+                util::read_field(data, &format!("romberg__{name}"), "n")?,
+                util::read_field(data, &format!("romberg__{name}"), "err")?,
+                Style::LineMarkers,
+            )
+            .label(&label),
+        );
+
+        let color = PALETTE[gauss.series.len() / 2];
+        let (gauss_h, gauss_err) = summary(data, &format!("gauss__{name}"))?;
+        gauss = gauss
+            .with(
+                Series::new(h, err, Style::DashedMarkers)
+                    .label(format!("trapezoid, {label}"))
+                    .color(color),
+            )
+            .with(
+                Series::new(gauss_h, gauss_err, Style::LineMarkers)
+                    .label(format!("Gauss-Legendre, {label}"))
+                    .color(color),
+            );
+    }
+    for series in references {
+        error = error.with(series);
+    }
+
+    let mut adaptive = Vec::new();
+    for (method, title) in [("simpson", "adaptive Simpson"), ("kronrod", "G7K15")] {
+        let mut panel = Panel::new(
+            format!("{title}: final partition"),
+            "subinterval midpoint",
+            "subinterval width",
+        )
+        .loglog();
+        for ((name, part, _), interval) in cases.iter().zip(&titles) {
+            let intervals = format!("intervals__{method}__{name}");
+            let a = util::read_field(data, &intervals, "a")?;
+            let b = util::read_field(data, &intervals, "b")?;
+            let (mid, width) = a
+                .iter()
+                .zip(&b)
+                .map(|(a, b)| (0.5 * (a + b), b - a))
+                .unzip();
+            panel = panel
+                .with(Series::new(mid, width, Style::Markers).label(format!("{part}: {interval}")));
+        }
+        adaptive.push(panel);
+    }
+
+    Ok(vec![
+        Figure::grid("trapezoids.png", (1200, 1500), 1, trapezoids),
+        Figure::single("error.png", error),
+        Figure::single("romberg.png", romberg),
+        Figure::single("gauss.png", gauss),
+        Figure::grid("adaptive.png", (1500, 600), 2, adaptive),
+    ])
+}
+
+fn ch2_6(data: &str) -> Result<Vec<Figure>> {
+    let sizes = numeric_keys(data, "residual__n", "__i")?;
+    let tiny =
+        |v: Vec<f64>| -> Vec<f64> { v.into_iter().map(|r| r.max(f64::MIN_POSITIVE)).collect() };
+
+    let mut residuals = Panel::new("Residual magnitude by component", "i", "|r_i|").log_y();
+    let mut solutions = Panel::new("Computed solution components", "i", "x_i");
+    let mut refinement = Panel::new(
+        "Iterative refinement from single-precision LU factors",
+        "refinement iteration",
+        "||r|| / (||T|| ||x|| + ||b||)",
+    )
+    .log_y();
+    let mut last_iteration: f64 = 0.0;
+    for n in sizes {
+        let label = format!("n={n}");
+        residuals = residuals.with(
+            Series::new(
+                util::read_data(data, &format!("residual__n{n}__i"))?,
+                tiny(util::read_data(data, &format!("residual__n{n}__abs"))?),
+                Style::LineMarkers,
+            )
+            .label(&label),
+        );
+        solutions = solutions.with(
+            Series::new(
+                util::read_data(data, &format!("solution__n{n}__i"))?,
+                util::read_data(data, &format!("solution__n{n}__x"))?,
+                Style::Line,
+            )
+            .label(&label),
+        );
+        let history = format!("refinement__n{n}");
+        let iteration = util::read_field(data, &history, "iteration")?;
+        last_iteration = iteration.iter().copied().fold(last_iteration, f64::max);
+        refinement = refinement.with(
+            Series::new(
+                iteration,
+                tiny(util::read_field(data, &history, "backward")?),
+                Style::LineMarkers,
+            )
+            .label(&label),
+        );
+    }
+    refinement = refinement.with(
+        Series::new(
+            vec![0.0, last_iteration],
+            vec![f64::EPSILON; 2],
+            Style::Dashed,
+        )
+        .label("eps_64")
+        .color(GRAY),
+    );
+
+    let max_residual = Panel::new(
+        "Maximum residual component versus system size",
+        "n",
+        "e_n = ||r||_inf",
+    )
+    .loglog()
+    .with(
+        Series::new(
+            util::read_field(data, "summary", "n")?,
+            util::read_field(data, "summary", "en")?,
+            Style::LineMarkers,
+        )
+        .color(RED),
+    );
+
+    Ok(vec![
+        Figure::single("residual_profiles.png", residuals),
+        Figure::single("max_residual.png", max_residual),
+        Figure::single("solution_profiles.png", solutions),
+        Figure::single("refinement.png", refinement),
+    ])
+}
+
+fn ch2_7(data: &str) -> Result<Vec<Figure>> {
+    let nodes_x = util::read_data(data, "plot__nodes_x")?;
+    let approximation = Panel::new("Part 1 approximation versus exact solution", "x", "u(x)")
+        .with(
+            Series::new(
+                util::read_data(data, "plot__x")?,
+                util::read_data(data, "plot__exact")?,
+                Style::Line,
+            )
+            .label("exact solution")
+            .color(BLACK),
+        )
+        .with(
+            Series::new(
+                nodes_x.clone(),
+                util::read_data(data, "plot__nodes_y")?,
+                Style::DashedMarkers,
+            )
+            .label(format!(
+                "finite-difference approximation (N={})",
+                nodes_x.len().saturating_sub(1)
+            ))
+            .color(RED),
+        );
+
+    let tiny =
+        |v: Vec<f64>| -> Vec<f64> { v.into_iter().map(|e| e.max(f64::MIN_POSITIVE)).collect() };
+    let (h2, err2) = summary(data, "part2")?;
+    let (h3, err3) = summary(data, "part3")?;
+    let (h4, err4) = summary(data, "part4")?;
+    let error = Panel::new(
+        "Finite-difference error decay",
+        "h",
+        "E_h = max_k |u(x_k) - U_k|",
+    )
+    .loglog()
+    .invert_x()
+    .with(
+        Series::new(h2.clone(), tiny(err2.clone()), Style::LineMarkers)
+            .label("Part 2: -u'' + u = f"),
+    )
+    .with(Series::new(h3, tiny(err3), Style::LineMarkers).label("Part 3: quadratic exact solution"))
+    .with(
+        Series::new(h4.clone(), tiny(err4.clone()), Style::LineMarkers)
+            .label("Part 4: backward difference for u'"),
+    )
+    .with(
+        Series::new(h2.clone(), reference(&h2, &err2, 2.0), Style::Dashed)
+            .label("reference slope 2")
+            .color(GRAY),
+    )
+    .with(
+        Series::new(h4.clone(), reference(&h4, &err4, 1.0), Style::Dotted)
+            .label("reference slope 1")
+            .color(BLACK),
+    );
+
+    Ok(vec![
+        Figure::single("approximation.png", approximation),
+        Figure::single("error.png", error),
+    ])
+}
@@ -0,0 +1,45 @@
+use crate::summation::Summation;
+
+/// Evaluate `p(x) = sum_k coeff[k] x^k` by accumulating successive powers of `x`.
+///
+/// # Arguments
+/// * `x` - The evaluation point
+/// * `coeff` - Coefficients in increasing order of degree
+pub fn standard(x: f64, coeff: &[f64]) -> f64 {
+    standard_with(x, coeff, Summation::Naive)
+}
+
+/// [`standard`] with the terms `coeff[k] x^k` added up by `summation`.
+pub fn standard_with(x: f64, coeff: &[f64], summation: Summation) -> f64 {
+    let mut term: f64 = 1.0;
+    summation.sum(coeff.iter().map(|&a| {
+        let t = a * term;
+        term *= x;
+        t
+    }))
+}
+
+/// Evaluate `p(x) = sum_k coeff[k] x^k` with Horner's nested multiplication.
+///
+/// # Arguments
+/// * `x` - The evaluation point
+/// * `coeff` - Coefficients in increasing order of degree
+pub fn horners(x: f64, coeff: &[f64]) -> f64 {
+    coeff.iter().rev().fold(0.0, |acc, &a| acc.mul_add(x, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{horners, standard};
+
+    #[test]
+    fn both_methods_agree_on_small_polynomial() {
+        let coeff = [1.0, -3.0, 2.0];
+
+        for x in [-2.0, 0.0, 0.5, 3.0] {
+            let exact = 2.0 * x * x - 3.0 * x + 1.0;
+            assert!((standard(x, &coeff) - exact).abs() < 1.0e-12);
+            assert!((horners(x, &coeff) - exact).abs() < 1.0e-12);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+pub mod adaptive;
+pub mod gauss;
+
+use crate::error::{Mat627Error, Result};
+use crate::extrapolation::{self, Tableau};
+use crate::summation::Summation;
+
+/// Composite trapezoid rule for `f` on `[a, b]` with `n` equal subintervals.
+///
+/// Fails with `InvalidInput` when `n` is zero.
+pub fn composite_trapezoid<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, n: usize) -> Result<f64> {
+    composite_trapezoid_with(f, a, b, n, Summation::Naive)
+}
+
+/// [`composite_trapezoid`] with the interior values added up by `summation`.
+pub fn composite_trapezoid_with<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    n: usize,
+    summation: Summation,
+) -> Result<f64> {
+    if n == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "trapezoid rule needs at least one subinterval",
+        )));
+    }
+
+    let h = (b - a) / n as f64;
+    let interior_sum = summation.sum((1..n).map(|i| f(a + i as f64 * h)));
+
+    Ok(0.5 * h * (f(a) + 2.0 * interior_sum + f(b)))
+}
+
+/// Output of [`romberg`].
+#[derive(Debug, Clone)]
+pub struct Romberg {
+    /// Row `i` starts from the trapezoid rule on `2^i` subintervals.
+    pub tableau: Tableau,
+    pub evaluations: usize,
+    pub converged: bool,
+}
+
+impl Romberg {
+    /// The diagonal entry `R[i][i]` of the last row.
+    pub fn estimate(&self) -> f64 {
+        self.tableau.best()
+    }
+
+    /// Diagonal `R[i][i]` for every row.
+    pub fn diagonal(&self) -> Vec<f64> {
+        self.tableau
+            .values
+            .iter()
+            .map(|row| *row.last().expect("empty tableau row"))
+            .collect()
+    }
+}
+
+/// Romberg integration of `f` on `[a, b]`.
+///
+/// Halves `h` each level, reusing every previous function value so level `i`
+/// costs only the `2^(i-1)` new midpoints. Stops once the error estimate of
+/// the diagonal entry drops below `tol`, or after `max_levels` refinements.
+pub fn romberg<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, tol: f64, max_levels: usize) -> Romberg {
+    let mut h = b - a;
+    let mut trapezoid = 0.5 * h * (f(a) + f(b));
+    let mut evaluations = 2;
+    let mut tableau = Tableau::new(2.0, &extrapolation::arithmetic_orders(2.0, max_levels))
+        .expect("halving h is a valid refinement ratio");
+    tableau.push(trapezoid, h);
+
+    for level in 1..=max_levels {
+        let midpoints = 1_usize << (level - 1);
+        let midpoint_sum: f64 = (0..midpoints)
+            .map(|i| f(a + (2 * i + 1) as f64 * 0.5 * h))
+            .sum();
+        evaluations += midpoints;
+
+        trapezoid = 0.5 * trapezoid + 0.5 * h * midpoint_sum;
+        h *= 0.5;
+        tableau.push(trapezoid, h);
+
+        if tableau.best_error().is_some_and(|err| err <= tol) {
+            return Romberg {
+                tableau,
+                evaluations,
+                converged: true,
+            };
+        }
+    }
+
+    Romberg {
+        tableau,
+        evaluations,
+        converged: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{composite_trapezoid, romberg};
+    use crate::error::Mat627Error;
+
+    #[test]
+    fn trapezoid_rule_is_exact_for_linear_functions() {
+        let approx = composite_trapezoid(|x| 3.0 * x - 1.0, 0.0, 2.0, 8).expect("n = 8");
+        let exact = 4.0;
+
+        assert!((approx - exact).abs() < 1.0e-12);
+        assert!(matches!(
+            composite_trapezoid(f64::exp, 0.0, 1.0, 0),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn romberg_reuses_evaluations_and_converges() {
+        let result = romberg(f64::exp, 0.0, 1.0, 1.0e-12, 10);
+        let levels = result.tableau.values.len() - 1;
+
+        assert!(result.converged);
+        assert_eq!(result.evaluations, (1 << levels) + 1);
+        assert!((result.estimate() - (1.0_f64.exp() - 1.0)).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn romberg_first_column_matches_composite_trapezoid() {
+        let f = |x: f64| x.powi(2) * (-x).exp();
+        let result = romberg(f, 1.0, 3.0, 0.0, 4);
+
+        for (i, row) in result.tableau.values.iter().enumerate() {
+            let trapezoid = composite_trapezoid(f, 1.0, 3.0, 1 << i).expect("n >= 1");
+            assert!((row[0] - trapezoid).abs() < 1.0e-14);
+        }
+        assert!(!result.converged);
+    }
+}
@@ -0,0 +1,325 @@
+//! Globally adaptive quadrature: repeatedly bisect the subinterval with the
+//! largest error estimate until the total estimate meets the tolerance.
+
+use crate::error::{Mat627Error, Result};
+use crate::util::{self, Field};
+use serde::Deserialize;
+use std::cell::Cell;
+
+/// Stopping criteria for [`adaptive_simpson`] and [`gauss_kronrod`].
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Options {
+    pub atol: f64,
+    pub rtol: f64,
+    /// Subintervals produced by `max_depth` bisections of `[a, b]` are never split further.
+    pub max_depth: usize,
+}
+
+/// One subinterval of the final partition.
+#[derive(Debug, Clone, Copy)]
+pub struct Subinterval {
+    pub a: f64,
+    pub b: f64,
+    pub estimate: f64,
+    pub error: f64,
+    pub depth: usize,
+    /// `f` at `a`, the quarter points, the midpoint and `b`, in order, when the
+    /// rule samples them; bisection hands each half its three known values.
+    pub samples: Option<[f64; 5]>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveResult {
+    pub estimate: f64,
+    /// Sum of the local error estimates.
+    pub error: f64,
+    pub evaluations: usize,
+    /// Final partition of `[a, b]`, ordered left to right.
+    pub intervals: Vec<Subinterval>,
+    /// `false` when `max_depth` stopped refinement before the tolerance was met.
+    pub converged: bool,
+}
+
+impl AdaptiveResult {
+    /// Write the final partition as the record array `{name}` with fields
+    /// `a`, `b`, `estimate`, `error` and `depth`.
+    pub fn write_intervals(&self, dir: &str, name: &str) -> Result<()> {
+        let column =
+            |g: fn(&Subinterval) -> f64| -> Vec<f64> { self.intervals.iter().map(g).collect() };
+        let fields = [
+            Field::Float("a", column(|s| s.a)),
+            Field::Float("b", column(|s| s.b)),
+            Field::Float("estimate", column(|s| s.estimate)),
+            Field::Float("error", column(|s| s.error)),
+            Field::Int(
+                "depth",
+                self.intervals.iter().map(|s| s.depth as i64).collect(),
+            ),
+        ];
+
+        util::write_records(&fields, dir.to_string(), name.to_string())
+    }
+}
+
+/// Simpson's rule on `[a, b]` and on its two halves, extrapolated.
+///
+/// `known` holds `f(a), f(m), f(b)` when a parent panel already sampled them,
+/// so only the two quarter points are new. Returns
+/// `(S2 + (S2 - S1) / 15, |S2 - S1| / 15)` and the five samples.
+fn simpson_panel<F: Fn(f64) -> f64>(
+    f: &F,
+    a: f64,
+    b: f64,
+    known: Option<[f64; 3]>,
+) -> (f64, f64, [f64; 5]) {
+    let h = b - a;
+    let m = 0.5 * (a + b);
+    let [fa, fm, fb] = known.unwrap_or_else(|| [f(a), f(m), f(b)]);
+    let fl = f(0.5 * (a + m));
+    let fr = f(0.5 * (m + b));
+
+    let whole = h / 6.0 * (fa + 4.0 * fm + fb);
+    let halves = h / 12.0 * (fa + 4.0 * fl + 2.0 * fm + 4.0 * fr + fb);
+
+    (
+        halves + (halves - whole) / 15.0,
+        (halves - whole).abs() / 15.0,
+        [fa, fl, fm, fr, fb],
+    )
+}
+
+/// Non-negative half of the 15 Kronrod nodes on `[-1, 1]`, which are symmetric
+/// about 0; the odd indices are the 7-point Gauss nodes.
+const XGK: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const WGK: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_18,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_83,
+];
+const WG: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// 15-point Kronrod estimate on `[a, b]` with `|K15 - G7|` as its error.
+fn kronrod_panel<F: Fn(f64) -> f64>(f: &F, a: f64, b: f64) -> (f64, f64) {
+    let half = 0.5 * (b - a);
+    let mid = 0.5 * (a + b);
+    let fc = f(mid);
+    let mut kronrod = WGK[7] * fc;
+    let mut gauss = WG[3] * fc;
+
+    for j in 0..7 {
+        let dx = half * XGK[j];
+        let pair = f(mid - dx) + f(mid + dx);
+        kronrod += WGK[j] * pair;
+        if j % 2 == 1 {
+            gauss += WG[j / 2] * pair;
+        }
+    }
+
+    (half * kronrod, (half * (kronrod - gauss)).abs())
+}
+
+fn refine<F, R>(f: F, a: f64, b: f64, options: Options, rule: R) -> Result<AdaptiveResult>
+where
+    F: Fn(f64) -> f64,
+    R: Fn(&dyn Fn(f64) -> f64, f64, f64, Option<[f64; 3]>) -> (f64, f64, Option<[f64; 5]>),
+{
+    if !(options.atol >= 0.0 && options.rtol >= 0.0) {
+        return Err(Mat627Error::InvalidInput(format!(
+            "tolerances must be non-negative, got atol = {}, rtol = {}",
+            options.atol, options.rtol
+        )));
+    }
+
+    let evaluations = Cell::new(0);
+    let counted = |x: f64| {
+        evaluations.set(evaluations.get() + 1);
+        f(x)
+    };
+    let panel = |a: f64, b: f64, depth: usize, known: Option<[f64; 3]>| {
+        let (estimate, error, samples) = rule(&counted, a, b, known);
+        Subinterval {
+            a,
+            b,
+            estimate,
+            error,
+            depth,
+            samples,
+        }
+    };
+
+    let mut intervals = vec![panel(a, b, 0, None)];
+    let converged = loop {
+        let estimate: f64 = intervals.iter().map(|s| s.estimate).sum();
+        let error: f64 = intervals.iter().map(|s| s.error).sum();
+        if error <= options.atol.max(options.rtol * estimate.abs()) {
+            break true;
+        }
+
+        let worst = intervals
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.depth < options.max_depth)
+            .max_by(|(_, s), (_, t)| s.error.total_cmp(&t.error))
+            .map(|(i, _)| i);
+        let Some(i) = worst else {
+            break false;
+        };
+
+        let parent = intervals.swap_remove(i);
+        let m = 0.5 * (parent.a + parent.b);
+        let (left, right) = match parent.samples {
+            Some([fa, fl, fm, fr, fb]) => (Some([fa, fl, fm]), Some([fm, fr, fb])),
+            None => (None, None),
+        };
+        intervals.push(panel(parent.a, m, parent.depth + 1, left));
+        intervals.push(panel(m, parent.b, parent.depth + 1, right));
+    };
+
+    intervals.sort_by(|s, t| s.a.total_cmp(&t.a));
+
+    Ok(AdaptiveResult {
+        estimate: intervals.iter().map(|s| s.estimate).sum(),
+        error: intervals.iter().map(|s| s.error).sum(),
+        evaluations: evaluations.get(),
+        intervals,
+        converged,
+    })
+}
+
+/// Globally adaptive Simpson's rule for the integral of `f` on `[a, b]`.
+///
+/// Each bisection reuses the endpoint and midpoint values of the parent, so a
+/// child panel costs two new evaluations of `f`. Fails with `InvalidInput` when a tolerance is negative.
+pub fn adaptive_simpson<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    options: Options,
+) -> Result<AdaptiveResult> {
+    refine(f, a, b, options, |g, a, b, known| {
+        let (estimate, error, samples) = simpson_panel(&g, a, b, known);
+        (estimate, error, Some(samples))
+    })
+}
+
+/// Globally adaptive Gauss-Kronrod G7/K15 for the integral of `f` on `[a, b]`.
+///
+/// Fails with `InvalidInput` when a tolerance is negative.
+pub fn gauss_kronrod<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    options: Options,
+) -> Result<AdaptiveResult> {
+    refine(f, a, b, options, |g, a, b, _| {
+        let (estimate, error) = kronrod_panel(&g, a, b);
+        (estimate, error, None)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Options, adaptive_simpson, gauss_kronrod};
+    use crate::error::Mat627Error;
+    use std::cell::Cell;
+
+    const OPTIONS: Options = Options {
+        atol: 1.0e-10,
+        rtol: 1.0e-10,
+        max_depth: 40,
+    };
+
+    #[test]
+    fn kronrod_is_exact_for_smooth_polynomials_in_one_panel() {
+        let result = gauss_kronrod(|x| x.powi(10), 0.0, 1.0, OPTIONS).unwrap();
+
+        assert!(result.converged);
+        assert_eq!(result.intervals.len(), 1);
+        assert_eq!(result.evaluations, 15);
+        assert!((result.estimate - 1.0 / 11.0).abs() < 1.0e-15);
+    }
+
+    #[test]
+    fn both_methods_resolve_sqrt_singularity() {
+        for result in [
+            adaptive_simpson(f64::sqrt, 0.0, 1.0, OPTIONS).unwrap(),
+            gauss_kronrod(f64::sqrt, 0.0, 1.0, OPTIONS).unwrap(),
+        ] {
+            let actual = (result.estimate - 2.0 / 3.0).abs();
+
+            assert!(result.converged);
+            assert!(actual <= 1.0e-9, "actual error {actual}");
+            assert!(result.intervals[0].b < 1.0e-3, "no clustering near x = 0");
+        }
+    }
+
+    #[test]
+    fn simpson_children_reuse_parent_samples() {
+        let calls = Cell::new(0);
+        let f = |x: f64| {
+            calls.set(calls.get() + 1);
+            x.sqrt()
+        };
+        let result = adaptive_simpson(f, 0.0, 1.0, OPTIONS).unwrap();
+        let bisections = result.intervals.len() - 1;
+
+        assert_eq!(result.evaluations, calls.get());
+        assert_eq!(result.evaluations, 5 + 4 * bisections);
+    }
+
+    #[test]
+    fn partition_covers_interval_in_order() {
+        let result = adaptive_simpson(|x| (10.0 * x).sin(), -1.0, 2.0, OPTIONS).unwrap();
+
+        assert_eq!(result.intervals[0].a, -1.0);
+        assert_eq!(result.intervals.last().unwrap().b, 2.0);
+        for pair in result.intervals.windows(2) {
+            assert_eq!(pair[0].b, pair[1].a);
+        }
+    }
+
+    #[test]
+    fn max_depth_stops_refinement() {
+        let options = Options {
+            max_depth: 3,
+            ..OPTIONS
+        };
+        let result = adaptive_simpson(f64::sqrt, 0.0, 1.0, options).unwrap();
+
+        assert!(!result.converged);
+        assert!(result.intervals.iter().all(|s| s.depth <= 3));
+    }
+
+    #[test]
+    fn negative_tolerance_is_invalid_input() {
+        let options = Options {
+            atol: -1.0,
+            ..OPTIONS
+        };
+
+        assert!(matches!(
+            adaptive_simpson(f64::sqrt, 0.0, 1.0, options),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,408 @@
+//! Gaussian quadrature rules with nodes and weights computed at runtime by
+//! the Golub-Welsch algorithm.
+
+use crate::error::{Mat627Error, Result};
+
+/// An `n`-point quadrature rule `sum_i w_i f(x_i)` on its reference interval.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub nodes: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+
+impl Rule {
+    /// Apply the rule to `f` on its reference interval.
+    pub fn integrate<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        self.nodes
+            .iter()
+            .zip(&self.weights)
+            .map(|(&x, &w)| w * f(x))
+            .sum()
+    }
+
+    /// Apply a rule on `[-1, 1]` to `f` on `[a, b]` by the affine change of variables.
+    pub fn integrate_on<F: Fn(f64) -> f64>(&self, f: F, a: f64, b: f64) -> f64 {
+        let half = 0.5 * (b - a);
+        let mid = 0.5 * (a + b);
+
+        half * self.integrate(|t| f(mid + half * t))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Gauss rule from the three-term recurrence of the monic orthogonal polynomials
+/// `p_{k+1}(x) = (x - alpha_k) p_k(x) - beta_k p_{k-1}(x)`.
+///
+/// The nodes are the eigenvalues of the symmetric Jacobi matrix and the weights
+/// are `mu0` times the squared first components of its normalised eigenvectors.
+///
+/// # Arguments
+/// * `alpha` - `alpha_0, ..., alpha_{n-1}`
+/// * `beta` - `beta_1, ..., beta_{n-1}`
+/// * `mu0` - Integral of the weight function over the interval
+///
+/// Fails with `InvalidInput` for an empty `alpha` or a `beta` that is not one
+/// shorter, and with `Breakdown` if the eigenvalue iteration does not converge.
+pub fn golub_welsch(alpha: &[f64], beta: &[f64], mu0: f64) -> Result<Rule> {
+    let n = alpha.len();
+    if n == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "Gauss rule must have at least one node",
+        )));
+    }
+    if beta.len() != n - 1 {
+        return Err(Mat627Error::InvalidInput(format!(
+            "{n}-point recurrence needs {} beta coefficients, got {}",
+            n - 1,
+            beta.len()
+        )));
+    }
+
+    let mut d = alpha.to_vec();
+    let mut e: Vec<f64> = beta.iter().map(|b| b.sqrt()).collect();
+    e.push(0.0);
+    let mut z = vec![0.0; n];
+    z[0] = 1.0;
+
+    tridiagonal_ql(&mut d, &mut e, &mut z)?;
+
+    let mut pairs: Vec<(f64, f64)> = d
+        .into_iter()
+        .zip(z)
+        .map(|(x, v)| (x, mu0 * v * v))
+        .collect();
+    pairs.sort_by(|p, q| p.0.total_cmp(&q.0));
+
+    Ok(Rule {
+        nodes: pairs.iter().map(|p| p.0).collect(),
+        weights: pairs.iter().map(|p| p.1).collect(),
+    })
+}
+
+/// Implicit-shift QL iteration for a symmetric tridiagonal matrix.
+///
+/// On return `d` holds the eigenvalues and `z` the first row of the
+/// eigenvector matrix (given `z = e_1` on entry). `e[i]` is the off-diagonal
+/// entry between rows `i` and `i + 1`; it is destroyed. Fails with
+/// `Breakdown` when an eigenvalue needs more than 60 iterations.
+fn tridiagonal_ql(d: &mut [f64], e: &mut [f64], z: &mut [f64]) -> Result<()> {
+    let n = d.len();
+
+    for l in 0..n {
+        let mut iterations = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= f64::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+
+            iterations += 1;
+            if iterations > 60 {
+                return Err(Mat627Error::Breakdown(format!(
+                    "QL iteration did not converge for eigenvalue {l}"
+                )));
+            }
+
+            let mut g = (d[l + 1] - d[l]) / (2.0 * e[l]);
+            let mut r = g.hypot(1.0);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let (mut s, mut c, mut p) = (1.0, 1.0, 0.0);
+            let mut deflated = false;
+
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == 0.0 {
+                    d[i + 1] -= p;
+                    e[m] = 0.0;
+                    deflated = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + 2.0 * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+
+                let zf = z[i + 1];
+                z[i + 1] = s * z[i] + c * zf;
+                z[i] = c * z[i] - s * zf;
+            }
+
+            if deflated {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.0;
+        }
+    }
+
+    Ok(())
+}
+
+/// `n`-point Gauss-Legendre rule on `[-1, 1]`, weight `1`.
+pub fn legendre(n: usize) -> Result<Rule> {
+    let alpha = vec![0.0; n];
+    let beta: Vec<f64> = (1..n)
+        .map(|k| {
+            let k = k as f64;
+            k * k / (4.0 * k * k - 1.0)
+        })
+        .collect();
+
+    golub_welsch(&alpha, &beta, 2.0)
+}
+
+/// `n`-point generalised Gauss-Laguerre rule on `[0, inf)`, weight `x^a e^{-x}`.
+pub fn laguerre(n: usize, a: f64) -> Result<Rule> {
+    if a.is_nan() || a <= -1.0 {
+        return Err(Mat627Error::InvalidInput(format!(
+            "Laguerre exponent {a} must exceed -1"
+        )));
+    }
+
+    let alpha: Vec<f64> = (0..n).map(|k| 2.0 * k as f64 + a + 1.0).collect();
+    let beta: Vec<f64> = (1..n)
+        .map(|k| {
+            let k = k as f64;
+            k * (k + a)
+        })
+        .collect();
+
+    golub_welsch(&alpha, &beta, gamma(a + 1.0))
+}
+
+/// `n`-point Gauss-Jacobi rule on `[-1, 1]`, weight `(1 - x)^a (1 + x)^b`.
+pub fn jacobi(n: usize, a: f64, b: f64) -> Result<Rule> {
+    if a.is_nan() || b.is_nan() || a <= -1.0 || b <= -1.0 {
+        return Err(Mat627Error::InvalidInput(format!(
+            "Jacobi exponents ({a}, {b}) must exceed -1"
+        )));
+    }
+
+    let ab = a + b;
+    let alpha: Vec<f64> = (0..n)
+        .map(|k| {
+            let s = 2.0 * k as f64 + ab;
+            if k == 0 {
+                (b - a) / (ab + 2.0)
+            } else {
+                (b * b - a * a) / (s * (s + 2.0))
+            }
+        })
+        .collect();
+    let beta: Vec<f64> = (1..n)
+        .map(|k| {
+            let kf = k as f64;
+            let s = 2.0 * kf + ab;
+            if k == 1 {
+                4.0 * (1.0 + a) * (1.0 + b) / ((2.0 + ab).powi(2) * (3.0 + ab))
+            } else {
+                4.0 * kf * (kf + a) * (kf + b) * (kf + ab) / (s * s * (s + 1.0) * (s - 1.0))
+            }
+        })
+        .collect();
+    let mu0 = 2.0_f64.powf(ab + 1.0) * gamma(a + 1.0) * gamma(b + 1.0) / gamma(ab + 2.0);
+
+    golub_welsch(&alpha, &beta, mu0)
+}
+
+/// Gamma function by the Lanczos approximation (g = 7, n = 9).
+fn gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * gamma(1.0 - x))
+    } else {
+        let x = x - 1.0;
+        let t = x + G + 0.5;
+        let series = COEFF[1..]
+            .iter()
+            .enumerate()
+            .fold(COEFF[0], |acc, (i, c)| acc + c / (x + (i + 1) as f64));
+
+        (2.0 * std::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * series
+    }
+}
+
+/// `n`-point Gauss-Legendre approximation of the integral of `f` on `[a, b]`.
+pub fn gauss_legendre<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, n: usize) -> Result<f64> {
+    Ok(legendre(n)?.integrate_on(f, a, b))
+}
+
+/// Composite `n`-point Gauss-Legendre rule on `panels` equal subintervals of `[a, b]`.
+pub fn composite_gauss_legendre<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    panels: usize,
+    n: usize,
+) -> Result<f64> {
+    if panels == 0 {
+        return Err(Mat627Error::InvalidInput(String::from(
+            "composite Gauss-Legendre needs at least one panel",
+        )));
+    }
+
+    let rule = legendre(n)?;
+    let h = (b - a) / panels as f64;
+
+    Ok((0..panels)
+        .map(|i| {
+            let left = a + i as f64 * h;
+            rule.integrate_on(&f, left, left + h)
+        })
+        .sum())
+}
+
+/// `n`-point Gauss-Jacobi approximation of the integral of
+/// `(b - x)^alpha (x - a)^beta g(x)` on `[a, b]`.
+///
+/// The endpoint factors are absorbed into the weight, so only the smooth part
+/// `g` is sampled.
+pub fn gauss_jacobi<F: Fn(f64) -> f64>(
+    g: F,
+    a: f64,
+    b: f64,
+    n: usize,
+    alpha: f64,
+    beta: f64,
+) -> Result<f64> {
+    let half = 0.5 * (b - a);
+    let mid = 0.5 * (a + b);
+    let scale = half.powf(alpha + beta + 1.0);
+
+    Ok(scale * jacobi(n, alpha, beta)?.integrate(|t| g(mid + half * t)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        composite_gauss_legendre, gamma, gauss_jacobi, golub_welsch, jacobi, laguerre, legendre,
+    };
+    use crate::error::Mat627Error;
+
+    #[test]
+    fn legendre_matches_tabulated_three_point_rule() {
+        let rule = legendre(3).expect("3 nodes");
+        let x = (0.6_f64).sqrt();
+
+        assert!((rule.nodes[0] + x).abs() < 1.0e-14);
+        assert!(rule.nodes[1].abs() < 1.0e-14);
+        assert!((rule.weights[0] - 5.0 / 9.0).abs() < 1.0e-14);
+        assert!((rule.weights[1] - 8.0 / 9.0).abs() < 1.0e-14);
+    }
+
+    #[test]
+    fn legendre_is_exact_to_degree_2n_minus_1() {
+        let rule = legendre(6).expect("6 nodes");
+
+        for k in 0..12 {
+            let exact = if k % 2 == 0 {
+                2.0 / (k + 1) as f64
+            } else {
+                0.0
+            };
+            assert!(
+                (rule.integrate(|x| x.powi(k)) - exact).abs() < 1.0e-13,
+                "degree {k}"
+            );
+        }
+    }
+
+    #[test]
+    fn laguerre_integrates_moments() {
+        let rule = laguerre(8, 0.0).expect("exponent 0");
+        let mut factorial = 1.0;
+
+        for k in 0..16 {
+            if k > 0 {
+                factorial *= k as f64;
+            }
+            let approx = rule.integrate(|x| x.powi(k));
+            assert!(
+                (approx - factorial).abs() < 1.0e-11 * factorial,
+                "degree {k}"
+            );
+        }
+    }
+
+    #[test]
+    fn jacobi_weights_sum_to_weight_integral() {
+        let rule = jacobi(5, 0.5, -0.5).expect("exponents exceed -1");
+
+        assert!((rule.weights.iter().sum::<f64>() - std::f64::consts::PI).abs() < 1.0e-13);
+        assert!((gamma(5.0) - 24.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn jacobi_reduces_to_gauss_chebyshev() {
+        let n = 7;
+        let rule = jacobi(n, -0.5, -0.5).expect("exponents exceed -1");
+        let pi = std::f64::consts::PI;
+
+        for i in 0..n {
+            let node = -((2 * i + 1) as f64 * pi / (2 * n) as f64).cos();
+            assert!((rule.nodes[i] - node).abs() < 1.0e-13);
+            assert!((rule.weights[i] - pi / n as f64).abs() < 1.0e-13);
+        }
+    }
+
+    #[test]
+    fn gauss_jacobi_integrates_sqrt_exactly() {
+        let approx = gauss_jacobi(|_| 1.0, 0.0, 1.0, 1, 0.0, 0.5).expect("exponents exceed -1");
+
+        assert!((approx - 2.0 / 3.0).abs() < 1.0e-14);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let invalid = |result| matches!(result, Err(Mat627Error::InvalidInput(_)));
+
+        assert!(invalid(legendre(0)));
+        assert!(invalid(golub_welsch(&[0.0, 0.0], &[], 2.0)));
+        assert!(invalid(laguerre(4, -1.0)));
+        assert!(invalid(jacobi(4, 0.0, -1.5)));
+        assert!(matches!(
+            composite_gauss_legendre(f64::exp, 0.0, 1.0, 0, 3),
+            Err(Mat627Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn non_finite_recurrence_breaks_down() {
+        let result = golub_welsch(&[0.0, f64::NAN, 0.0], &[1.0, 1.0], 1.0);
+
+        assert!(matches!(result, Err(Mat627Error::Breakdown(_))));
+    }
+}
@@ -0,0 +1,89 @@
+//! LaTeX output for the chapter reports.
+//!
+//! The drivers build their booktabs tables with [`table::Table`] straight from
+//! the row structs they already compute and collect them in a [`TexFile`],
+//! which each report's `main.tex` pulls in with `\input{figures/tables.tex}`.
+
+pub mod table;
+
+use crate::error::Result;
+use std::fs;
+use std::path::Path;
+
+/// A generated `.tex` fragment: header comments followed by blocks of LaTeX.
+#[derive(Debug, Clone, Default)]
+pub struct TexFile {
+    blocks: Vec<String>,
+    needs_graphicx: bool,
+}
+
+impl TexFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append raw LaTeX, e.g. a `\section{...}` heading or a blank line.
+    pub fn line(&mut self, text: impl Into<String>) -> &mut Self {
+        self.blocks.push(text.into());
+        self
+    }
+
+    /// Append `table` rendered over `rows`.
+    pub fn table<R>(&mut self, table: &table::Table<'_, R>, rows: &[R]) -> &mut Self {
+        self.needs_graphicx |= table.fits_width();
+        self.blocks.push(table.render(rows));
+        self
+    }
+
+    /// The file contents, with the packages the tables need listed up front.
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            String::from("% Auto-generated by the mat627 chapter drivers"),
+            String::from("% Requires \\usepackage{booktabs}"),
+        ];
+        if self.needs_graphicx {
+            lines.push(String::from(
+                "% Requires \\usepackage{graphicx} (for \\resizebox)",
+            ));
+        }
+        lines.push(String::new());
+        lines.extend(self.blocks.iter().cloned());
+        lines.join("\n")
+    }
+
+    /// Write the file, creating its parent directory.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.render())?;
+        println!("Wrote LaTeX tables to {}", path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TexFile;
+    use super::table::{Format, Table};
+
+    #[test]
+    fn graphicx_is_required_only_for_fitted_tables() {
+        let rows = [1.0_f64, 2.0];
+        let plain = Table::new("Plain.", "plain").column("$x$", Format::Sci, |x: &f64| *x);
+        let fitted =
+            Table::new("Fitted.", "fitted")
+                .fit_width()
+                .column("$x$", Format::Sci, |x: &f64| *x);
+
+        let mut tex = TexFile::new();
+        tex.table(&plain, &rows);
+        assert!(!tex.render().contains("graphicx"));
+
+        tex.line(r"\section{Fitted}").table(&fitted, &rows);
+        let text = tex.render();
+        assert!(text.contains("graphicx"));
+        assert!(text.contains("\\end{table}\n\n\\section{Fitted}\n\\begin{table}"));
+    }
+}
@@ -0,0 +1,265 @@
+//! Booktabs tables built from a slice of rows.
+//!
+//! Each column pairs a header with an accessor into the row type and a
+//! [`Format`]; [`Table::rate`] columns take `Option<f64>` and print `--` for
+//! rates that could not be measured.
+
+use std::fmt;
+
+/// How a numeric cell is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Rounded to the nearest integer: `16`.
+    Int,
+    /// Fixed number of decimals: `Fixed(6)` gives `0.998118`.
+    Fixed(usize),
+    /// Fixed decimals with trailing zeros removed: `Trimmed(5)` gives `0.0125`.
+    Trimmed(usize),
+    /// Seven significant digits in math mode: `$1.234567\times 10^{-3}$`.
+    Sci,
+    /// The reciprocal, as `$n$` when it is an integer and [`Format::Sci`] otherwise.
+    /// Used for step sizes `h = 1/n`.
+    Reciprocal,
+}
+
+impl Format {
+    pub fn apply(self, x: f64) -> String {
+        match self {
+            Self::Int => format!("{}", x.round() as i64),
+            Self::Fixed(digits) => format!("{x:.digits$}"),
+            Self::Trimmed(digits) => {
+                let s = format!("{x:.digits$}");
+                if s.contains('.') {
+                    s.trim_end_matches('0').trim_end_matches('.').to_string()
+                } else {
+                    s
+                }
+            }
+            Self::Sci => num_sci(x),
+            Self::Reciprocal => {
+                let inv = 1.0 / x;
+                let rounded = inv.round();
+                if (inv - rounded).abs() < 1.0e-12 {
+                    format!("${}$", rounded as i64)
+                } else {
+                    num_sci(inv)
+                }
+            }
+        }
+    }
+}
+
+/// Plain LaTeX scientific notation with a six-digit mantissa, no siunitx needed.
+pub fn num_sci(x: f64) -> String {
+    if !x.is_finite() {
+        return String::from("--");
+    }
+    let s = format!("{x:.6e}");
+    let (mantissa, exponent) = s.split_once('e').expect("`{:e}` always has an exponent");
+    format!("${mantissa}\\times 10^{{{exponent}}}$")
+}
+
+type Cell<'a, R> = Box<dyn Fn(&R) -> String + 'a>;
+
+struct Column<'a, R> {
+    header: String,
+    align: char,
+    cell: Cell<'a, R>,
+}
+
+/// A `table` float holding one booktabs `tabular`.
+pub struct Table<'a, R> {
+    caption: String,
+    label: String,
+    compact: Option<u32>,
+    fit_width: bool,
+    columns: Vec<Column<'a, R>>,
+}
+
+impl<'a, R> Table<'a, R> {
+    /// An empty table; `label` is prefixed with `tab:`.
+    pub fn new(caption: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            caption: caption.into(),
+            label: label.into(),
+            compact: None,
+            fit_width: false,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Set in `\scriptsize` with `tabcolsep` points between columns.
+    pub fn compact(mut self, tabcolsep: u32) -> Self {
+        self.compact = Some(tabcolsep);
+        self
+    }
+
+    /// Scale the tabular to `\textwidth` with `\resizebox` (needs graphicx).
+    pub fn fit_width(mut self) -> Self {
+        self.fit_width = true;
+        self
+    }
+
+    pub fn fits_width(&self) -> bool {
+        self.fit_width
+    }
+
+    /// A right-aligned numeric column.
+    pub fn column(
+        self,
+        header: impl Into<String>,
+        format: Format,
+        value: impl Fn(&R) -> f64 + 'a,
+    ) -> Self {
+        self.push(header, 'r', move |row| format.apply(value(row)))
+    }
+
+    /// A right-aligned column of observed rates; `None` and non-finite values print as `--`.
+    pub fn rate(
+        self,
+        header: impl Into<String>,
+        format: Format,
+        value: impl Fn(&R) -> Option<f64> + 'a,
+    ) -> Self {
+        self.optional(header, format, value)
+    }
+
+    /// A right-aligned numeric column with missing values, printed as `--`.
+    pub fn optional(
+        self,
+        header: impl Into<String>,
+        format: Format,
+        value: impl Fn(&R) -> Option<f64> + 'a,
+    ) -> Self {
+        self.push(header, 'r', move |row| match value(row) {
+            Some(x) if x.is_finite() => format.apply(x),
+            _ => String::from("--"),
+        })
+    }
+
+    /// A left-aligned column of LaTeX text.
+    pub fn text(self, header: impl Into<String>, value: impl Fn(&R) -> String + 'a) -> Self {
+        self.push(header, 'l', value)
+    }
+
+    fn push(
+        mut self,
+        header: impl Into<String>,
+        align: char,
+        cell: impl Fn(&R) -> String + 'a,
+    ) -> Self {
+        self.columns.push(Column {
+            header: header.into(),
+            align,
+            cell: Box::new(cell),
+        });
+        self
+    }
+
+    /// The table float over `rows`, ending in a newline.
+    pub fn render(&self, rows: &[R]) -> String {
+        let spec: String = self.columns.iter().map(|c| c.align).collect();
+        let row_line = |cells: Vec<String>| format!("{} \\\\", cells.join(" & "));
+
+        let mut lines = vec![
+            String::from("\\begin{table}[htbp]"),
+            String::from("\\centering"),
+        ];
+        if let Some(tabcolsep) = self.compact {
+            lines.push(String::from("\\scriptsize"));
+            lines.push(format!("\\setlength{{\\tabcolsep}}{{{tabcolsep}pt}}"));
+            lines.push(String::from("\\renewcommand{\\arraystretch}{1.15}"));
+        }
+        lines.push(format!("\\caption{{{}}}", self.caption));
+        lines.push(format!("\\label{{tab:{}}}", self.label));
+        if self.fit_width {
+            lines.push(String::from("\\resizebox{\\textwidth}{!}{%"));
+        }
+        lines.push(format!("\\begin{{tabular}}{{{spec}}}"));
+        lines.push(String::from("\\toprule"));
+        lines.push(row_line(
+            self.columns.iter().map(|c| c.header.clone()).collect(),
+        ));
+        lines.push(String::from("\\midrule"));
+        for row in rows {
+            lines.push(row_line(
+                self.columns.iter().map(|c| (c.cell)(row)).collect(),
+            ));
+        }
+        lines.push(String::from("\\bottomrule"));
+        if self.fit_width {
+            lines.push(String::from("\\end{tabular}%"));
+            lines.push(String::from("}"));
+        } else {
+            lines.push(String::from("\\end{tabular}"));
+        }
+        lines.push(String::from("\\end{table}"));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+impl<R> fmt::Debug for Table<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("label", &self.label)
+            .field(
+                "columns",
+                &self.columns.iter().map(|c| &c.header).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Table, num_sci};
+
+    #[test]
+    fn scientific_notation_matches_python_num_sci() {
+        assert_eq!(num_sci(-1.234_567_4e-3), r"$-1.234567\times 10^{-3}$");
+        assert_eq!(num_sci(0.0), r"$0.000000\times 10^{0}$");
+        assert_eq!(num_sci(2.5e12), r"$2.500000\times 10^{12}$");
+    }
+
+    #[test]
+    fn formats_cover_the_report_columns() {
+        assert_eq!(Format::Int.apply(160.0), "160");
+        assert_eq!(Format::Fixed(2).apply(1.25), "1.25");
+        assert_eq!(Format::Trimmed(5).apply(0.0125), "0.0125");
+        assert_eq!(Format::Trimmed(5).apply(0.5), "0.5");
+        assert_eq!(Format::Trimmed(5).apply(1.0), "1");
+        assert_eq!(Format::Reciprocal.apply(0.125), "$8$");
+        assert_eq!(Format::Reciprocal.apply(0.3), r"$3.333333\times 10^{0}$");
+    }
+
+    #[test]
+    fn rate_column_renders_missing_rates_as_dashes() {
+        let rows = [(10, 1.0e-2, None), (20, 2.5e-3, Some(2.0))];
+        let table = Table::new("Errors.", "errors")
+            .column("$N$", Format::Int, |r: &(usize, f64, Option<f64>)| {
+                r.0 as f64
+            })
+            .column("$E_h$", Format::Sci, |r| r.1)
+            .rate("Rate", Format::Fixed(6), |r| r.2);
+
+        let expected = [
+            r"\begin{table}[htbp]",
+            r"\centering",
+            r"\caption{Errors.}",
+            r"\label{tab:errors}",
+            r"\begin{tabular}{rrr}",
+            r"\toprule",
+            r"$N$ & $E_h$ & Rate \\",
+            r"\midrule",
+            r"10 & $1.000000\times 10^{-2}$ & -- \\",
+            r"20 & $2.500000\times 10^{-3}$ & 2.000000 \\",
+            r"\bottomrule",
+            r"\end{tabular}",
+            r"\end{table}",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(table.render(&rows), expected);
+    }
+}
@@ -0,0 +1,193 @@
+//! Summing series term by term under a stopping rule.
+//!
+//! A [`Series`] is a first term and a generator for each following term
+//! from its predecessor, which is how Taylor series are usually evaluated.
+//! [`Series::sum`] records every partial sum until its [`Stop`] rule fires,
+//! so the error can be followed as a function of the number of terms. The
+//! terms are added with a selectable [`Summation`] strategy, and
+//! [`acceleration`] extrapolates the partial sums to their limit.
+
+pub mod acceleration;
+
+use crate::summation::Summation;
+use crate::util;
+use serde::Deserialize;
+
+/// When [`Series::sum`] stops adding terms.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Stop {
+    /// Sum exactly the terms `0..=n`.
+    Terms { n: usize },
+    /// Stop at the first term with `|t_n| <= tol * |S_n|`.
+    RelativeTerm { tol: f64, max_terms: usize },
+    /// Stop once adding a term leaves the partial sum unchanged.
+    Stagnation { max_terms: usize },
+}
+
+/// Which rule ended a [`PartialSums`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stopped {
+    /// [`Stop::Terms`] reached its count, or another rule hit `max_terms`.
+    TermLimit,
+    Tolerance,
+    Stagnation,
+}
+
+/// A series `t_0 + t_1 + ...` with `t_k = next(k, t_{k-1})`.
+pub struct Series<F> {
+    first: f64,
+    next: F,
+    finish: fn(f64) -> f64,
+    summation: Summation,
+}
+
+impl<F: FnMut(usize, f64) -> f64> Series<F> {
+    pub fn new(first: f64, next: F) -> Self {
+        Self {
+            first,
+            next,
+            finish: |sum| sum,
+            summation: Summation::Naive,
+        }
+    }
+
+    /// Turn each partial sum into the approximation it stands for, e.g.
+    /// `f64::recip` when the series sums the reciprocal of the target.
+    pub fn finish(mut self, finish: fn(f64) -> f64) -> Self {
+        self.finish = finish;
+        self
+    }
+
+    /// Accumulate the terms with `summation` instead of left-to-right addition.
+    pub fn summation(mut self, summation: Summation) -> Self {
+        self.summation = summation;
+        self
+    }
+
+    /// Add terms until `stop` fires, recording every partial sum.
+    pub fn sum(mut self, stop: Stop) -> PartialSums {
+        let max_terms = match stop {
+            Stop::Terms { n } => n,
+            Stop::RelativeTerm { max_terms, .. } | Stop::Stagnation { max_terms } => max_terms,
+        };
+
+        let mut term = self.first;
+        let mut acc = self.summation.accumulator();
+        acc.add(term);
+        let mut partial_sums = vec![acc.value()];
+        let mut stopped = Stopped::TermLimit;
+        for k in 1..=max_terms {
+            let prev = partial_sums[k - 1];
+            term = (self.next)(k, term);
+            acc.add(term);
+            let sum = acc.value();
+            partial_sums.push(sum);
+            match stop {
+                Stop::RelativeTerm { tol, .. } if term.abs() <= tol * sum.abs() => {
+                    stopped = Stopped::Tolerance;
+                    break;
+                }
+                Stop::Stagnation { .. } if sum == prev => {
+                    stopped = Stopped::Stagnation;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let approx = partial_sums.iter().map(|&s| (self.finish)(s)).collect();
+        PartialSums {
+            partial_sums,
+            approx,
+            stopped,
+        }
+    }
+}
+
+/// The partial sums `S_0, ..., S_n` of a [`Series`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialSums {
+    pub partial_sums: Vec<f64>,
+    /// The partial sums after [`Series::finish`].
+    pub approx: Vec<f64>,
+    pub stopped: Stopped,
+}
+
+impl PartialSums {
+    /// Index `n` of the last term added.
+    pub fn terms(&self) -> usize {
+        self.partial_sums.len() - 1
+    }
+
+    /// The final approximation.
+    pub fn value(&self) -> f64 {
+        *self
+            .approx
+            .last()
+            .expect("a summation has at least one term")
+    }
+
+    /// Relative error of each approximation against `exact`, indexed by `n`.
+    pub fn rel_errors(&self, exact: f64) -> Vec<f64> {
+        self.approx
+            .iter()
+            .map(|&approx| util::rel_error(approx, exact))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Series, Stop, Stopped, Summation};
+
+    fn exp(x: f64) -> Series<impl FnMut(usize, f64) -> f64> {
+        Series::new(1.0, move |k, term| term * (x / k as f64))
+    }
+
+    #[test]
+    fn stopping_rules() {
+        let fixed = exp(1.0).sum(Stop::Terms { n: 3 });
+        assert_eq!(fixed.partial_sums, vec![1.0, 2.0, 2.5, 2.5 + 1.0 / 6.0]);
+        assert_eq!(fixed.stopped, Stopped::TermLimit);
+
+        let tol = exp(1.0).sum(Stop::RelativeTerm {
+            tol: 1.0e-6,
+            max_terms: 100,
+        });
+        assert_eq!(tol.stopped, Stopped::Tolerance);
+        assert_eq!(tol.terms(), 10);
+        assert!(tol.rel_errors(1.0_f64.exp())[10] < 1.0e-7);
+
+        let stagnant = exp(1.0).sum(Stop::Stagnation { max_terms: 100 });
+        assert_eq!(stagnant.stopped, Stopped::Stagnation);
+        assert_eq!(
+            stagnant.value(),
+            stagnant.partial_sums[stagnant.terms() - 1]
+        );
+        assert!((stagnant.value() - 1.0_f64.exp()).abs() <= f64::EPSILON * 3.0);
+
+        let capped = exp(1.0).sum(Stop::Stagnation { max_terms: 5 });
+        assert_eq!((capped.terms(), capped.stopped), (5, Stopped::TermLimit));
+    }
+
+    #[test]
+    fn finish_maps_every_partial_sum() {
+        let recip = exp(2.0).finish(f64::recip).sum(Stop::Terms { n: 2 });
+        assert_eq!(recip.partial_sums, vec![1.0, 3.0, 5.0]);
+        assert_eq!(recip.approx, vec![1.0, 1.0 / 3.0, 0.2]);
+    }
+
+    #[test]
+    fn summation_strategy_applies_to_every_partial_sum() {
+        let terms = [1.0, 1.0e100, 1.0, -1.0e100];
+        let series = || Series::new(terms[0], |k, _| terms[k]);
+
+        let naive = series().sum(Stop::Terms { n: 3 });
+        let exact = series()
+            .summation(Summation::Exact)
+            .sum(Stop::Terms { n: 3 });
+        assert_eq!(naive.partial_sums, vec![1.0, 1.0e100, 1.0e100, 0.0]);
+        assert_eq!(exact.partial_sums, vec![1.0, 1.0e100, 1.0e100, 2.0]);
+    }
+}
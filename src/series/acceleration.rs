@@ -0,0 +1,178 @@
+//! Sequence transformations that accelerate slowly or wildly converging
+//! partial sums.
+//!
+//! Each [`Transform`] maps `S_0, ..., S_n` to an estimate of the limit that
+//! uses only those terms, so applied to every prefix it gives a new sequence
+//! indexed like the original. The error indicator of each estimate is its
+//! distance from the previous one.
+
+use serde::Deserialize;
+use std::fmt;
+
+/// A sequence transformation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transform {
+    /// Aitken's Δ² process on the last three terms.
+    Aitken,
+    /// Wynn's ε-algorithm, i.e. the Shanks transformation of the highest
+    /// order the terms allow.
+    Wynn,
+    /// Levin's u-transform over all terms.
+    Levin,
+}
+
+impl Transform {
+    pub const ALL: [Self; 3] = [Self::Aitken, Self::Wynn, Self::Levin];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aitken => "aitken",
+            Self::Wynn => "wynn",
+            Self::Levin => "levin",
+        }
+    }
+
+    /// The estimate of the limit from `sums`, falling back to the last term
+    /// when the transform is undefined (too few terms, a zero denominator).
+    pub fn estimate(self, sums: &[f64]) -> f64 {
+        let Some(&last) = sums.last() else {
+            return f64::NAN;
+        };
+        let estimate = match self {
+            Self::Aitken => aitken(sums),
+            Self::Wynn => wynn(sums),
+            Self::Levin => levin_u(sums),
+        };
+        estimate.filter(|e| e.is_finite()).unwrap_or(last)
+    }
+
+    /// [`Transform::estimate`] of every prefix of `sums`.
+    pub fn apply(self, sums: &[f64]) -> Accelerated {
+        let estimates: Vec<f64> = (1..=sums.len())
+            .map(|len| self.estimate(&sums[..len]))
+            .collect();
+        let errors = std::iter::once(None)
+            .chain(estimates.windows(2).map(|w| Some((w[1] - w[0]).abs())))
+            .collect();
+        Accelerated { estimates, errors }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The output of [`Transform::apply`], indexed like the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accelerated {
+    pub estimates: Vec<f64>,
+    /// `|T_n - T_{n-1}|`, `None` for the first estimate.
+    pub errors: Vec<Option<f64>>,
+}
+
+/// `S_n - (ΔS_{n-1})^2 / Δ²S_{n-2}`.
+fn aitken(sums: &[f64]) -> Option<f64> {
+    let [s0, s1, s2] = sums.get(sums.len().checked_sub(3)?..)? else {
+        return None;
+    };
+    let (d1, d2) = (s1 - s0, s2 - s1);
+    let denom = d2 - d1;
+    (denom != 0.0).then(|| s2 - d2 * d2 / denom)
+}
+
+/// The last entry of the deepest even column of the ε-table built on `sums`.
+///
+/// Columns `ε_k^{(j)} = ε_{k-2}^{(j+1)} + 1 / (ε_{k-1}^{(j+1)} - ε_{k-1}^{(j)})`;
+/// the table is cut off at the first column whose last entry is not finite,
+/// which is where the terms have stopped changing.
+fn wynn(sums: &[f64]) -> Option<f64> {
+    let mut prev = vec![0.0; sums.len() + 1];
+    let mut cur = sums.to_vec();
+    let mut best = *sums.last()?;
+    for k in 1..sums.len() {
+        let next: Vec<f64> = (0..cur.len() - 1)
+            .map(|j| prev[j + 1] + 1.0 / (cur[j + 1] - cur[j]))
+            .collect();
+        if !next.last().is_some_and(|e| e.is_finite()) {
+            break;
+        }
+        if k % 2 == 0 {
+            best = *next.last().expect("checked above");
+        }
+        prev = cur;
+        cur = next;
+    }
+    Some(best)
+}
+
+/// Levin's u-transform `L_n` with `β = 1` and remainder estimates
+/// `ω_j = (j + 1) a_j`, where `a_j = S_j - S_{j-1}` are the terms.
+fn levin_u(sums: &[f64]) -> Option<f64> {
+    let k = sums.len() - 1;
+    let beta = 1.0;
+    let mut binomial = 1.0;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (j, &s) in sums.iter().enumerate() {
+        let term = if j == 0 { s } else { s - sums[j - 1] };
+        if term == 0.0 {
+            return None;
+        }
+        let omega = (beta + j as f64) * term;
+        let sign = if j % 2 == 0 { 1.0 } else { -1.0 };
+        let c = sign * binomial * ((beta + j as f64) / (beta + k as f64)).powi(k as i32 - 1);
+        num += c * s / omega;
+        den += c / omega;
+        binomial *= (k - j) as f64 / (j + 1) as f64;
+    }
+    Some(num / den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transform;
+
+    fn partial_sums(terms: impl Iterator<Item = f64>) -> Vec<f64> {
+        terms
+            .scan(0.0, |sum, t| {
+                *sum += t;
+                Some(*sum)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aitken_and_wynn_sum_a_geometric_series_exactly() {
+        // 1 - 1/2 + 1/4 - ... = 2/3.
+        let sums = partial_sums((0..6).map(|k| (-0.5_f64).powi(k)));
+        for transform in [Transform::Aitken, Transform::Wynn] {
+            let estimate = transform.estimate(&sums);
+            assert!((estimate - 2.0 / 3.0).abs() < 1.0e-15, "{transform}");
+        }
+        assert_eq!(Transform::Aitken.estimate(&sums[..2]), sums[1]);
+    }
+
+    #[test]
+    fn transforms_accelerate_the_alternating_harmonic_series() {
+        // 1 - 1/2 + 1/3 - ... = ln 2; twelve raw terms are only good to ~4e-2.
+        let sums =
+            partial_sums((0..12).map(|k| if k % 2 == 0 { 1.0 } else { -1.0 } / (k + 1) as f64));
+        let ln2 = 2.0_f64.ln();
+        assert!((sums[11] - ln2).abs() > 1.0e-2);
+
+        let bounds = [
+            (Transform::Aitken, 1.0e-3),
+            (Transform::Wynn, 1.0e-8),
+            (Transform::Levin, 1.0e-10),
+        ];
+        for (transform, bound) in bounds {
+            let accelerated = transform.apply(&sums);
+            let error = (accelerated.estimates[11] - ln2).abs();
+            assert!(error < bound, "{transform}: {error:e}");
+            assert_eq!(accelerated.errors[0], None);
+            assert!(accelerated.errors[11].unwrap() < 10.0 * bound);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Floating-point summation strategies.
+//!
+//! Every loop that accumulates a sum can take a [`Summation`] to choose how
+//! rounding errors in the additions are handled, from plain left-to-right
+//! addition to a correctly rounded sum built on error-free transformations.
+//! Comparing a result against [`Summation::Exact`] separates the error due
+//! to accumulation from everything else (truncation, rounded terms).
+
+use serde::Deserialize;
+use std::fmt;
+
+/// How to add up a sequence of floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Summation {
+    /// Left to right; the error bound grows like `n eps`.
+    #[default]
+    Naive,
+    /// Pairs of equal-size blocks, combined like a binary counter; `log2(n) eps`.
+    Pairwise,
+    /// Kahan's compensated summation.
+    Kahan,
+    /// Neumaier's variant of Kahan's, which also handles terms larger than
+    /// the running sum.
+    Neumaier,
+    /// Shewchuk's non-overlapping partials (as in Python's `math.fsum`):
+    /// the sum correctly rounded to the nearest double.
+    Exact,
+}
+
+impl Summation {
+    pub const ALL: [Self; 5] = [
+        Self::Naive,
+        Self::Pairwise,
+        Self::Kahan,
+        Self::Neumaier,
+        Self::Exact,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Naive => "naive",
+            Self::Pairwise => "pairwise",
+            Self::Kahan => "kahan",
+            Self::Neumaier => "neumaier",
+            Self::Exact => "exact",
+        }
+    }
+
+    pub fn accumulator(self) -> Accumulator {
+        let state = match self {
+            Self::Naive => State::Naive(0.0),
+            Self::Pairwise => State::Pairwise(Vec::new()),
+            Self::Kahan => State::Kahan { sum: 0.0, c: 0.0 },
+            Self::Neumaier => State::Neumaier { sum: 0.0, c: 0.0 },
+            Self::Exact => State::Exact {
+                partials: Vec::new(),
+                special: 0.0,
+            },
+        };
+        Accumulator { state }
+    }
+
+    pub fn sum(self, values: impl IntoIterator<Item = f64>) -> f64 {
+        let mut acc = self.accumulator();
+        for x in values {
+            acc.add(x);
+        }
+        acc.value()
+    }
+}
+
+impl fmt::Display for Summation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A running sum under one [`Summation`] strategy.
+///
+/// [`Accumulator::value`] may be read after every [`Accumulator::add`], so a
+/// series can record its partial sums with the same strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accumulator {
+    state: State,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Naive(f64),
+    /// `(block sum, block length)` with lengths strictly decreasing.
+    Pairwise(Vec<(f64, usize)>),
+    Kahan {
+        sum: f64,
+        c: f64,
+    },
+    Neumaier {
+        sum: f64,
+        c: f64,
+    },
+    /// Non-overlapping partials in increasing magnitude, plus the plain sum
+    /// of any infinite or NaN inputs (or an overflow), which takes over.
+    Exact {
+        partials: Vec<f64>,
+        special: f64,
+    },
+}
+
+impl Accumulator {
+    pub fn add(&mut self, x: f64) {
+        match &mut self.state {
+            State::Naive(sum) => *sum += x,
+            State::Pairwise(blocks) => {
+                let mut block = (x, 1);
+                while let Some(&(sum, len)) = blocks.last() {
+                    if len != block.1 {
+                        break;
+                    }
+                    blocks.pop();
+                    block = (sum + block.0, len * 2);
+                }
+                blocks.push(block);
+            }
+            State::Kahan { sum, c } => {
+                let y = x - *c;
+                let t = *sum + y;
+                *c = (t - *sum) - y;
+                *sum = t;
+            }
+            State::Neumaier { sum, c } => {
+                let t = *sum + x;
+                *c += if sum.abs() >= x.abs() {
+                    (*sum - t) + x
+                } else {
+                    (x - t) + *sum
+                };
+                *sum = t;
+            }
+            State::Exact { partials, special } => {
+                if !x.is_finite() {
+                    *special += x;
+                    return;
+                }
+                let mut x = x;
+                let mut kept = 0;
+                for i in 0..partials.len() {
+                    let mut y = partials[i];
+                    if x.abs() < y.abs() {
+                        std::mem::swap(&mut x, &mut y);
+                    }
+                    let (hi, lo) = fast_two_sum(x, y);
+                    if !hi.is_finite() {
+                        *special += hi;
+                        return;
+                    }
+                    if lo != 0.0 {
+                        partials[kept] = lo;
+                        kept += 1;
+                    }
+                    x = hi;
+                }
+                partials.truncate(kept);
+                if x != 0.0 {
+                    partials.push(x);
+                }
+            }
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        match &self.state {
+            State::Naive(sum) => *sum,
+            State::Pairwise(blocks) => blocks
+                .iter()
+                .rev()
+                .map(|(sum, _)| sum)
+                .fold(0.0, |a, b| a + b),
+            State::Kahan { sum, .. } => *sum,
+            State::Neumaier { sum, c } => sum + c,
+            State::Exact { partials, special } => {
+                if *special != 0.0 {
+                    *special
+                } else {
+                    round_partials(partials)
+                }
+            }
+        }
+    }
+}
+
+/// `a + b` as the rounded sum and its exact rounding error (Knuth's TwoSum).
+pub fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    let err = (a - (s - bb)) + (b - bb);
+    (s, err)
+}
+
+/// [`two_sum`] for `|a| >= |b|` (Dekker's FastTwoSum).
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// The correctly rounded sum of non-overlapping partials in increasing
+/// magnitude, following `math.fsum`.
+fn round_partials(partials: &[f64]) -> f64 {
+    let mut n = partials.len();
+    if n == 0 {
+        return 0.0;
+    }
+    n -= 1;
+    let mut hi = partials[n];
+    let mut lo = 0.0;
+    while n > 0 {
+        n -= 1;
+        let (sum, err) = fast_two_sum(hi, partials[n]);
+        hi = sum;
+        lo = err;
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // Half-way cases: the rest of the partials decide the rounding direction.
+    if n > 0 && ((lo < 0.0 && partials[n - 1] < 0.0) || (lo > 0.0 && partials[n - 1] > 0.0)) {
+        let y = lo * 2.0;
+        let x = hi + y;
+        if y == x - hi {
+            hi = x;
+        }
+    }
+    hi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Summation, two_sum};
+
+    #[test]
+    fn compensated_strategies_recover_what_naive_loses() {
+        // 1 + 1e100 + 1 - 1e100: naive and Kahan lose both ones.
+        let values = [1.0, 1.0e100, 1.0, -1.0e100];
+        assert_eq!(Summation::Naive.sum(values), 0.0);
+        assert_eq!(Summation::Kahan.sum(values), 0.0);
+        assert_eq!(Summation::Neumaier.sum(values), 2.0);
+        assert_eq!(Summation::Exact.sum(values), 2.0);
+
+        // 0.1 ten times: only the exact sum rounds to the double nearest 1.
+        let tenths = [0.1; 10];
+        assert_ne!(Summation::Naive.sum(tenths), 1.0);
+        assert_eq!(Summation::Exact.sum(tenths), 1.0);
+        assert_eq!(Summation::Pairwise.sum(tenths), 1.0);
+
+        let (s, e) = two_sum(1.0, 1.0e-20);
+        assert_eq!((s, e), (1.0, 1.0e-20));
+        assert!(Summation::Exact.sum([f64::INFINITY, 1.0]).is_infinite());
+        assert!(Summation::Exact.sum([f64::MAX, f64::MAX]).is_infinite());
+    }
+
+    #[test]
+    fn partial_values_track_the_running_sum() {
+        let values: Vec<f64> = (1..=37).map(|k| 1.0 / f64::from(k)).collect();
+        for strategy in Summation::ALL {
+            let mut acc = strategy.accumulator();
+            for (i, &x) in values.iter().enumerate() {
+                acc.add(x);
+                let exact = Summation::Exact.sum(values[..=i].iter().copied());
+                assert!(
+                    (acc.value() - exact).abs() <= 4.0 * f64::EPSILON * exact,
+                    "{strategy} after {} terms",
+                    i + 1
+                );
+            }
+        }
+        assert_eq!(
+            Summation::Naive.sum(values.iter().copied()),
+            values.iter().sum::<f64>()
+        );
+    }
+}
@@ -1,3 +1,8 @@
+pub mod compare;
+pub mod npy;
+
+use crate::config::{Export, Paths};
+use crate::error::{Mat627Error, Result};
 use npy_writer::NumpyWriter;
 use std::fs;
 use std::io;
@@ -10,43 +15,301 @@ use std::process::{Command, Stdio};
 /// * `data` - The array of f64s
 /// * `dir` - The directory to write to
 /// * `fname` - the filename
-pub fn write_data(data: &[f64], dir: String, fname: String) {
-    fs::create_dir_all(&dir).unwrap();
+pub fn write_data(data: &[f64], dir: String, fname: String) -> Result<()> {
+    fs::create_dir_all(&dir)?;
 
     let full_path = format!("{}/{}.npy", &dir, &fname);
 
-    let mut f = fs::File::create(&full_path).unwrap();
+    fs::File::create(&full_path)
+        .and_then(|mut f| data.write_npy(&mut f))
+        .map_err(|source| Mat627Error::Npy {
+            path: full_path,
+            source,
+        })
+}
+
+/// One named column of a structured `.npy` record array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    /// Stored as `<i8`, e.g. mesh sizes and step indices.
+    Int(&'static str, Vec<i64>),
+    /// Stored as `<f8`.
+    Float(&'static str, Vec<f64>),
+    /// Stored as `|b1`, e.g. whether an adaptive step was accepted.
+    Bool(&'static str, Vec<bool>),
+    /// Stored as `<f8` with NaN for `None`, e.g. the undefined first rate.
+    /// Exported as an empty CSV cell or JSON `null`.
+    Optional(&'static str, Vec<Option<f64>>),
+}
+
+impl Field {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Int(name, _)
+            | Self::Float(name, _)
+            | Self::Bool(name, _)
+            | Self::Optional(name, _) => name,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Int(_, values) => values.len(),
+            Self::Float(_, values) => values.len(),
+            Self::Bool(_, values) => values.len(),
+            Self::Optional(_, values) => values.len(),
+        }
+    }
+
+    fn dtype(&self) -> &'static str {
+        match self {
+            Self::Int(..) => "<i8",
+            Self::Float(..) | Self::Optional(..) => "<f8",
+            Self::Bool(..) => "|b1",
+        }
+    }
+
+    fn write_le(&self, i: usize, out: &mut Vec<u8>) {
+        match self {
+            Self::Int(_, values) => out.extend_from_slice(&values[i].to_le_bytes()),
+            Self::Float(_, values) => out.extend_from_slice(&values[i].to_le_bytes()),
+            Self::Bool(_, values) => out.push(u8::from(values[i])),
+            Self::Optional(_, values) => {
+                out.extend_from_slice(&values[i].unwrap_or(f64::NAN).to_le_bytes())
+            }
+        }
+    }
+
+    /// Row `i` as plain text, `None` when the value is missing.
+    ///
+    /// Floats use the shortest representation that round-trips, switching to
+    /// exponent notation for very large or small magnitudes.
+    fn text(&self, i: usize) -> Option<String> {
+        match self {
+            Self::Int(_, values) => Some(values[i].to_string()),
+            Self::Float(_, values) => Some(format!("{:?}", values[i])),
+            Self::Bool(_, values) => Some(values[i].to_string()),
+            Self::Optional(_, values) => values[i].map(|x| format!("{x:?}")),
+        }
+    }
+
+    /// Row `i` as a JSON value; JSON has no NaN or infinity, so those are `null` too.
+    fn json(&self, i: usize) -> String {
+        let finite = match self {
+            Self::Int(..) | Self::Bool(..) => true,
+            Self::Float(_, values) => values[i].is_finite(),
+            Self::Optional(_, values) => values[i].is_some_and(f64::is_finite),
+        };
+        match self.text(i) {
+            Some(text) if finite => text,
+            _ => String::from("null"),
+        }
+    }
+}
+
+/// Write a table as one structured `.npy` array with a record per row.
+///
+/// `numpy.load` returns an array whose columns are indexed by field name
+/// (`rows["h"]`); [`read_field`] reads a single column back.
+///
+/// # Arguments
+/// * `fields` - The columns, all of the same length
+/// * `dir` - The directory to write to
+/// * `fname` - the filename
+pub fn write_records(fields: &[Field], dir: String, fname: String) -> Result<()> {
+    let rows = row_count(fields, &fname)?;
+    fs::create_dir_all(&dir)?;
+    let full_path = format!("{}/{}.npy", &dir, &fname);
+
+    let descr = fields
+        .iter()
+        .map(|field| format!("('{}', '{}')", field.name(), field.dtype()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut header =
+        format!("{{'descr': [{descr}], 'fortran_order': False, 'shape': ({rows},), }}");
+    // Magic, version and length take 10 bytes; numpy pads the header with
+    // spaces and a newline so the data starts on a 64-byte boundary.
+    let padded = (10 + header.len() + 1).div_ceil(64) * 64;
+    header.extend(std::iter::repeat_n(' ', padded - 10 - header.len() - 1));
+    header.push('\n');
+
+    let mut bytes = Vec::with_capacity(padded + rows * fields.len() * 8);
+    bytes.extend_from_slice(b"\x93NUMPY\x01\x00");
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for i in 0..rows {
+        for field in fields {
+            field.write_le(i, &mut bytes);
+        }
+    }
+
+    fs::write(&full_path, bytes).map_err(|source| Mat627Error::Npy {
+        path: full_path,
+        source,
+    })
+}
+
+/// Write a result table with [`write_records`], plus a `{fname}.csv` or
+/// `{fname}.jsonl` copy for each format in `export`.
+pub fn write_table(fields: &[Field], dir: String, fname: String, export: &[Export]) -> Result<()> {
+    write_records(fields, dir.clone(), fname.clone())?;
+
+    let rows = row_count(fields, &fname)?;
+    for format in export {
+        let text = match format {
+            Export::Csv => table_csv(fields, rows),
+            Export::Jsonl => table_jsonl(fields, rows),
+        };
+        fs::write(format!("{dir}/{fname}.{}", format.extension()), text)?;
+    }
+    Ok(())
+}
+
+/// The common length of `fields`, or an error naming the first one that differs.
+fn row_count(fields: &[Field], fname: &str) -> Result<usize> {
+    let rows = fields.first().map_or(0, Field::len);
+    match fields.iter().find(|field| field.len() != rows) {
+        Some(field) => Err(Mat627Error::InvalidInput(format!(
+            "{fname}: field '{}' has {} rows, expected {rows}",
+            field.name(),
+            field.len()
+        ))),
+        None => Ok(rows),
+    }
+}
+
+/// A header row of field names, then one line per row; missing values are empty.
+fn table_csv(fields: &[Field], rows: usize) -> String {
+    let mut lines = vec![fields.iter().map(Field::name).collect::<Vec<_>>().join(",")];
+    for i in 0..rows {
+        lines.push(
+            fields
+                .iter()
+                .map(|field| field.text(i).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// One JSON object per row; missing and non-finite values are `null`.
+fn table_jsonl(fields: &[Field], rows: usize) -> String {
+    (0..rows)
+        .map(|i| {
+            let members = fields
+                .iter()
+                .map(|field| format!("\"{}\":{}", field.name(), field.json(i)))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{{{members}}}\n")
+        })
+        .collect()
+}
 
-    data.write_npy(&mut f).unwrap();
+/// Read a 1-D little-endian f64 array written by [`write_data`].
+///
+/// Reads `{dir}/{fname}.npy`; any other dtype or shape is reported as an
+/// [`Mat627Error::Npy`] error. Use [`npy::read`] for the other arrays.
+pub fn read_data(dir: &str, fname: &str) -> Result<Vec<f64>> {
+    let path = format!("{dir}/{fname}.npy");
+    let array = npy::read(&path)?;
+    match array.values() {
+        Some(npy::Values::F64(values)) if array.shape.len() == 1 => Ok(values.clone()),
+        _ => Err(invalid_npy(path, "expected a 1-D '<f8' array")),
+    }
 }
+
+/// Read column `field` of a record array written by [`write_records`].
+///
+/// Integer fields are converted to f64 and booleans to 0 or 1.
+pub fn read_field(dir: &str, fname: &str, field: &str) -> Result<Vec<f64>> {
+    let path = format!("{dir}/{fname}.npy");
+    let array = npy::read(&path)?;
+    match array.field(field) {
+        Some(values) if array.is_record() => Ok(values.to_f64()),
+        _ => Err(invalid_npy(path, &format!("no field '{field}'"))),
+    }
+}
+
+fn invalid_npy(path: String, message: &str) -> Mat627Error {
+    Mat627Error::Npy {
+        path,
+        source: io::Error::new(io::ErrorKind::InvalidData, message),
+    }
+}
+
 pub fn rel_error(approx: f64, exact: f64) -> f64 {
     f64::abs(approx - exact) / f64::abs(exact)
 }
 
 /// Plots data according to the plot.py script.
 /// Assumes plot.py in scripts/{chapter}
-/// Assumes data in data/{chapter}
-/// Places plot  in plots/{chapter}
-pub fn plot(chapter: &str) -> io::Result<()> {
-    run_python_script(&format!("scripts/{}/plot.py", chapter))
+/// Reads data from `paths.data`
+/// Places plots in `paths.plots`
+///
+/// With the `native-plots` feature, the figures are drawn by
+/// [`plotting::render_chapter`](crate::plotting::render_chapter) instead when
+/// no Python with numpy and matplotlib can be found.
+pub fn plot(chapter: &str, paths: &Paths) -> Result<()> {
+    #[cfg(feature = "native-plots")]
+    if !python_plotting_available() {
+        println!("Python plotting unavailable; rendering {chapter} figures natively");
+        return crate::plotting::render_chapter(chapter, paths);
+    }
+    run_python_script(&format!("scripts/{}/plot.py", chapter), paths)
 }
 
-pub fn run_python_script(path: &str) -> io::Result<()> {
+/// Run a chapter script with `paths` passed as `MAT627_DATA_DIR`,
+/// `MAT627_PLOT_DIR` and `MAT627_REPORT_DIR`.
+pub fn run_python_script(path: &str, paths: &Paths) -> Result<()> {
+    let mut cmd = python_command()?;
+    cmd.env("MPLCONFIGDIR", "/tmp/matplotlib")
+        .env("MAT627_DATA_DIR", &paths.data)
+        .env("MAT627_PLOT_DIR", &paths.plots)
+        .env("MAT627_REPORT_DIR", &paths.reports)
+        .arg(path);
+    run_command(&mut cmd)
+}
+
+/// `uv run python` inside a uv project, otherwise `python`/`python3`.
+fn python_command() -> Result<Command> {
     if command_exists("uv") && Path::new("pyproject.toml").exists() {
         let mut cmd = Command::new("uv");
         cmd.env("UV_CACHE_DIR", "/tmp/uv-cache");
-        cmd.env("MPLCONFIGDIR", "/tmp/matplotlib");
-        cmd.arg("run").arg("python").arg(path);
-        run_command(&mut cmd, &format!("python script failed: {path}"))
+        cmd.arg("run").arg("python");
+        Ok(cmd)
     } else {
-        let mut cmd = Command::new(python_executable()?);
-        cmd.env("MPLCONFIGDIR", "/tmp/matplotlib");
-        cmd.arg(path);
-        run_command(&mut cmd, &format!("python script failed: {path}"))
+        Ok(Command::new(python_executable()?))
+    }
+}
+
+/// Whether the plot scripts can run: a Python is found and imports numpy and matplotlib.
+pub fn python_plotting_available() -> bool {
+    python_command().is_ok_and(|mut cmd| {
+        cmd.env("MPLCONFIGDIR", "/tmp/matplotlib")
+            .args(["-c", "import numpy, matplotlib"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Copy `figures` from the plot directory into the report's `figures/` directory.
+pub fn publish_figures(paths: &Paths, figures: &[&str]) -> Result<()> {
+    for name in figures {
+        copy_file(
+            &format!("{}/{name}", paths.plots),
+            &format!("{}/figures/{name}", paths.reports),
+        )?;
     }
+    Ok(())
 }
 
-pub fn copy_file(src: &str, dst: &str) -> io::Result<()> {
+pub fn copy_file(src: &str, dst: &str) -> Result<()> {
     if let Some(parent) = Path::new(dst).parent() {
         fs::create_dir_all(parent)?;
     }
@@ -54,43 +317,74 @@ pub fn copy_file(src: &str, dst: &str) -> io::Result<()> {
     Ok(())
 }
 
-pub fn build_report(report_dir: &str, output_name: &str) -> io::Result<()> {
+/// Build `reports/<chapter>/main.tex` in `paths.reports` and copy the PDF to `output_name`.
+///
+/// When the report directory has been moved, the LaTeX source is copied there first.
+pub fn build_report(chapter: &str, paths: &Paths, output_name: &str) -> Result<()> {
+    let source_dir = format!("reports/{chapter}");
+    let report_dir = paths.reports.as_str();
+    if Path::new(report_dir) != Path::new(&source_dir) {
+        copy_file(
+            &format!("{source_dir}/main.tex"),
+            &format!("{report_dir}/main.tex"),
+        )?;
+    }
+
     let mut cmd = Command::new("latexmk");
     cmd.arg("-pdf")
         .arg("-outdir=build")
         .arg("main.tex")
         .current_dir(report_dir);
 
-    run_command(&mut cmd, &format!("latex build failed: {report_dir}"))?;
+    run_command(&mut cmd)?;
     copy_file(
         &format!("{report_dir}/build/main.pdf"),
         &format!("{report_dir}/{output_name}"),
     )
 }
 
-fn run_command(command: &mut Command, message: &str) -> io::Result<()> {
+/// Run `command` to completion, reporting its command line on failure.
+fn run_command(command: &mut Command) -> Result<()> {
     let status = command
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .status()?;
+        .status()
+        .map_err(|source| Mat627Error::Spawn {
+            command: command_line(command),
+            source,
+        })?;
 
     if status.success() {
         Ok(())
     } else {
-        Err(io::Error::other(format!("{message}: {status}")))
+        Err(Mat627Error::Process {
+            command: command_line(command),
+            status,
+        })
     }
 }
 
-fn python_executable() -> io::Result<&'static str> {
+fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn python_executable() -> Result<&'static str> {
     if command_exists("python") {
         Ok("python")
     } else if command_exists("python3") {
         Ok("python3")
     } else {
-        Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "neither 'python' nor 'python3' is available",
-        ))
+        Err(Mat627Error::Spawn {
+            command: String::from("python"),
+            source: io::Error::new(
+                io::ErrorKind::NotFound,
+                "neither 'python' nor 'python3' is available",
+            ),
+        })
     }
 }
 
@@ -102,3 +396,102 @@ fn command_exists(program: &str) -> bool {
         .status()
         .is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Field, read_data, read_field, write_data, write_records, write_table};
+    use crate::config::Export;
+    use crate::error::Mat627Error;
+    use std::fs;
+
+    #[test]
+    fn write_data_reports_the_npy_path() {
+        let dir = std::env::temp_dir().join(format!("mat627-util-{}", std::process::id()));
+        // A directory where the .npy file should go makes File::create fail.
+        fs::create_dir_all(dir.join("blocked.npy")).unwrap();
+
+        let dir_name = dir.display().to_string();
+        let err = write_data(&[1.0], dir_name.clone(), String::from("blocked")).unwrap_err();
+        match err {
+            Mat627Error::Npy { path, .. } => assert_eq!(path, format!("{dir_name}/blocked.npy")),
+            other => panic!("expected an Npy error, got {other:?}"),
+        }
+
+        write_data(&[1.0, -2.5e-300], dir_name.clone(), String::from("ok")).unwrap();
+        assert_eq!(read_data(&dir_name, "ok").unwrap(), vec![1.0, -2.5e-300]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn record_arrays_keep_integer_columns() {
+        let dir = std::env::temp_dir().join(format!("mat627-records-{}", std::process::id()));
+        let dir_name = dir.display().to_string();
+        let fields = [
+            Field::Int("n", vec![4, 8]),
+            Field::Float("h", vec![0.25, 0.125]),
+            Field::Float("rate", vec![f64::NAN, 2.0]),
+            Field::Bool("ok", vec![false, true]),
+        ];
+        write_records(&fields, dir_name.clone(), String::from("summary")).unwrap();
+
+        let bytes = fs::read(dir.join("summary.npy")).unwrap();
+        let header = String::from_utf8_lossy(&bytes[10..]);
+        assert!(header.starts_with(
+            "{'descr': [('n', '<i8'), ('h', '<f8'), ('rate', '<f8'), ('ok', '|b1')], 'fortran_order': False, 'shape': (2,), }"
+        ));
+        assert_eq!((bytes.len() - 2 * 25) % 64, 0);
+        assert_eq!(
+            i64::from_le_bytes(bytes[bytes.len() - 25..][..8].try_into().unwrap()),
+            8
+        );
+
+        assert_eq!(
+            read_field(&dir_name, "summary", "n").unwrap(),
+            vec![4.0, 8.0]
+        );
+        assert_eq!(
+            read_field(&dir_name, "summary", "h").unwrap(),
+            vec![0.25, 0.125]
+        );
+        assert!(read_field(&dir_name, "summary", "rate").unwrap()[0].is_nan());
+        assert_eq!(
+            read_field(&dir_name, "summary", "ok").unwrap(),
+            vec![0.0, 1.0]
+        );
+        assert!(read_field(&dir_name, "summary", "err").is_err());
+        assert!(read_data(&dir_name, "summary").is_err());
+
+        let ragged = [Field::Int("n", vec![1]), Field::Float("h", vec![])];
+        let err = write_records(&ragged, dir_name, String::from("ragged")).unwrap_err();
+        assert!(matches!(err, Mat627Error::InvalidInput(_)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exports_write_missing_values_as_empty_and_null() {
+        let dir = std::env::temp_dir().join(format!("mat627-export-{}", std::process::id()));
+        let dir_name = dir.display().to_string();
+        let fields = [
+            Field::Int("n", vec![4, 8]),
+            Field::Float("err", vec![1.0e-20, 0.25]),
+            Field::Optional("rate", vec![None, Some(2.0)]),
+        ];
+        write_table(
+            &fields,
+            dir_name.clone(),
+            String::from("summary"),
+            &[Export::Csv, Export::Jsonl],
+        )
+        .unwrap();
+
+        let csv = fs::read_to_string(dir.join("summary.csv")).unwrap();
+        assert_eq!(csv, "n,err,rate\n4,1e-20,\n8,0.25,2.0\n");
+        let jsonl = fs::read_to_string(dir.join("summary.jsonl")).unwrap();
+        assert_eq!(
+            jsonl,
+            "{\"n\":4,\"err\":1e-20,\"rate\":null}\n{\"n\":8,\"err\":0.25,\"rate\":2.0}\n"
+        );
+        assert!(read_field(&dir_name, "summary", "rate").unwrap()[0].is_nan());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+//! Comparing two runs' `.npy` output.
+//!
+//! [`compare_dirs`] pairs the files of an old and a new data directory by
+//! relative path and reports every difference as a [`Drift`]: files that
+//! appeared or disappeared, changed shapes or columns, and floating-point
+//! values outside a [`Tolerance`]. Integer and boolean values must match
+//! exactly.
+
+use super::npy::{self, Array, Values};
+use crate::error::Result;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How far two floats may differ.
+///
+/// A value is accepted when it is within `ulps` units in the last place of the
+/// old one, or when `|old - new| <= abs + rel * max(|old|, |new|)`. NaNs
+/// compare equal to NaNs and infinities to infinities of the same sign. Keys
+/// missing from a TOML table are zero.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tolerance {
+    #[serde(default)]
+    pub rel: f64,
+    #[serde(default)]
+    pub abs: f64,
+    #[serde(default)]
+    pub ulps: u64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            rel: 1.0e-12,
+            abs: 0.0,
+            ulps: 0,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Bit-for-bit equality (up to the sign of zero).
+    pub const EXACT: Self = Self {
+        rel: 0.0,
+        abs: 0.0,
+        ulps: 0,
+    };
+
+    pub fn accepts(self, old: f64, new: f64) -> bool {
+        if old.is_nan() || new.is_nan() {
+            return old.is_nan() && new.is_nan();
+        }
+        if old == new {
+            return true;
+        }
+        ulp_distance(old, new) <= self.ulps
+            || (old - new).abs() <= self.abs + self.rel * old.abs().max(new.abs())
+    }
+}
+
+/// The number of representable doubles between `a` and `b`.
+fn ulp_distance(a: f64, b: f64) -> u64 {
+    // Map the bit patterns onto a line where adjacent doubles differ by one.
+    let ordered = |x: f64| {
+        let bits = x.to_bits() as i64;
+        i128::from(if bits < 0 { i64::MIN - bits } else { bits })
+    };
+    u64::try_from((ordered(a) - ordered(b)).unsigned_abs()).unwrap_or(u64::MAX)
+}
+
+/// One difference between the old and the new run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// Present in the old run only.
+    Missing(PathBuf),
+    /// Present in the new run only.
+    Added(PathBuf),
+    /// The arrays have different shapes.
+    Shape {
+        file: PathBuf,
+        old: Vec<usize>,
+        new: Vec<usize>,
+    },
+    /// The field names or dtypes differ.
+    Layout {
+        file: PathBuf,
+        old: String,
+        new: String,
+    },
+    /// `count` values of `field` (empty for a plain array) are out of
+    /// tolerance; `index` is the worst of them.
+    Values {
+        file: PathBuf,
+        field: String,
+        count: usize,
+        index: usize,
+        old: f64,
+        new: f64,
+    },
+}
+
+impl Drift {
+    fn file(&self) -> &Path {
+        match self {
+            Self::Missing(file) | Self::Added(file) => file,
+            Self::Shape { file, .. } | Self::Layout { file, .. } | Self::Values { file, .. } => {
+                file
+            }
+        }
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(file) => write!(f, "{}: missing from the new run", file.display()),
+            Self::Added(file) => write!(f, "{}: not in the old run", file.display()),
+            Self::Shape { file, old, new } => {
+                write!(f, "{}: shape {old:?} -> {new:?}", file.display())
+            }
+            Self::Layout { file, old, new } => {
+                write!(f, "{}: layout {old} -> {new}", file.display())
+            }
+            Self::Values {
+                file,
+                field,
+                count,
+                index,
+                old,
+                new,
+            } => {
+                let column = if field.is_empty() {
+                    String::new()
+                } else {
+                    format!("['{field}']")
+                };
+                write!(
+                    f,
+                    "{}{column}: {count} value(s) drifted, worst at [{index}]: {old:e} -> {new:e}",
+                    file.display()
+                )
+            }
+        }
+    }
+}
+
+/// Compare every `.npy` file under `old` with its counterpart under `new`.
+///
+/// Other files (CSV exports, figures) are ignored. The result is sorted by
+/// path and empty when the runs agree.
+pub fn compare_dirs(old: &Path, new: &Path, tolerance: Tolerance) -> Result<Vec<Drift>> {
+    compare_dirs_with(old, new, |_| tolerance)
+}
+
+/// [`compare_dirs`] with a tolerance chosen per file, by its path relative to
+/// the two directories.
+pub fn compare_dirs_with(
+    old: &Path,
+    new: &Path,
+    tolerance: impl Fn(&Path) -> Tolerance,
+) -> Result<Vec<Drift>> {
+    let old_files = npy_files(old)?;
+    let new_files = npy_files(new)?;
+
+    let mut drift = Vec::new();
+    for file in &old_files {
+        if new_files.binary_search(file).is_err() {
+            drift.push(Drift::Missing(file.clone()));
+            continue;
+        }
+        let old_array = npy::read(old.join(file))?;
+        let new_array = npy::read(new.join(file))?;
+        drift.extend(compare_arrays(
+            file,
+            &old_array,
+            &new_array,
+            tolerance(file),
+        ));
+    }
+    for file in &new_files {
+        if old_files.binary_search(file).is_err() {
+            drift.push(Drift::Added(file.clone()));
+        }
+    }
+    drift.sort_by(|a, b| a.file().cmp(b.file()));
+    Ok(drift)
+}
+
+/// The differences between two decoded arrays read from `file`.
+pub fn compare_arrays(file: &Path, old: &Array, new: &Array, tolerance: Tolerance) -> Vec<Drift> {
+    if old.shape != new.shape {
+        return vec![Drift::Shape {
+            file: file.to_path_buf(),
+            old: old.shape.clone(),
+            new: new.shape.clone(),
+        }];
+    }
+    if layout(old) != layout(new) {
+        return vec![Drift::Layout {
+            file: file.to_path_buf(),
+            old: layout(old),
+            new: layout(new),
+        }];
+    }
+
+    old.fields
+        .iter()
+        .zip(&new.fields)
+        .filter_map(|((field, old), (_, new))| {
+            let (count, index, old, new) = compare_values(old, new, tolerance)?;
+            Some(Drift::Values {
+                file: file.to_path_buf(),
+                field: field.clone(),
+                count,
+                index,
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+/// `<f8` or `[('n', '<i8'), ('h', '<f8')]`, as in the NPY header.
+fn layout(array: &Array) -> String {
+    match array.values() {
+        Some(values) => values.dtype().to_string(),
+        None => {
+            let fields: Vec<String> = array
+                .fields
+                .iter()
+                .map(|(name, values)| format!("('{name}', '{}')", values.dtype()))
+                .collect();
+            format!("[{}]", fields.join(", "))
+        }
+    }
+}
+
+/// The number of mismatches and the worst one as `(count, index, old, new)`,
+/// or `None` when every value is accepted. Same-dtype inputs are assumed.
+fn compare_values(
+    old: &Values,
+    new: &Values,
+    tolerance: Tolerance,
+) -> Option<(usize, usize, f64, f64)> {
+    let tolerance = if matches!(old, Values::F64(_)) {
+        tolerance
+    } else {
+        Tolerance::EXACT
+    };
+    let (old, new) = (old.to_f64(), new.to_f64());
+
+    let mismatches: Vec<usize> = (0..old.len())
+        .filter(|&i| !tolerance.accepts(old[i], new[i]))
+        .collect();
+    let severity = |i: usize| {
+        let diff = (old[i] - new[i]).abs();
+        if diff.is_nan() { f64::INFINITY } else { diff }
+    };
+    let worst = mismatches
+        .iter()
+        .copied()
+        .max_by(|&a, &b| severity(a).total_cmp(&severity(b)))?;
+    Some((mismatches.len(), worst, old[worst], new[worst]))
+}
+
+/// The `.npy` files below `root`, relative to it and sorted.
+fn npy_files(root: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(root, &path, files)?;
+            } else if path.extension().is_some_and(|ext| ext == "npy") {
+                let relative = path.strip_prefix(root).expect("walked below root");
+                files.push(relative.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Drift, Tolerance, compare_dirs};
+    use crate::util::{Field, write_data, write_records};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn tolerance_treats_nan_as_equal_to_nan_only() {
+        let tol = Tolerance::default();
+        assert!(tol.accepts(f64::NAN, f64::NAN));
+        assert!(!tol.accepts(f64::NAN, 1.0));
+        assert!(tol.accepts(f64::INFINITY, f64::INFINITY));
+        assert!(tol.accepts(1.0, 1.0 + 1.0e-13));
+        assert!(!tol.accepts(1.0, 1.0 + 1.0e-10));
+        let abs = Tolerance {
+            abs: 1.0e-8,
+            ..Tolerance::EXACT
+        };
+        assert!(abs.accepts(0.0, 1.0e-9));
+
+        let ulps = Tolerance {
+            ulps: 2,
+            ..Tolerance::EXACT
+        };
+        let next = |x: f64| f64::from_bits(x.to_bits() + 1);
+        assert!(ulps.accepts(1.0, next(next(1.0))));
+        assert!(!ulps.accepts(1.0, next(next(next(1.0)))));
+        assert!(ulps.accepts(-0.0, f64::from_bits(1)));
+        assert!(!ulps.accepts(-1.0, 1.0));
+    }
+
+    #[test]
+    fn directories_report_added_missing_and_drifted_files() {
+        let root = std::env::temp_dir().join(format!("mat627-compare-{}", std::process::id()));
+        let (old, new) = (root.join("old"), root.join("new"));
+        let dir = |path: &PathBuf| path.display().to_string();
+
+        for (path, err) in [(&old, 1.0e-3), (&new, 1.1e-3)] {
+            let fields = [
+                Field::Int("n", vec![4, 8]),
+                Field::Float("err", vec![4.0e-3, err]),
+            ];
+            write_records(&fields, dir(path), String::from("summary")).unwrap();
+            write_data(&[0.5], dir(&path.join("nested")), String::from("x")).unwrap();
+        }
+        write_data(&[1.0], dir(&old), String::from("gone")).unwrap();
+        write_data(&[1.0], dir(&new), String::from("new")).unwrap();
+        fs::write(new.join("summary.csv"), "ignored").unwrap();
+
+        let drift = compare_dirs(&old, &new, Tolerance::default()).unwrap();
+        assert_eq!(
+            drift,
+            vec![
+                Drift::Missing(PathBuf::from("gone.npy")),
+                Drift::Added(PathBuf::from("new.npy")),
+                Drift::Values {
+                    file: PathBuf::from("summary.npy"),
+                    field: String::from("err"),
+                    count: 1,
+                    index: 1,
+                    old: 1.0e-3,
+                    new: 1.1e-3,
+                },
+            ]
+        );
+        assert!(
+            drift[2]
+                .to_string()
+                .starts_with("summary.npy['err']: 1 value(s)")
+        );
+
+        let loose = Tolerance {
+            rel: 0.2,
+            ..Tolerance::EXACT
+        };
+        assert_eq!(compare_dirs(&old, &new, loose).unwrap().len(), 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
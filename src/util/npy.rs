@@ -0,0 +1,283 @@
+//! Reading back the `.npy` files the drivers write.
+//!
+//! Covers the subset of the format we emit: C-ordered, little-endian `<f8`,
+//! `<i8` and `|b1` arrays of one or two dimensions, and 1-D record arrays
+//! with fields of those dtypes (see [`super::write_records`]).
+
+use crate::error::{Mat627Error, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The elements of a plain array or of one record field, in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    F64(Vec<f64>),
+    I64(Vec<i64>),
+    Bool(Vec<bool>),
+}
+
+impl Values {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::F64(values) => values.len(),
+            Self::I64(values) => values.len(),
+            Self::Bool(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The numpy dtype string, e.g. `<f8`.
+    pub fn dtype(&self) -> &'static str {
+        match self {
+            Self::F64(_) => "<f8",
+            Self::I64(_) => "<i8",
+            Self::Bool(_) => "|b1",
+        }
+    }
+
+    /// The values as f64; integers are cast and booleans become 0 or 1.
+    pub fn to_f64(&self) -> Vec<f64> {
+        match self {
+            Self::F64(values) => values.clone(),
+            Self::I64(values) => values.iter().map(|&x| x as f64).collect(),
+            Self::Bool(values) => values.iter().map(|&x| f64::from(u8::from(x))).collect(),
+        }
+    }
+}
+
+/// A decoded `.npy` file.
+///
+/// A plain array has a single field with an empty name; a record array has
+/// one field per column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array {
+    pub shape: Vec<usize>,
+    pub fields: Vec<(String, Values)>,
+}
+
+impl Array {
+    pub fn is_record(&self) -> bool {
+        !matches!(self.fields.as_slice(), [(name, _)] if name.is_empty())
+    }
+
+    /// The elements of a plain array, `None` for a record array.
+    pub fn values(&self) -> Option<&Values> {
+        match self.fields.as_slice() {
+            [(name, values)] if name.is_empty() => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Column `name` of a record array.
+    pub fn field(&self, name: &str) -> Option<&Values> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, values)| values)
+    }
+}
+
+/// Read and decode the `.npy` file at `path`.
+///
+/// Decoding failures are reported as [`Mat627Error::Npy`] with the path.
+pub fn read(path: impl AsRef<Path>) -> Result<Array> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    parse(&bytes).map_err(|message| Mat627Error::Npy {
+        path: path.display().to_string(),
+        source: io::Error::new(io::ErrorKind::InvalidData, message),
+    })
+}
+
+/// Decode the bytes of a `.npy` file.
+pub fn parse(bytes: &[u8]) -> std::result::Result<Array, String> {
+    let (header, data) = split(bytes)?;
+    if header_value(header, "fortran_order") != Some("False") {
+        return Err(format!(
+            "expected a C-ordered array, found {}",
+            header.trim()
+        ));
+    }
+    let shape = header_value(header, "shape")
+        .and_then(parse_shape)
+        .ok_or_else(|| format!("unreadable shape in {}", header.trim()))?;
+    if shape.is_empty() || shape.len() > 2 {
+        return Err(format!(
+            "expected a 1-D or 2-D array, found shape {shape:?}"
+        ));
+    }
+    let descr =
+        header_value(header, "descr").ok_or_else(|| format!("no descr in {}", header.trim()))?;
+
+    let dtypes = if descr.starts_with('[') {
+        if shape.len() != 1 {
+            return Err(format!(
+                "expected a 1-D record array, found shape {shape:?}"
+            ));
+        }
+        record_fields(descr).ok_or_else(|| format!("unreadable descr {descr}"))?
+    } else {
+        vec![(String::new(), descr.trim_matches('\'').to_string())]
+    };
+    let widths = dtypes
+        .iter()
+        .map(|(name, dtype)| match dtype.as_str() {
+            "<f8" | "<i8" => Ok(8),
+            "|b1" => Ok(1),
+            _ if name.is_empty() => Err(format!("unsupported dtype '{dtype}'")),
+            _ => Err(format!("field '{name}' has unsupported dtype '{dtype}'")),
+        })
+        .collect::<std::result::Result<Vec<usize>, String>>()?;
+
+    let count: usize = shape.iter().product();
+    let record: usize = widths.iter().sum();
+    if data.len() != count * record {
+        return Err(format!(
+            "expected {} data bytes for shape {shape:?}, found {}",
+            count * record,
+            data.len()
+        ));
+    }
+
+    let mut offset = 0;
+    let fields = dtypes
+        .into_iter()
+        .zip(widths)
+        .map(|((name, dtype), width)| {
+            let start = offset;
+            offset += width;
+            let cells = (0..count).map(|i| &data[i * record + start..][..width]);
+            let values = match dtype.as_str() {
+                "<f8" => Values::F64(cells.map(|c| f64::from_le_bytes(bytes8(c))).collect()),
+                "<i8" => Values::I64(cells.map(|c| i64::from_le_bytes(bytes8(c))).collect()),
+                _ => Values::Bool(cells.map(|c| c[0] != 0).collect()),
+            };
+            (name, values)
+        })
+        .collect();
+
+    Ok(Array { shape, fields })
+}
+
+fn bytes8(cell: &[u8]) -> [u8; 8] {
+    cell.try_into().expect("cell of 8 bytes")
+}
+
+/// Split an NPY file into its header dictionary and data bytes.
+fn split(bytes: &[u8]) -> std::result::Result<(&str, &[u8]), String> {
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" {
+        return Err(String::from("missing NPY magic string"));
+    }
+    let (header_len, start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        version => return Err(format!("unsupported NPY version {version}")),
+    };
+    let header = bytes
+        .get(start..start + header_len)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or("truncated NPY header")?;
+    Ok((header, &bytes[start + header_len..]))
+}
+
+/// The raw text of `key`'s value in the header dictionary: a quoted string,
+/// a `[...]` list, a `(...)` tuple or a bare word.
+fn header_value<'h>(header: &'h str, key: &str) -> Option<&'h str> {
+    let pattern = format!("'{key}':");
+    let start = header.find(&pattern)? + pattern.len();
+    let rest = header[start..].trim_start();
+    let end = match rest.chars().next()? {
+        '\'' => rest[1..].find('\'')? + 2,
+        '[' => rest.find(']')? + 1,
+        '(' => rest.find(')')? + 1,
+        _ => rest.find([',', '}'])?,
+    };
+    Some(rest[..end].trim())
+}
+
+/// `(3,)` or `(2, 5)`; `()` (a scalar) parses as an empty shape.
+fn parse_shape(text: &str) -> Option<Vec<usize>> {
+    text.strip_prefix('(')?
+        .strip_suffix(')')?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse().ok())
+        .collect()
+}
+
+/// The `(name, dtype)` pairs of a structured `descr`, e.g. `[('n', '<i8'), ('h', '<f8')]`.
+fn record_fields(descr: &str) -> Option<Vec<(String, String)>> {
+    descr
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .split(')')
+        .map(|entry| entry.trim_start_matches([',', ' ', '(']))
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, dtype) = entry.split_once(',')?;
+            Some((
+                name.trim().trim_matches('\'').to_string(),
+                dtype.trim().trim_matches('\'').to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Values, parse};
+
+    fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn reads_two_dimensional_integer_and_bool_arrays() {
+        let data: Vec<u8> = [1_i64, -2, 3, 4, 5, 6]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let ints = parse(&npy(
+            "{'descr': '<i8', 'fortran_order': False, 'shape': (2, 3), }\n",
+            &data,
+        ))
+        .unwrap();
+        assert_eq!(ints.shape, vec![2, 3]);
+        assert!(!ints.is_record());
+        assert_eq!(ints.values(), Some(&Values::I64(vec![1, -2, 3, 4, 5, 6])));
+
+        let flags = parse(&npy(
+            "{'descr': '|b1', 'fortran_order': False, 'shape': (3,), }\n",
+            &[1, 0, 1],
+        ))
+        .unwrap();
+        assert_eq!(flags.values(), Some(&Values::Bool(vec![true, false, true])));
+        assert_eq!(flags.values().unwrap().to_f64(), vec![1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_decode() {
+        let header = |descr: &str, order: &str, shape: &str| {
+            format!("{{'descr': {descr}, 'fortran_order': {order}, 'shape': {shape}, }}\n")
+        };
+        let eight = [0_u8; 8];
+        assert!(parse(&npy(&header("'<f8'", "True", "(1,)"), &eight)).is_err());
+        assert!(parse(&npy(&header("'<f4'", "False", "(2,)"), &eight)).is_err());
+        assert!(parse(&npy(&header("'<f8'", "False", "(2,)"), &eight)).is_err());
+        assert!(parse(&npy(&header("'<f8'", "False", "(1, 1, 1)"), &eight)).is_err());
+        assert!(parse(&npy(&header("[('x', '<f8')]", "False", "(1, 1)"), &eight)).is_err());
+        assert!(parse(b"not an npy file").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Golden-file regression test over every chapter's data.
+//!
+//! Runs `run all --data-only` into a scratch directory and compares each
+//! `.npy` file with its copy under `tests/golden/<chapter>/`, using the
+//! tolerances in `tests/golden/tolerances.toml`.
+//!
+//! ```text
+//! cargo test --test golden                # compare
+//! cargo test --test golden -- --bless     # accept the current output
+//! ```
+
+use mat627::util::compare::{self, Tolerance};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+
+/// `tests/golden/tolerances.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Tolerances {
+    /// Applies to every array without an entry in `arrays`.
+    default: Tolerance,
+    /// Keyed by a file or directory below `tests/golden`; the longest
+    /// matching key wins.
+    arrays: BTreeMap<String, Tolerance>,
+}
+
+impl Tolerances {
+    fn for_file(&self, file: &Path) -> Tolerance {
+        self.arrays
+            .iter()
+            .filter(|(key, _)| file.starts_with(key))
+            .max_by_key(|(key, _)| key.len())
+            .map_or(self.default, |(_, tolerance)| *tolerance)
+    }
+}
+
+fn main() -> ExitCode {
+    let bless = std::env::args().any(|arg| arg == "--bless");
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+
+    let scratch = std::env::temp_dir().join(format!("mat627-golden-{}", std::process::id()));
+    let result = generate(&scratch).and_then(|data| {
+        if bless {
+            bless_dir(&data, &golden)
+        } else {
+            check(&data, &golden)
+        }
+    });
+    let _ = fs::remove_dir_all(&scratch);
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run every section with plotting and the report disabled; returns the data
+/// directory. The binary runs inside `scratch` so local `config/*.toml`
+/// overrides do not leak into the comparison.
+fn generate(scratch: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(scratch).map_err(io_error(scratch))?;
+    let status = Command::new(env!("CARGO_BIN_EXE_mat627_assignments"))
+        .args(["run", "all", "--data-only", "--out-dir"])
+        .arg(scratch)
+        .current_dir(scratch)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| format!("could not run the chapter drivers: {e}"))?;
+    if !status.success() {
+        return Err(format!("`run all --data-only` failed ({status})"));
+    }
+    Ok(scratch.join("data"))
+}
+
+fn check(data: &Path, golden: &Path) -> Result<(), String> {
+    let text = fs::read_to_string(golden.join("tolerances.toml")).unwrap_or_default();
+    let tolerances: Tolerances =
+        toml::from_str(&text).map_err(|e| format!("tests/golden/tolerances.toml: {e}"))?;
+
+    let drift = compare::compare_dirs_with(golden, data, |file| tolerances.for_file(file))
+        .map_err(|e| e.to_string())?;
+    if drift.is_empty() {
+        println!("golden: every array matches tests/golden");
+        return Ok(());
+    }
+    for d in &drift {
+        eprintln!("  {d}");
+    }
+    Err(format!(
+        "golden: {} difference(s) from tests/golden; if they are intended, \
+         rerun with `cargo test --test golden -- --bless` and review the diff",
+        drift.len()
+    ))
+}
+
+/// Replace every chapter directory under `golden` with the fresh output,
+/// keeping `tolerances.toml`.
+fn bless_dir(data: &Path, golden: &Path) -> Result<(), String> {
+    if golden.exists() {
+        for entry in fs::read_dir(golden).map_err(io_error(golden))? {
+            let path = entry.map_err(io_error(golden))?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path).map_err(io_error(&path))?;
+            }
+        }
+    }
+
+    let mut count = 0;
+    let mut pending = vec![data.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).map_err(io_error(&dir))? {
+            let path = entry.map_err(io_error(&dir))?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "npy") {
+                let target = golden.join(path.strip_prefix(data).expect("walked below data"));
+                let parent = target.parent().expect("target has a parent");
+                fs::create_dir_all(parent).map_err(io_error(parent))?;
+                fs::copy(&path, &target).map_err(io_error(&target))?;
+                count += 1;
+            }
+        }
+    }
+    println!("golden: blessed {count} arrays into tests/golden");
+    Ok(())
+}
+
+fn io_error(path: &Path) -> impl Fn(std::io::Error) -> String + '_ {
+    move |e| format!("{}: {e}", path.display())
+}